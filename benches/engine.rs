@@ -0,0 +1,79 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use russsty::{Engine, Mesh};
+
+/// Counts heap allocations so the benchmark can report them per `update`.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A sphere with roughly `2 * rings * sectors` faces in front of the camera.
+fn engine_with_sphere(rings: u32, sectors: u32) -> Engine {
+    let mut engine = Engine::builder().with_size(800, 600).build();
+    *engine.mesh_mut() = Mesh::sphere(1.5, rings, sectors);
+    engine
+}
+
+/// Heap allocations and bytes of one `update` after the first has sized its buffers.
+fn steady_state_allocations(engine: &mut Engine) -> (usize, usize) {
+    engine.update(0.016);
+    let (count, bytes) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    );
+    engine.update(0.016);
+    (
+        ALLOCATIONS.load(Ordering::Relaxed) - count,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes,
+    )
+}
+
+fn benchmark_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+
+    for (rings, sectors) in [(16, 32), (64, 128), (256, 512)] {
+        let mut engine = engine_with_sphere(rings, sectors);
+
+        // Meshes are borrowed and last frame's triangle list is reused, so this
+        // stays flat as the mesh grows
+        let (count, bytes) = steady_state_allocations(&mut engine);
+        let faces = engine.last_frame_stats().triangles_submitted;
+        assert_eq!(
+            count, 0,
+            "update/{faces}: {count} allocations ({bytes} bytes) per frame"
+        );
+
+        group.bench_with_input(BenchmarkId::from_parameter(faces), &faces, |b, _| {
+            b.iter(|| engine.update(0.016));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_update);
+criterion_main!(benches);
@@ -1,8 +1,13 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use russsty::bench::{
-    EdgeFunctionRasterizer, FrameBuffer, Rasterizer, ScanlineRasterizer, Triangle,
+    EdgeFunctionRasterizer, EdgeFunctionSimdRasterizer, FrameBuffer, Rasterizer,
+    ScanlineRasterizer, Triangle,
 };
+use russsty::engine::TextureMode;
+use russsty::math::vec2::Vec2;
 use russsty::math::vec3::Vec3;
+use russsty::texture::Texture;
+use russsty::ShadingMode;
 
 const BUFFER_WIDTH: u32 = 800;
 const BUFFER_HEIGHT: u32 = 600;
@@ -11,40 +16,45 @@ fn create_buffer() -> Vec<u32> {
     vec![0u32; (BUFFER_WIDTH * BUFFER_HEIGHT) as usize]
 }
 
-fn small_triangle() -> Triangle {
+fn create_depth_buffer() -> Vec<f32> {
+    vec![0.0f32; (BUFFER_WIDTH * BUFFER_HEIGHT) as usize]
+}
+
+/// Build a flat-shaded triangle. Vertex z stores clip-space W, so it must be non-zero.
+fn flat_triangle(points: [Vec3; 3]) -> Triangle {
     Triangle::new(
-        [
-            Vec3::new(100.0, 100.0, 0.0),
-            Vec3::new(120.0, 100.0, 0.0),
-            Vec3::new(110.0, 120.0, 0.0),
-        ],
+        points,
         0xFFFF0000,
-        0.0,
+        [0xFFFF0000; 3],
+        [Vec2::ZERO; 3],
+        1.0,
+        ShadingMode::Flat,
+        TextureMode::None,
     )
 }
 
+fn small_triangle() -> Triangle {
+    flat_triangle([
+        Vec3::new(100.0, 100.0, 1.0),
+        Vec3::new(120.0, 100.0, 1.0),
+        Vec3::new(110.0, 120.0, 1.0),
+    ])
+}
+
 fn medium_triangle() -> Triangle {
-    Triangle::new(
-        [
-            Vec3::new(100.0, 100.0, 0.0),
-            Vec3::new(300.0, 100.0, 0.0),
-            Vec3::new(200.0, 300.0, 0.0),
-        ],
-        0xFFFF0000,
-        0.0,
-    )
+    flat_triangle([
+        Vec3::new(100.0, 100.0, 1.0),
+        Vec3::new(300.0, 100.0, 1.0),
+        Vec3::new(200.0, 300.0, 1.0),
+    ])
 }
 
 fn large_triangle() -> Triangle {
-    Triangle::new(
-        [
-            Vec3::new(50.0, 50.0, 0.0),
-            Vec3::new(750.0, 100.0, 0.0),
-            Vec3::new(400.0, 550.0, 0.0),
-        ],
-        0xFFFF0000,
-        0.0,
-    )
+    flat_triangle([
+        Vec3::new(50.0, 50.0, 1.0),
+        Vec3::new(750.0, 100.0, 1.0),
+        Vec3::new(400.0, 550.0, 1.0),
+    ])
 }
 
 fn benchmark_single_triangle(c: &mut Criterion) {
@@ -60,9 +70,11 @@ fn benchmark_single_triangle(c: &mut Criterion) {
     ] {
         group.bench_with_input(BenchmarkId::new("scanline", name), &triangle, |b, tri| {
             let mut buffer = create_buffer();
+            let mut depth = create_depth_buffer();
             b.iter(|| {
-                let mut fb = FrameBuffer::new(&mut buffer, BUFFER_WIDTH, BUFFER_HEIGHT);
-                scanline.fill_triangle(black_box(tri), &mut fb, tri.color);
+                depth.fill(0.0);
+                let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                scanline.fill_triangle(black_box(tri), &mut fb, tri.color, None);
             });
         });
 
@@ -71,9 +83,12 @@ fn benchmark_single_triangle(c: &mut Criterion) {
             &triangle,
             |b, tri| {
                 let mut buffer = create_buffer();
+                let mut depth = create_depth_buffer();
                 b.iter(|| {
-                    let mut fb = FrameBuffer::new(&mut buffer, BUFFER_WIDTH, BUFFER_HEIGHT);
-                    edge_fn.fill_triangle(black_box(tri), &mut fb, tri.color);
+                    depth.fill(0.0);
+                    let mut fb =
+                        FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                    edge_fn.fill_triangle(black_box(tri), &mut fb, tri.color, None);
                 });
             },
         );
@@ -94,35 +109,35 @@ fn benchmark_many_triangles(c: &mut Criterion) {
             (0..20).map(move |col| {
                 let x = col as f32 * 40.0;
                 let y = row as f32 * 30.0;
-                Triangle::new(
-                    [
-                        Vec3::new(x, y, 0.0),
-                        Vec3::new(x + 35.0, y, 0.0),
-                        Vec3::new(x + 17.5, y + 25.0, 0.0),
-                    ],
-                    0xFFFF0000,
-                    0.0,
-                )
+                flat_triangle([
+                    Vec3::new(x, y, 1.0),
+                    Vec3::new(x + 35.0, y, 1.0),
+                    Vec3::new(x + 17.5, y + 25.0, 1.0),
+                ])
             })
         })
         .collect();
 
     group.bench_function("scanline_400_triangles", |b| {
         let mut buffer = create_buffer();
+        let mut depth = create_depth_buffer();
         b.iter(|| {
-            let mut fb = FrameBuffer::new(&mut buffer, BUFFER_WIDTH, BUFFER_HEIGHT);
+            depth.fill(0.0);
+            let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
             for tri in &triangles {
-                scanline.fill_triangle(black_box(tri), &mut fb, tri.color);
+                scanline.fill_triangle(black_box(tri), &mut fb, tri.color, None);
             }
         });
     });
 
     group.bench_function("edge_function_400_triangles", |b| {
         let mut buffer = create_buffer();
+        let mut depth = create_depth_buffer();
         b.iter(|| {
-            let mut fb = FrameBuffer::new(&mut buffer, BUFFER_WIDTH, BUFFER_HEIGHT);
+            depth.fill(0.0);
+            let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
             for tri in &triangles {
-                edge_fn.fill_triangle(black_box(tri), &mut fb, tri.color);
+                edge_fn.fill_triangle(black_box(tri), &mut fb, tri.color, None);
             }
         });
     });
@@ -130,5 +145,192 @@ fn benchmark_many_triangles(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, benchmark_single_triangle, benchmark_many_triangles);
+/// Edge function with per-vertex interpolation, where every covered pixel pays for
+/// barycentrics as well as the inside test.
+fn benchmark_edge_stepping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("edge_stepping");
+
+    let edge_fn = EdgeFunctionRasterizer::new();
+
+    for (name, mut triangle) in [("medium", medium_triangle()), ("large", large_triangle())] {
+        triangle.vertex_colors = [0xFFFF0000, 0xFF00FF00, 0xFF0000FF];
+        triangle.shading_mode = ShadingMode::Gouraud;
+        group.bench_with_input(BenchmarkId::new("gouraud", name), &triangle, |b, tri| {
+            let mut buffer = create_buffer();
+            let mut depth = create_depth_buffer();
+            b.iter(|| {
+                depth.fill(0.0);
+                let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                edge_fn.fill_triangle(black_box(tri), &mut fb, tri.color, None);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Gouraud and perspective-correct textured fills on every rasterizer, where the
+/// per-pixel cost is interpolation and sampling rather than coverage. Depth is
+/// cleared every iteration, otherwise early-z would reject every fragment after
+/// the first.
+fn benchmark_shaded_fills(c: &mut Criterion) {
+    let mut group = c.benchmark_group("shaded_fills");
+
+    let scanline = ScanlineRasterizer::new();
+    let edge_fn = EdgeFunctionRasterizer::new();
+    let simd = EdgeFunctionSimdRasterizer::new();
+    let texture = Texture::checkerboard(256, 0xFFFFFFFF, 0xFF202020, 8);
+
+    let mut gouraud = large_triangle();
+    gouraud.vertex_colors = [0xFFFF0000, 0xFF00FF00, 0xFF0000FF];
+    gouraud.shading_mode = ShadingMode::Gouraud;
+    // Receding vertices (differing W) so the 1/w interpolation does real work
+    let mut textured = large_triangle();
+    textured.points = [
+        Vec3::new(50.0, 50.0, 1.0),
+        Vec3::new(750.0, 100.0, 4.0),
+        Vec3::new(400.0, 550.0, 2.0),
+    ];
+    textured.texture_coords = [
+        Vec2::new(0.0, 0.0),
+        Vec2::new(1.0, 0.0),
+        Vec2::new(0.5, 1.0),
+    ];
+    textured.texture_mode = TextureMode::Replace;
+    let mut textured_gouraud = textured;
+    textured_gouraud.vertex_colors = gouraud.vertex_colors;
+    textured_gouraud.shading_mode = ShadingMode::Gouraud;
+    textured_gouraud.texture_mode = TextureMode::Modulate;
+
+    for (name, triangle) in [
+        ("gouraud", gouraud),
+        ("textured", textured),
+        ("textured_modulate", textured_gouraud),
+    ] {
+        group.bench_with_input(BenchmarkId::new("scanline", name), &triangle, |b, tri| {
+            let mut buffer = create_buffer();
+            let mut depth = create_depth_buffer();
+            b.iter(|| {
+                depth.fill(0.0);
+                let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                scanline.fill_triangle(black_box(tri), &mut fb, tri.color, Some(&texture));
+            });
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("edge_function", name),
+            &triangle,
+            |b, tri| {
+                let mut buffer = create_buffer();
+                let mut depth = create_depth_buffer();
+                b.iter(|| {
+                    depth.fill(0.0);
+                    let mut fb =
+                        FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                    edge_fn.fill_triangle(black_box(tri), &mut fb, tri.color, Some(&texture));
+                });
+            },
+        );
+
+        group.bench_with_input(BenchmarkId::new("simd", name), &triangle, |b, tri| {
+            let mut buffer = create_buffer();
+            let mut depth = create_depth_buffer();
+            b.iter(|| {
+                depth.fill(0.0);
+                let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                simd.fill_triangle(black_box(tri), &mut fb, tri.color, Some(&texture));
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// 2x2 quad SIMD edge function vs the scalar edge function.
+fn benchmark_simd(c: &mut Criterion) {
+    let mut group = c.benchmark_group("edge_function_simd");
+
+    let edge_fn = EdgeFunctionRasterizer::new();
+    let simd = EdgeFunctionSimdRasterizer::new();
+
+    for (name, triangle) in [
+        ("small", small_triangle()),
+        ("medium", medium_triangle()),
+        ("large", large_triangle()),
+    ] {
+        group.bench_with_input(BenchmarkId::new("scalar", name), &triangle, |b, tri| {
+            let mut buffer = create_buffer();
+            let mut depth = create_depth_buffer();
+            b.iter(|| {
+                depth.fill(0.0);
+                let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                edge_fn.fill_triangle(black_box(tri), &mut fb, tri.color, None);
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("simd", name), &triangle, |b, tri| {
+            let mut buffer = create_buffer();
+            let mut depth = create_depth_buffer();
+            b.iter(|| {
+                depth.fill(0.0);
+                let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+                simd.fill_triangle(black_box(tri), &mut fb, tri.color, None);
+            });
+        });
+    }
+
+    group.finish();
+}
+
+/// Tiled-parallel vs serial edge function on the large triangle (`--features parallel`).
+#[cfg(feature = "parallel")]
+fn benchmark_tiled(c: &mut Criterion) {
+    use russsty::bench::TiledRasterizer;
+
+    let mut group = c.benchmark_group("tiled_parallel");
+
+    let edge_fn = EdgeFunctionRasterizer::new();
+    let tiled = TiledRasterizer::new();
+    let triangles = [large_triangle()];
+
+    group.bench_function("edge_function_large", |b| {
+        let mut buffer = create_buffer();
+        let mut depth = create_depth_buffer();
+        b.iter(|| {
+            depth.fill(0.0);
+            let mut fb = FrameBuffer::new(&mut buffer, &mut depth, BUFFER_WIDTH, BUFFER_HEIGHT);
+            edge_fn.fill_triangle(black_box(&triangles[0]), &mut fb, triangles[0].color, None);
+        });
+    });
+
+    group.bench_function("tiled_large", |b| {
+        let mut buffer = create_buffer();
+        let mut depth = create_depth_buffer();
+        b.iter(|| {
+            depth.fill(0.0);
+            tiled.fill_triangles(
+                black_box(&triangles),
+                &mut buffer,
+                &mut depth,
+                BUFFER_WIDTH,
+                None,
+            );
+        });
+    });
+
+    group.finish();
+}
+
+#[cfg(not(feature = "parallel"))]
+fn benchmark_tiled(_c: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    benchmark_single_triangle,
+    benchmark_many_triangles,
+    benchmark_edge_stepping,
+    benchmark_shaded_fills,
+    benchmark_simd,
+    benchmark_tiled
+);
 criterion_main!(benches);
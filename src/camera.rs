@@ -387,6 +387,22 @@ mod tests {
         assert_relative_eq!(camera.position().z, 5.0, epsilon = 1e-5);
     }
 
+    #[test]
+    fn move_after_yaw_follows_orientation() {
+        let mut camera = FpsCamera::new(Vec3::ZERO);
+        camera.rotate_yaw(std::f32::consts::FRAC_PI_2);
+
+        // Facing +X, forward movement changes x rather than z
+        camera.move_forward(3.0);
+        assert_relative_eq!(camera.position().x, 3.0, epsilon = 1e-5);
+        assert_relative_eq!(camera.position().z, 0.0, epsilon = 1e-5);
+
+        // Strafing right now moves along -Z
+        camera.move_right(2.0);
+        assert_relative_eq!(camera.position().x, 3.0, epsilon = 1e-5);
+        assert_relative_eq!(camera.position().z, -2.0, epsilon = 1e-5);
+    }
+
     #[test]
     fn view_matrix_is_valid() {
         let camera = FpsCamera::looking_at(Vec3::new(0.0, 0.0, -5.0), Vec3::ZERO);
@@ -397,6 +413,53 @@ mod tests {
         assert_relative_eq!(origin.z, 5.0, epsilon = 1e-4);
     }
 
+    #[test]
+    fn identity_orientation_only_translates() {
+        let camera = FpsCamera::new(Vec3::new(1.0, 2.0, -5.0));
+        let p = camera.view_matrix() * Vec3::new(3.0, 4.0, 5.0);
+
+        // Looking down +Z with no rotation, the view transform is a pure translation.
+        assert_relative_eq!(p.x, 2.0, epsilon = 1e-5);
+        assert_relative_eq!(p.y, 2.0, epsilon = 1e-5);
+        assert_relative_eq!(p.z, 10.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn yaw_90_swaps_x_and_z_in_view_space() {
+        let mut camera = FpsCamera::new(Vec3::ZERO);
+        camera.rotate_yaw(std::f32::consts::FRAC_PI_2);
+        let view = camera.view_matrix();
+
+        // A point on +X is now straight ahead...
+        let ahead = view * Vec3::new(1.0, 0.0, 0.0);
+        assert_relative_eq!(ahead.x, 0.0, epsilon = 1e-5);
+        assert_relative_eq!(ahead.z, 1.0, epsilon = 1e-5);
+
+        // ...and a point on +Z is now to the left.
+        let left = view * Vec3::new(0.0, 0.0, 1.0);
+        assert_relative_eq!(left.x, -1.0, epsilon = 1e-5);
+        assert_relative_eq!(left.z, 0.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn view_matrix_matches_look_at() {
+        let mut camera = FpsCamera::new(Vec3::new(1.0, -2.0, -4.0));
+        camera.rotate(0.7, -0.3);
+
+        // look_at_lh expects the +Y axis, which is "down" in this Y-down system.
+        let expected = Mat4::look_at_lh(
+            camera.position(),
+            camera.position() + camera.forward(),
+            -camera.up(),
+        );
+        let view = camera.view_matrix();
+        for row in 0..4 {
+            for col in 0..4 {
+                assert_relative_eq!(view.get(row, col), expected.get(row, col), epsilon = 1e-5);
+            }
+        }
+    }
+
     #[test]
     fn roll_works_via_matrix() {
         let mut camera = FpsCamera::new(Vec3::ZERO);
@@ -1,4 +1,4 @@
-//! Color constants used throughout the renderer.
+//! Color constants and conversions used throughout the renderer.
 //!
 //! All colors are in ARGB8888 format (0xAARRGGBB).
 
@@ -8,6 +8,12 @@ pub const BACKGROUND: u32 = 0xFF1E1E1E;
 /// Grid line color (medium gray).
 pub const GRID: u32 = 0xFF333333;
 
+/// World-space grid X axis color (red).
+pub const GRID_X_AXIS: u32 = 0xFFB03A3A;
+
+/// World-space grid Z axis color (blue).
+pub const GRID_Z_AXIS: u32 = 0xFF3A5CB0;
+
 /// Default triangle fill color (gray).
 pub const FILL: u32 = 0xFF888888;
 
@@ -17,6 +23,9 @@ pub const WIREFRAME: u32 = 0xFF00FF00;
 /// Vertex marker color (red).
 pub const VERTEX: u32 = 0xFFFF0000;
 
+/// On-screen overlay text color (white).
+pub const TEXT: u32 = 0xFFFFFFFF;
+
 /// Modulate a color by an intensity factor (0.0 to 1.0).
 ///
 /// Preserves the alpha channel while scaling the RGB channels.
@@ -29,6 +38,25 @@ pub fn modulate(color: u32, intensity: f32) -> u32 {
     (a << 24) | (r << 16) | (g << 8) | b
 }
 
+/// Add two colors channel by channel, saturating at 255.
+///
+/// Sums the contributions of several lights on one surface. Keeps `a`'s alpha.
+pub fn color_add(a: u32, b: u32) -> u32 {
+    let channel = |shift: u32| (((a >> shift) & 0xFF) + ((b >> shift) & 0xFF)).min(255) << shift;
+    (a & 0xFF000000) | channel(16) | channel(8) | channel(0)
+}
+
+/// Multiply two colors channel by channel as normalized [0, 1] values.
+///
+/// Tints a surface color by a light color. Keeps `a`'s alpha.
+pub fn color_mul(a: u32, b: u32) -> u32 {
+    let channel = |shift: u32| {
+        let product = ((a >> shift) & 0xFF) * ((b >> shift) & 0xFF);
+        ((product + 127) / 255) << shift
+    };
+    (a & 0xFF000000) | channel(16) | channel(8) | channel(0)
+}
+
 /// Unpack an ARGB8888 color into its constituent RGB components constrained to the range [0.0, 1.0].
 ///
 /// Returns a tuple of floats representing the red, green, and blue components.
@@ -49,6 +77,131 @@ pub fn pack_color(r: f32, g: f32, b: f32, a: f32) -> u32 {
     (a << 24) | (r << 16) | (g << 8) | b
 }
 
+/// Convert an sRGB-encoded channel in [0.0, 1.0] to linear light.
+///
+/// Colors stored in the frame buffer (and in textures and materials) are sRGB
+/// encoded, so averaging them directly darkens the midtones. Blend and light in
+/// linear space, then convert back with [`linear_to_srgb`].
+#[inline]
+pub fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear-light channel in [0.0, 1.0] to sRGB encoding.
+#[inline]
+pub fn linear_to_srgb(c: f32) -> f32 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Unpack an ARGB8888 color into linear-light RGB components in [0.0, 1.0].
+pub fn unpack_color_linear(color: u32) -> (f32, f32, f32) {
+    let (r, g, b) = unpack_color(color);
+    (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b))
+}
+
+/// Pack linear-light RGB components and an alpha value into an sRGB ARGB8888 color.
+pub fn pack_color_linear(r: f32, g: f32, b: f32, a: f32) -> u32 {
+    pack_color(linear_to_srgb(r), linear_to_srgb(g), linear_to_srgb(b), a)
+}
+
+/// Like [`modulate`], but scales the color in linear space so half intensity
+/// looks half as bright.
+pub fn modulate_linear(color: u32, intensity: f32) -> u32 {
+    let (r, g, b) = unpack_color_linear(color);
+    let a = ((color >> 24) & 0xFF) as f32 / 255.0;
+    let intensity = intensity.max(0.0);
+    pack_color_linear(r * intensity, g * intensity, b * intensity, a)
+}
+
+/// Convert hue (degrees), saturation, and value to an opaque ARGB8888 color.
+///
+/// Hue wraps around, so -30 and 690 are both 330. Saturation and value are
+/// clamped to [0.0, 1.0].
+pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> u32 {
+    let s = s.clamp(0.0, 1.0);
+    let v = v.clamp(0.0, 1.0);
+    let chroma = v * s;
+    let (r, g, b) = hue_to_rgb(h, chroma);
+    let m = v - chroma;
+    pack_color(r + m, g + m, b + m, 1.0)
+}
+
+/// Convert a color to hue (degrees in [0, 360)), saturation, and value.
+///
+/// Grays have no hue and report 0. Alpha is ignored.
+pub fn rgb_to_hsv(color: u32) -> (f32, f32, f32) {
+    let (r, g, b) = unpack_color(color);
+    let max = r.max(g).max(b);
+    let chroma = max - r.min(g).min(b);
+    let s = if max > 0.0 { chroma / max } else { 0.0 };
+    (hue(r, g, b, max, chroma), s, max)
+}
+
+/// Convert hue (degrees), saturation, and lightness to an opaque ARGB8888 color.
+///
+/// Hue wraps around like [`hsv_to_rgb`].
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> u32 {
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let (r, g, b) = hue_to_rgb(h, chroma);
+    let m = l - chroma / 2.0;
+    pack_color(r + m, g + m, b + m, 1.0)
+}
+
+/// Convert a color to hue (degrees in [0, 360)), saturation, and lightness.
+pub fn rgb_to_hsl(color: u32) -> (f32, f32, f32) {
+    let (r, g, b) = unpack_color(color);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let l = (max + min) / 2.0;
+    let s = if chroma > 0.0 {
+        chroma / (1.0 - (2.0 * l - 1.0).abs())
+    } else {
+        0.0
+    };
+    (hue(r, g, b, max, chroma), s, l)
+}
+
+/// The fully saturated RGB for a hue, scaled to `chroma` (before adding the gray offset).
+fn hue_to_rgb(h: f32, chroma: f32) -> (f32, f32, f32) {
+    let sector = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    }
+}
+
+/// Hue in degrees shared by HSV and HSL, given the largest channel and chroma.
+fn hue(r: f32, g: f32, b: f32, max: f32, chroma: f32) -> f32 {
+    if chroma == 0.0 {
+        return 0.0;
+    }
+    let h = if max == r {
+        (g - b) / chroma
+    } else if max == g {
+        (b - r) / chroma + 2.0
+    } else {
+        (r - g) / chroma + 4.0
+    };
+    (h * 60.0).rem_euclid(360.0)
+}
+
 /// Linearly interpolates between two RGB colors.
 ///
 /// # Formula
@@ -74,3 +227,70 @@ pub fn lerp_color(c1: (f32, f32, f32), c2: (f32, f32, f32), t: f32) -> (f32, f32
         c1.2 + (c2.2 - c1.2) * t,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_round_trips_every_channel_value() {
+        for value in 0..=255u32 {
+            let color = 0xFF000000 | value;
+            let (r, g, b) = unpack_color_linear(color);
+            assert_eq!(pack_color_linear(r, g, b, 1.0), color);
+        }
+    }
+
+    #[test]
+    fn half_blend_in_linear_space_is_brighter_than_in_srgb() {
+        let black = unpack_color_linear(0xFF000000);
+        let white = unpack_color_linear(0xFFFFFFFF);
+        let (r, g, b) = lerp_color(black, white, 0.5);
+        assert_eq!(pack_color_linear(r, g, b, 1.0), 0xFFBCBCBC); // 188
+
+        let (r, g, b) = lerp_color(unpack_color(0xFF000000), unpack_color(0xFFFFFFFF), 0.5);
+        assert_eq!(pack_color(r, g, b, 1.0), 0xFF808080); // 128
+    }
+
+    #[test]
+    fn primaries_round_trip_through_hsv_and_hsl() {
+        let primaries = [
+            (0xFFFF0000, 0.0),
+            (0xFFFFFF00, 60.0),
+            (0xFF00FF00, 120.0),
+            (0xFF00FFFF, 180.0),
+            (0xFF0000FF, 240.0),
+            (0xFFFF00FF, 300.0),
+        ];
+        for (color, hue) in primaries {
+            assert_eq!(rgb_to_hsv(color), (hue, 1.0, 1.0));
+            assert_eq!(hsv_to_rgb(hue, 1.0, 1.0), color);
+            assert_eq!(rgb_to_hsl(color), (hue, 1.0, 0.5));
+            assert_eq!(hsl_to_rgb(hue, 1.0, 0.5), color);
+        }
+        assert_eq!(rgb_to_hsv(0xFF808080), (0.0, 0.0, 128.0 / 255.0));
+    }
+
+    #[test]
+    fn hue_wraps_around() {
+        assert_eq!(hsv_to_rgb(360.0, 1.0, 1.0), 0xFFFF0000);
+        assert_eq!(hsv_to_rgb(-120.0, 1.0, 1.0), hsv_to_rgb(240.0, 1.0, 1.0));
+        assert_eq!(hsl_to_rgb(780.0, 1.0, 0.5), hsl_to_rgb(60.0, 1.0, 0.5));
+        // Just below red on the wheel is a magenta-leaning red
+        let (h, _, _) = rgb_to_hsv(0xFFFF0010);
+        assert!(h > 350.0 && h < 360.0);
+    }
+
+    #[test]
+    fn color_add_saturates_each_channel() {
+        assert_eq!(color_add(0xFFC00000, 0x80C01020), 0xFFFF1020);
+        assert_eq!(color_add(0x00102030, 0xFF010203), 0x00112233);
+    }
+
+    #[test]
+    fn color_mul_scales_by_normalized_channels() {
+        assert_eq!(color_mul(0xFF808080, 0xFF808080), 0xFF404040);
+        assert_eq!(color_mul(0x80FF8040, 0xFFFFFFFF), 0x80FF8040);
+        assert_eq!(color_mul(0xFFFF8040, 0xFF000000), 0xFF000000);
+    }
+}
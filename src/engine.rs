@@ -6,10 +6,15 @@
 
 use crate::camera::FpsCamera;
 use crate::colors;
-use crate::light::DirectionalLight;
-use crate::mesh::{LoadError, Mesh};
+use crate::light::{DirectionalLight, PointLight};
+use crate::mesh::{LoadError, Mesh, ObjOptions};
 use crate::prelude::{Mat4, Vec3, Vec4};
-use crate::render::{Rasterizer, RasterizerDispatcher, Renderer, Triangle};
+use crate::render::clipping::{clip_line, clip_triangle, ClipVertex};
+use crate::render::rasterizer::shader::{NormalMappedShader, PhongLight, PhongParams, PhongShader};
+use crate::render::{
+    BlendMode, DepthFog, DepthPass, ImageView, PostProcess, Rasterizer, RasterizerDispatcher, Rect,
+    Renderer, Triangle,
+};
 
 pub use crate::render::RasterizerType;
 use crate::texture::Texture;
@@ -40,6 +45,8 @@ pub enum ShadingMode {
     Flat,
     /// Gouraud shading - per-vertex lighting interpolated across face
     Gouraud,
+    /// Phong shading - per-pixel lighting from interpolated normals, with specular highlights
+    Phong,
 }
 
 /// Texture mapping mode
@@ -54,12 +61,54 @@ pub enum TextureMode {
     Modulate,
 }
 
+/// Shape of the markers drawn at vertices in the `*Vertices` render modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VertexMarkerShape {
+    #[default]
+    Square,
+    Circle,
+}
+
+/// How vertices are marked in the `*Vertices` render modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct VertexMarker {
+    /// Width in output pixels
+    pub size: u32,
+    /// ARGB8888 color
+    pub color: u32,
+    pub shape: VertexMarkerShape,
+}
+
+/// Camera projection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProjectionMode {
+    /// Distant objects appear smaller
+    #[default]
+    Perspective,
+    /// No foreshortening: parallel lines stay parallel, for CAD-style views
+    Orthographic,
+}
+
+/// Debug coloring that replaces the material color of every face
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FaceColorMode {
+    /// Material (or default fill) color
+    #[default]
+    Uniform,
+    /// A distinct hue per face, derived from a hash of the face index
+    PerFace,
+    /// Face depth across the mesh's bounds, from blue (nearest) to red (furthest)
+    DepthHeatmap,
+}
+
 impl std::fmt::Display for ShadingMode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ShadingMode::None => write!(f, "None"),
             ShadingMode::Flat => write!(f, "Flat"),
             ShadingMode::Gouraud => write!(f, "Gouraud"),
+            ShadingMode::Phong => write!(f, "Phong"),
         }
     }
 }
@@ -74,42 +123,370 @@ impl std::fmt::Display for TextureMode {
     }
 }
 
+/// Default vertical field of view in degrees.
+const DEFAULT_FOV_Y_DEGREES: f32 = 45.0;
+/// Default distance to the near clip plane.
+const DEFAULT_Z_NEAR: f32 = 0.1;
+/// Default distance to the far clip plane.
+const DEFAULT_Z_FAR: f32 = 100.0;
+/// Default frame buffer size used by [`EngineBuilder`].
+const DEFAULT_WIDTH: u32 = 800;
+const DEFAULT_HEIGHT: u32 = 600;
+/// Default world-space height of the orthographic view volume, roughly what the
+/// default perspective shows at the starting camera distance.
+const DEFAULT_ORTHO_HEIGHT: f32 = 4.0;
+/// Brightness of back-face edges drawn while only fills are culled.
+const BACK_FACE_WIREFRAME_INTENSITY: f32 = 0.4;
+/// Default vertex marker size in pixels.
+const DEFAULT_VERTEX_MARKER_SIZE: u32 = 4;
+
+/// A world-space grid on the XZ plane (y = 0), set with [`Engine::set_grid`].
+///
+/// Lines are projected through the camera and depth tested like wireframes, so
+/// the grid recedes with perspective and geometry can hide it. The lines through
+/// the origin are the X and Z axes and get their own colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct GridConfig {
+    /// World units between neighboring lines.
+    pub spacing: f32,
+    /// Lines on each side of an axis, so the grid spans `spacing * half_lines` each way.
+    pub half_lines: u32,
+    pub color: u32,
+    /// Color of the line along the X axis (z = 0).
+    pub x_axis_color: u32,
+    /// Color of the line along the Z axis (x = 0).
+    pub z_axis_color: u32,
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        Self {
+            spacing: 1.0,
+            half_lines: 10,
+            color: colors::GRID,
+            x_axis_color: colors::GRID_X_AXIS,
+            z_axis_color: colors::GRID_Z_AXIS,
+        }
+    }
+}
+
+/// Depth fog settings for [`Engine::set_fog`].
+///
+/// Filled surfaces blend linearly from their shaded color at `near` to `color`
+/// at `far`, measured as view-space depth along the camera's forward axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct FogConfig {
+    pub color: u32,
+    /// Depth where the fog starts.
+    pub near: f32,
+    /// Depth where surfaces are fully fogged.
+    pub far: f32,
+}
+
+impl FogConfig {
+    pub fn new(color: u32, near: f32, far: f32) -> Self {
+        Self { color, near, far }
+    }
+}
+
+/// Per-frame counters for profiling, from [`Engine::last_frame_stats`].
+///
+/// Every submitted face is either culled or drawn as one or more triangles:
+/// near-plane clipping can split a face in two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub struct RenderStats {
+    /// Mesh faces considered by the last [`Engine::update`].
+    pub triangles_submitted: usize,
+    /// Faces dropped whole: outside the view frustum, back facing, or behind the near plane.
+    pub triangles_culled: usize,
+    /// Faces cut by the near plane, whose visible part is still drawn.
+    pub triangles_clipped: usize,
+    /// Screen-space triangles handed to the rasterizer.
+    pub triangles_drawn: usize,
+    /// Fragments shaded while filling triangles in the last [`Engine::render`].
+    pub pixels_shaded: usize,
+}
+
+/// Handle to a mesh added with [`Engine::add_mesh`].
+///
+/// Ids are never reused, so a handle to a removed mesh stays invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MeshId(u32);
+
+/// The renderer's main entry point: owns the scene, camera, and frame buffer.
+///
+/// Every mode is a plain enum re-exported at the crate root, so an engine can be
+/// driven without a window:
+///
+/// ```
+/// use russsty::{Engine, Mesh, RasterizerType, RenderMode, ShadingMode};
+///
+/// let mut engine = Engine::new(64, 48);
+/// engine.add_mesh(Mesh::sphere(1.0, 8, 12));
+/// engine.set_shading_mode(ShadingMode::Gouraud);
+/// engine.set_rasterizer(RasterizerType::Scanline);
+///
+/// for mode in [
+///     RenderMode::Wireframe,
+///     RenderMode::WireframeVertices,
+///     RenderMode::FilledWireframe,
+///     RenderMode::FilledWireframeVertices,
+///     RenderMode::Filled,
+/// ] {
+///     engine.set_render_mode(mode);
+///     engine.update(0.0);
+///     engine.render();
+///     assert_eq!(engine.render_mode(), mode);
+/// }
+/// assert_eq!(engine.shading_mode(), ShadingMode::Gouraud);
+/// assert_eq!(engine.frame_buffer().len(), 64 * 48 * 4);
+/// ```
 pub struct Engine {
     renderer: Renderer,
     rasterizer: RasterizerDispatcher,
     triangles_to_render: Vec<Triangle>,
-    mesh: Mesh,
+    silhouette_lines: Vec<(Vec3, Vec3, u32)>, // Screen-space endpoints and color, from `update`
+    front_facing: Vec<bool>, // Per-face scratch for silhouette detection, reused across frames
+    meshes: Vec<(MeshId, Mesh)>, // Scene meshes in insertion order; the first is `mesh()`
+    next_mesh_id: u32,
     camera: FpsCamera,
+    fov_y: f32, // Vertical field of view (radians)
+    z_near: f32,
+    z_far: f32,
+    projection_mode: ProjectionMode,
+    ortho_height: f32, // World-space height visible under orthographic projection
     projection_matrix: Mat4,
     render_mode: RenderMode,
     texture: Option<Texture>,
     texture_mode: TextureMode,
+    normal_map: Option<Texture>,
     shading_mode: ShadingMode,
+    face_color_mode: FaceColorMode,
     light: DirectionalLight,
+    point_lights: Vec<PointLight>,
+    phong_params: PhongParams,
+    blend_mode: BlendMode,
+    depth_sort: bool,
+    depth_prepass: bool,
+    stats: RenderStats, // Counted by the last `update` and `render`
+    antialiased_lines: bool,
+    wireframe_width: f32,
+    wireframe_over_fill: bool,
+    silhouette_edges: bool,
+    wireframe_color: u32,
+    vertex_marker: VertexMarker,
+    overlay_text: String,
+    clear_color: u32,
+    gamma_correct: bool,
+    incremental_clear: bool,
+    cleared_background: Option<(u32, bool)>, // Clear color and screen grid of the last full clear
+    grid: Option<GridConfig>,                // World-space grid; `None` draws the screen grid
+    fog: Option<FogConfig>,
+    post_processes: Vec<Box<dyn PostProcess>>, // Applied in order after the scene is drawn
+    mesh_angular_velocity: Vec3, // Radians per second, applied to `mesh()` by `update`
+    /// Skip filling triangles that face away from the camera.
     pub backface_culling: bool,
+    /// Skip the edges and vertex markers of back faces. `None` (the default)
+    /// follows `backface_culling`; `Some(false)` with `backface_culling` on shows
+    /// back faces as dimmed edges behind the fills.
+    pub wireframe_backface_culling: Option<bool>,
     pub draw_grid: bool,
+    /// Draw debug text (overlay text, modes, camera position) onto the frame.
+    pub draw_overlay: bool,
+}
+
+/// Chainable [`Engine`] configuration, finished with [`EngineBuilder::build`].
+///
+/// Options left unset keep the same defaults as [`Engine::new`].
+///
+/// ```
+/// use russsty::{Engine, RasterizerType};
+///
+/// let engine = Engine::builder()
+///     .with_size(320, 240)
+///     .with_fov_degrees(60.0)
+///     .with_rasterizer(RasterizerType::Scanline)
+///     .with_clear_color(0xFFFFFFFF)
+///     .build();
+/// assert_eq!(engine.rasterizer(), RasterizerType::Scanline);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EngineBuilder {
+    width: u32,
+    height: u32,
+    fov_degrees: f32,
+    projection: ProjectionMode,
+    rasterizer: RasterizerType,
+    render_mode: RenderMode,
+    shading_mode: ShadingMode,
+    clear_color: u32,
+    camera_position: Option<Vec3>,
+    backface_culling: bool,
+    draw_grid: bool,
+}
+
+impl Default for EngineBuilder {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            fov_degrees: DEFAULT_FOV_Y_DEGREES,
+            projection: ProjectionMode::default(),
+            rasterizer: RasterizerType::default(),
+            render_mode: RenderMode::default(),
+            shading_mode: ShadingMode::default(),
+            clear_color: colors::BACKGROUND,
+            camera_position: None,
+            backface_culling: true,
+            draw_grid: true,
+        }
+    }
+}
+
+impl EngineBuilder {
+    /// Frame buffer size in pixels (default 800x600).
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn with_fov_degrees(mut self, degrees: f32) -> Self {
+        self.fov_degrees = degrees;
+        self
+    }
+
+    pub fn with_projection(mut self, mode: ProjectionMode) -> Self {
+        self.projection = mode;
+        self
+    }
+
+    pub fn with_rasterizer(mut self, rasterizer_type: RasterizerType) -> Self {
+        self.rasterizer = rasterizer_type;
+        self
+    }
+
+    pub fn with_render_mode(mut self, mode: RenderMode) -> Self {
+        self.render_mode = mode;
+        self
+    }
+
+    pub fn with_shading_mode(mut self, mode: ShadingMode) -> Self {
+        self.shading_mode = mode;
+        self
+    }
+
+    pub fn with_clear_color(mut self, color: u32) -> Self {
+        self.clear_color = color;
+        self
+    }
+
+    pub fn with_camera_position(mut self, position: Vec3) -> Self {
+        self.camera_position = Some(position);
+        self
+    }
+
+    pub fn with_backface_culling(mut self, enabled: bool) -> Self {
+        self.backface_culling = enabled;
+        self
+    }
+
+    pub fn with_grid(mut self, enabled: bool) -> Self {
+        self.draw_grid = enabled;
+        self
+    }
+
+    pub fn build(self) -> Engine {
+        let mut engine = Engine::new(self.width, self.height);
+        engine.set_projection(self.projection);
+        engine.set_fov_degrees(self.fov_degrees);
+        engine.set_rasterizer(self.rasterizer);
+        engine.set_render_mode(self.render_mode);
+        engine.set_shading_mode(self.shading_mode);
+        engine.set_clear_color(self.clear_color);
+        if let Some(position) = self.camera_position {
+            engine.set_camera_position(position);
+        }
+        engine.backface_culling = self.backface_culling;
+        engine.draw_grid = self.draw_grid;
+        engine
+    }
 }
 
 impl Engine {
+    /// Start configuring an engine; see [`EngineBuilder`].
+    pub fn builder() -> EngineBuilder {
+        EngineBuilder::default()
+    }
+
     pub fn new(width: u32, height: u32) -> Self {
-        let fov: f32 = 45.0;
-        let aspect_ratio = width as f32 / height as f32;
-        let projection_matrix = Mat4::perspective_lh(fov.to_radians(), aspect_ratio, 0.1, 100.0);
+        // The renderer clamps to at least 1x1; the aspect ratio has to agree
+        let renderer = Renderer::new(width, height);
+        let fov_y = DEFAULT_FOV_Y_DEGREES.to_radians();
+        let projection_matrix = Mat4::perspective_lh(
+            fov_y,
+            renderer.width() as f32 / renderer.height() as f32,
+            DEFAULT_Z_NEAR,
+            DEFAULT_Z_FAR,
+        );
 
         Self {
-            renderer: Renderer::new(width, height),
+            renderer,
             rasterizer: RasterizerDispatcher::new(RasterizerType::default()),
             triangles_to_render: Vec::new(),
-            mesh: Mesh::new(vec![], vec![], Vec3::ZERO, Vec3::ONE, Vec3::ZERO),
+            silhouette_lines: Vec::new(),
+            front_facing: Vec::new(),
+            meshes: vec![(
+                MeshId(0),
+                Mesh::new(vec![], vec![], Vec3::ZERO, Vec3::ONE, Vec3::ZERO),
+            )],
+            next_mesh_id: 1,
             camera: FpsCamera::new(Vec3::new(0.0, 0.0, -5.0)),
+            fov_y,
+            z_near: DEFAULT_Z_NEAR,
+            z_far: DEFAULT_Z_FAR,
+            projection_mode: ProjectionMode::default(),
+            ortho_height: DEFAULT_ORTHO_HEIGHT,
             projection_matrix,
             texture: None,
             texture_mode: TextureMode::default(),
+            normal_map: None,
             render_mode: RenderMode::default(),
             shading_mode: ShadingMode::default(),
+            face_color_mode: FaceColorMode::default(),
             light: DirectionalLight::new(Vec3::new(0.0, 0.0, 1.0)),
+            point_lights: Vec::new(),
+            phong_params: PhongParams::default(),
+            blend_mode: BlendMode::default(),
+            depth_sort: false,
+            depth_prepass: false,
+            stats: RenderStats::default(),
+            antialiased_lines: false,
+            wireframe_width: 1.0,
+            wireframe_over_fill: true,
+            silhouette_edges: false,
+            wireframe_color: colors::WIREFRAME,
+            vertex_marker: VertexMarker {
+                size: DEFAULT_VERTEX_MARKER_SIZE,
+                color: colors::VERTEX,
+                shape: VertexMarkerShape::Square,
+            },
+            overlay_text: String::new(),
+            clear_color: colors::BACKGROUND,
+            gamma_correct: false,
+            incremental_clear: false,
+            cleared_background: None,
+            grid: None,
+            fog: None,
+            post_processes: Vec::new(),
+            mesh_angular_velocity: Vec3::ZERO,
             backface_culling: true,
+            wireframe_backface_culling: None,
             draw_grid: true,
+            draw_overlay: false,
         }
     }
 
@@ -121,6 +498,16 @@ impl Engine {
         self.shading_mode
     }
 
+    /// Color faces by index or depth instead of by material, to debug topology,
+    /// culling and sorting. The debug colors are still lit by the shading mode.
+    pub fn set_face_color_mode(&mut self, mode: FaceColorMode) {
+        self.face_color_mode = mode;
+    }
+
+    pub fn face_color_mode(&self) -> FaceColorMode {
+        self.face_color_mode
+    }
+
     pub fn set_render_mode(&mut self, mode: RenderMode) {
         self.render_mode = mode;
     }
@@ -138,14 +525,351 @@ impl Engine {
     }
 
     pub fn load_mesh(&mut self, file_path: &str) -> Result<(), LoadError> {
-        self.mesh = Mesh::from_obj(file_path)?;
+        self.load_mesh_with_options(file_path, &ObjOptions::default())
+    }
+
+    /// Load an OBJ mesh, e.g. with `flip_v` for exporters using top-left UV origins.
+    pub fn load_mesh_with_options(
+        &mut self,
+        file_path: &str,
+        options: &ObjOptions,
+    ) -> Result<(), LoadError> {
+        let mesh = Mesh::from_obj_merged(file_path, options)?;
+        match self.meshes.first_mut() {
+            Some((_, first)) => *first = mesh,
+            None => {
+                self.add_mesh(mesh);
+            }
+        }
         Ok(())
     }
 
+    /// Add a mesh to the scene, drawn alongside the others with its own transform.
+    pub fn add_mesh(&mut self, mesh: Mesh) -> MeshId {
+        let id = MeshId(self.next_mesh_id);
+        self.next_mesh_id += 1;
+        self.meshes.push((id, mesh));
+        id
+    }
+
+    /// Remove a mesh from the scene, returning it if the id was present.
+    pub fn remove_mesh(&mut self, id: MeshId) -> Option<Mesh> {
+        let index = self.meshes.iter().position(|(mesh_id, _)| *mesh_id == id)?;
+        Some(self.meshes.remove(index).1)
+    }
+
+    pub fn mesh_by_id(&self, id: MeshId) -> Option<&Mesh> {
+        self.meshes
+            .iter()
+            .find(|(mesh_id, _)| *mesh_id == id)
+            .map(|(_, mesh)| mesh)
+    }
+
+    pub fn mesh_by_id_mut(&mut self, id: MeshId) -> Option<&mut Mesh> {
+        self.meshes
+            .iter_mut()
+            .find(|(mesh_id, _)| *mesh_id == id)
+            .map(|(_, mesh)| mesh)
+    }
+
+    /// Number of meshes in the scene.
+    pub fn mesh_count(&self) -> usize {
+        self.meshes.len()
+    }
+
+    /// Resize the output, e.g. when the window is resized.
+    ///
+    /// Zero dimensions, as reported while a window is minimized, are treated as `1`,
+    /// so updating and rendering keep working until a real size comes back.
     pub fn resize(&mut self, width: u32, height: u32) {
         self.renderer.resize(width, height);
-        self.projection_matrix =
-            Mat4::perspective_lh(45.0, width as f32 / height as f32, 0.1, 100.0);
+        self.update_projection();
+    }
+
+    /// Render at `factor` times the output resolution along each axis and
+    /// box-downsample when presenting, anti-aliasing every edge.
+    ///
+    /// `1` (the default) disables supersampling. Costs `factor^2` the fill work and
+    /// buffer memory. Takes effect from the next [`Engine::update`].
+    pub fn set_supersampling(&mut self, factor: u32) {
+        self.renderer.set_supersampling(factor);
+        self.update_projection();
+    }
+
+    pub fn supersampling(&self) -> u32 {
+        self.renderer.supersampling()
+    }
+
+    /// Set how far wireframe edges are pulled in front of filled triangles, in 1/w units.
+    ///
+    /// Keeps `RenderMode::FilledWireframe` edges from z-fighting with the fill. Defaults
+    /// to 0.0001; 0.0 disables the offset.
+    pub fn set_wireframe_depth_bias(&mut self, bias: f32) {
+        self.renderer.set_wireframe_depth_bias(bias);
+    }
+
+    pub fn wireframe_depth_bias(&self) -> f32 {
+        self.renderer.wireframe_depth_bias()
+    }
+
+    /// Draw wireframe edges with anti-aliased (Xiaolin Wu) lines instead of Bresenham.
+    ///
+    /// Smoother but slower; anti-aliased lines are blended and don't write depth.
+    pub fn set_antialiased_lines(&mut self, enabled: bool) {
+        self.antialiased_lines = enabled;
+    }
+
+    pub fn antialiased_lines(&self) -> bool {
+        self.antialiased_lines
+    }
+
+    /// Set the wireframe line width in pixels (default 1.0).
+    ///
+    /// Widths above one pixel draw each edge as a capsule with rounded joins and
+    /// take precedence over [`Engine::set_antialiased_lines`].
+    pub fn set_wireframe_width(&mut self, width: f32) {
+        self.wireframe_width = width.max(1.0);
+    }
+
+    pub fn wireframe_width(&self) -> f32 {
+        self.wireframe_width
+    }
+
+    /// Draw wireframe edges over the fill (the default), or under it.
+    ///
+    /// Under the fill, edges are drawn first and never occlude it: an opaque fill
+    /// hides the edges it covers and a blended fill (see [`Engine::set_blend_mode`])
+    /// lets them show through.
+    pub fn set_wireframe_over_fill(&mut self, over: bool) {
+        self.wireframe_over_fill = over;
+    }
+
+    pub fn wireframe_over_fill(&self) -> bool {
+        self.wireframe_over_fill
+    }
+
+    /// Draw only silhouette edges in wireframe modes: edges between a face turned
+    /// toward the camera and one turned away, plus the open edges of front faces.
+    ///
+    /// Faces are matched across shared edges when the mesh is loaded, so this
+    /// outlines each mesh regardless of backface culling. Takes effect from the
+    /// next [`Engine::update`].
+    pub fn set_silhouette_edges(&mut self, enabled: bool) {
+        self.silhouette_edges = enabled;
+    }
+
+    pub fn silhouette_edges(&self) -> bool {
+        self.silhouette_edges
+    }
+
+    /// Set the edge color for wireframe render modes (ARGB8888, default `colors::WIREFRAME`).
+    ///
+    /// Edges are drawn separately from fills, so filled-wireframe modes can show
+    /// them against a similarly colored surface. Meshes can override it with
+    /// [`Mesh::set_wireframe_color`].
+    pub fn set_wireframe_color(&mut self, color: u32) {
+        self.wireframe_color = color;
+    }
+
+    pub fn wireframe_color(&self) -> u32 {
+        self.wireframe_color
+    }
+
+    /// Whether back-face edges are skipped, after applying the
+    /// `wireframe_backface_culling` override to `backface_culling`.
+    pub fn wireframe_culls_back_faces(&self) -> bool {
+        self.wireframe_backface_culling
+            .unwrap_or(self.backface_culling)
+    }
+
+    /// Set how vertices are marked in the `*Vertices` render modes.
+    ///
+    /// Markers are `size` output pixels across (at least 1), centered on the
+    /// projected vertex. Defaults to 4 pixel `colors::VERTEX` squares.
+    pub fn set_vertex_marker(&mut self, size: u32, color: u32, shape: VertexMarkerShape) {
+        self.vertex_marker = VertexMarker {
+            size: size.max(1),
+            color,
+            shape,
+        };
+    }
+
+    /// The vertex marker's size, color and shape.
+    pub fn vertex_marker(&self) -> VertexMarker {
+        self.vertex_marker
+    }
+
+    /// Set the color each frame is cleared to (ARGB8888, default `colors::BACKGROUND`).
+    ///
+    /// A zero alpha gives a transparent background in PNG screenshots.
+    pub fn set_clear_color(&mut self, color: u32) {
+        self.clear_color = color;
+    }
+
+    pub fn clear_color(&self) -> u32 {
+        self.clear_color
+    }
+
+    /// Light and blend colors in linear space instead of directly on sRGB values.
+    ///
+    /// Covers vertex lighting, Gouraud interpolation, per-pixel Phong lighting and
+    /// texture modulation, so gradients and lighting falloff look perceptually even
+    /// instead of too dark in the midtones. Off by default since it is slower.
+    pub fn set_gamma_correct(&mut self, enabled: bool) {
+        self.gamma_correct = enabled;
+    }
+
+    pub fn gamma_correct(&self) -> bool {
+        self.gamma_correct
+    }
+
+    /// Clear only the region drawn last frame instead of the whole buffer.
+    ///
+    /// Saves fill work for small or mostly static scenes. The grid is redrawn
+    /// only inside the cleared region. Changing the clear color or toggling the
+    /// grid forces one full clear.
+    pub fn set_incremental_clear(&mut self, enabled: bool) {
+        self.incremental_clear = enabled;
+    }
+
+    pub fn incremental_clear(&self) -> bool {
+        self.incremental_clear
+    }
+
+    /// Draw a world-space grid on the XZ plane instead of the screen-space grid.
+    ///
+    /// Either grid is only drawn while `draw_grid` is set.
+    pub fn set_grid(&mut self, config: GridConfig) {
+        self.grid = Some(config);
+    }
+
+    /// The world-space grid settings, or `None` while the screen-space grid is used.
+    pub fn grid(&self) -> Option<GridConfig> {
+        self.grid
+    }
+
+    /// Go back to the screen-space grid, a fixed pixel pattern behind the scene.
+    pub fn use_screen_grid(&mut self) {
+        self.grid = None;
+    }
+
+    /// Fade filled surfaces toward a fog color with distance.
+    ///
+    /// Applied to each fragment as the triangles are filled, so wireframes,
+    /// markers, the grid and the background stay unfogged.
+    pub fn set_fog(&mut self, config: FogConfig) {
+        self.fog = Some(config);
+    }
+
+    pub fn fog(&self) -> Option<FogConfig> {
+        self.fog
+    }
+
+    pub fn clear_fog(&mut self) {
+        self.fog = None;
+    }
+
+    /// Set caller-provided text (e.g. FPS) shown at the top of the debug overlay.
+    ///
+    /// Lines are separated by `\n`. Only drawn while `draw_overlay` is set.
+    pub fn set_overlay_text(&mut self, text: &str) {
+        self.overlay_text = text.to_string();
+    }
+
+    pub fn overlay_text(&self) -> &str {
+        &self.overlay_text
+    }
+
+    /// The debug overlay: caller text followed by the engine's own state.
+    fn overlay_lines(&self) -> String {
+        let position = self.camera.position();
+        let mut text = self.overlay_text.clone();
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!(
+            "{:?} | {} | {}\nCamera: ({:.2}, {:.2}, {:.2})",
+            self.render_mode,
+            self.rasterizer.active_type(),
+            self.shading_mode,
+            position.x,
+            position.y,
+            position.z
+        ));
+        text
+    }
+
+    /// Set the vertical field of view in degrees (default 45).
+    ///
+    /// The horizontal extent follows the buffer's aspect ratio, so resizing the
+    /// window never stretches the image.
+    pub fn set_fov_degrees(&mut self, degrees: f32) {
+        self.fov_y = degrees.clamp(1.0, 179.0).to_radians();
+        self.update_projection();
+    }
+
+    pub fn fov_degrees(&self) -> f32 {
+        self.fov_y.to_degrees()
+    }
+
+    /// Switch between perspective and orthographic projection.
+    pub fn set_projection(&mut self, mode: ProjectionMode) {
+        self.projection_mode = mode;
+        self.update_projection();
+    }
+
+    pub fn projection(&self) -> ProjectionMode {
+        self.projection_mode
+    }
+
+    /// Set how many world units fit vertically under orthographic projection (default 4).
+    ///
+    /// The width follows from the aspect ratio.
+    pub fn set_ortho_height(&mut self, height: f32) {
+        self.ortho_height = height;
+        self.update_projection();
+    }
+
+    pub fn ortho_height(&self) -> f32 {
+        self.ortho_height
+    }
+
+    /// Set the view-space distances of the near and far clip planes (default 0.1 and 100).
+    ///
+    /// Geometry closer than `near` or beyond `far` is clipped away. A tighter range
+    /// spreads the depth buffer's precision over less distance.
+    ///
+    /// # Panics
+    /// Panics unless `0 < near < far` and `far` is finite.
+    pub fn set_clip_planes(&mut self, near: f32, far: f32) {
+        assert!(
+            near > 0.0 && near < far && far.is_finite(),
+            "clip planes must satisfy 0 < near < far with a finite far, got near {near} far {far}"
+        );
+        self.z_near = near;
+        self.z_far = far;
+        self.update_projection();
+    }
+
+    /// The near and far clip plane distances.
+    pub fn clip_planes(&self) -> (f32, f32) {
+        (self.z_near, self.z_far)
+    }
+
+    /// Rebuilds the projection matrix from the current mode, FOV or ortho height,
+    /// clip planes, and buffer size.
+    fn update_projection(&mut self) {
+        let aspect_ratio = self.renderer.width() as f32 / self.renderer.height() as f32;
+        self.projection_matrix = match self.projection_mode {
+            ProjectionMode::Perspective => {
+                Mat4::perspective_lh(self.fov_y, aspect_ratio, self.z_near, self.z_far)
+            }
+            ProjectionMode::Orthographic => {
+                let top = self.ortho_height / 2.0;
+                let right = top * aspect_ratio;
+                Mat4::orthographic(-right, right, -top, top, self.z_near, self.z_far)
+            }
+        };
     }
 
     pub fn camera(&self) -> &FpsCamera {
@@ -164,27 +888,266 @@ impl Engine {
         self.camera.position()
     }
 
+    pub fn set_light(&mut self, light: DirectionalLight) {
+        self.light = light;
+    }
+
+    pub fn light(&self) -> &DirectionalLight {
+        &self.light
+    }
+
+    /// Add a point light to the scene.
+    ///
+    /// Point lights brighten flat and Gouraud shading on top of the directional
+    /// light. Per-pixel Phong lighting still uses the directional light only.
+    pub fn add_point_light(&mut self, light: PointLight) {
+        self.point_lights.push(light);
+    }
+
+    pub fn point_lights(&self) -> &[PointLight] {
+        &self.point_lights
+    }
+
+    pub fn point_lights_mut(&mut self) -> &mut Vec<PointLight> {
+        &mut self.point_lights
+    }
+
+    /// Queue a full-screen effect, run by [`Engine::render`] after the scene is drawn.
+    ///
+    /// Effects run in the order they were added, before the debug overlay.
+    pub fn add_post_process(&mut self, effect: impl PostProcess + 'static) {
+        self.post_processes.push(Box::new(effect));
+    }
+
+    pub fn post_processes(&self) -> &[Box<dyn PostProcess>] {
+        &self.post_processes
+    }
+
+    pub fn post_processes_mut(&mut self) -> &mut Vec<Box<dyn PostProcess>> {
+        &mut self.post_processes
+    }
+
+    /// Lighting factor at a world-space surface point, at most 1.
+    ///
+    /// The ambient level scaled by the material's coefficient, plus the
+    /// directional diffuse term and every point light's contribution.
+    fn light_intensity(&self, position: Vec3, normal: Vec3, ambient_coefficient: f32) -> f32 {
+        let directional = self.light.intensity(normal) * self.light.diffuse_strength;
+        self.point_lights
+            .iter()
+            .fold(
+                self.light.ambient_intensity * ambient_coefficient + directional,
+                |sum, light| sum + light.intensity_at(position, normal),
+            )
+            .min(1.0)
+    }
+
+    /// Set the global ambient light level, the brightness of surfaces no light reaches.
+    ///
+    /// Each material scales it by its own [`Material::ambient`](crate::mesh::Material::ambient) coefficient.
+    pub fn set_ambient(&mut self, level: f32) {
+        self.light.ambient_intensity = level.max(0.0);
+    }
+
+    pub fn ambient(&self) -> f32 {
+        self.light.ambient_intensity
+    }
+
+    /// Set the specular coefficients used by [`ShadingMode::Phong`].
+    ///
+    /// Ambient and diffuse come from the light; only `specular` and `shininess` are used.
+    pub fn set_phong_params(&mut self, params: PhongParams) {
+        self.phong_params = params;
+    }
+
+    pub fn phong_params(&self) -> PhongParams {
+        self.phong_params
+    }
+
+    /// Set how the mesh's pixels combine with what is already drawn.
+    ///
+    /// Blended modes read the depth buffer but don't write it. Alpha comes from the
+    /// texture or base color, so opaque sources blend as opaque.
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Sort triangles back to front by average view depth in [`Engine::update`].
+    ///
+    /// Off by default since the depth buffer resolves visibility on its own. Enable it
+    /// for translucent geometry, which needs the painter's algorithm to blend correctly.
+    pub fn set_depth_sort(&mut self, enabled: bool) {
+        self.depth_sort = enabled;
+    }
+
+    pub fn depth_sort(&self) -> bool {
+        self.depth_sort
+    }
+
+    /// Draw opaque triangles to the depth buffer first, then shade only the visible fragments.
+    ///
+    /// Rasterizes opaque geometry twice but runs the pixel shaders once per
+    /// covered pixel, which pays off for heavy overdraw or expensive shading
+    /// (Phong, textures). Blended triangles are drawn in the shading pass only.
+    pub fn set_depth_prepass(&mut self, enabled: bool) {
+        self.depth_prepass = enabled;
+    }
+
+    pub fn depth_prepass(&self) -> bool {
+        self.depth_prepass
+    }
+
+    /// Number of fragments shaded while filling triangles in the last [`Engine::render`].
+    ///
+    /// Wireframes, vertex markers and overlays are not counted.
+    pub fn shaded_fragments(&self) -> usize {
+        self.stats.pixels_shaded
+    }
+
+    /// Triangle and pixel counts from the last [`Engine::update`] and [`Engine::render`].
+    pub fn last_frame_stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// Points the light in a new direction, keeping its ambient and diffuse settings.
     pub fn set_light_direction(&mut self, direction: Vec3) {
-        self.light = DirectionalLight::new(direction);
+        self.light.direction = direction.normalize();
     }
 
     pub fn light_direction(&self) -> Vec3 {
         self.light.direction
     }
 
+    /// The first mesh in the scene (the one `load_mesh` replaces).
+    ///
+    /// # Panics
+    /// Panics if every mesh has been removed.
     pub fn mesh_mut(&mut self) -> &mut Mesh {
-        &mut self.mesh
+        &mut self.meshes.first_mut().expect("scene has no meshes").1
     }
 
+    /// The first mesh in the scene (the one `load_mesh` replaces).
+    ///
+    /// # Panics
+    /// Panics if every mesh has been removed.
     pub fn mesh(&self) -> &Mesh {
-        &self.mesh
+        &self.meshes.first().expect("scene has no meshes").1
+    }
+
+    /// Spin the first mesh at `velocity` radians per second about each axis.
+    ///
+    /// [`update`](Self::update) advances the rotation by `velocity * delta_time`.
+    /// The velocity belongs to the engine, so it carries over to meshes loaded
+    /// later. Pass [`Vec3::ZERO`] to stop; manual `rotation_mut` edits still work.
+    pub fn set_mesh_angular_velocity(&mut self, velocity: Vec3) {
+        self.mesh_angular_velocity = velocity;
+    }
+
+    pub fn mesh_angular_velocity(&self) -> Vec3 {
+        self.mesh_angular_velocity
+    }
+
+    /// The last rendered frame, for reading pixels back without copying.
+    pub fn image(&self) -> ImageView<'_> {
+        self.renderer.image()
+    }
+
+    /// The ARGB8888 color of the last rendered frame at (`x`, `y`).
+    ///
+    /// ```
+    /// use russsty::{Engine, Mesh};
+    ///
+    /// let background = 0xFF000000;
+    /// let mut engine = Engine::builder()
+    ///     .with_size(64, 48)
+    ///     .with_clear_color(background)
+    ///     .with_grid(false)
+    ///     .build();
+    /// *engine.mesh_mut() = Mesh::sphere(1.0, 8, 16);
+    /// engine.update(0.0);
+    /// engine.render();
+    ///
+    /// assert_ne!(engine.pixel(32, 24), background);
+    /// assert_eq!(engine.pixel(0, 0), background);
+    /// assert_eq!(engine.image().width(), 64);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if (`x`, `y`) is outside the frame.
+    pub fn pixel(&self, x: u32, y: u32) -> u32 {
+        let image = self.image();
+        image.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "pixel ({x}, {y}) is outside the {}x{} frame",
+                image.width(),
+                image.height()
+            )
+        })
+    }
+
+    /// Save the last rendered frame as a PNG file.
+    pub fn save_screenshot<P: AsRef<std::path::Path>>(
+        &self,
+        path: P,
+    ) -> Result<(), image::ImageError> {
+        self.renderer.write_png(path)
+    }
+
+    /// Save the last rendered frame as a binary PPM file, without any image codec.
+    pub fn save_ppm<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        self.renderer.write_ppm(path)
+    }
+
+    /// Update and render a frame, returning it as an owned RGBA image.
+    ///
+    /// Needs no window, so it suits server-side thumbnails. The result is an
+    /// [`image::RgbaImage`]: save it with any `image` codec, or take the raw
+    /// RGBA8 bytes with `into_raw()`.
+    pub fn render_to_image(&mut self) -> image::RgbaImage {
+        self.update(0.0);
+        self.render();
+        image::RgbaImage::from_raw(
+            self.renderer.output_width(),
+            self.renderer.output_height(),
+            self.renderer.to_rgba8(),
+        )
+        .expect("frame size matches the output dimensions")
+    }
+
+    /// 64-bit FNV-1a hash of the last rendered frame's ARGB8888 `u32` pixels.
+    ///
+    /// Lets regression tests assert a scene renders identically without storing
+    /// images. Pixels are hashed as little-endian bytes, so the value is portable.
+    pub fn frame_hash(&self) -> u64 {
+        self.renderer.frame_hash()
+    }
+
+    /// The region of the frame changed since the last call, in output pixels.
+    ///
+    /// Lets a window upload and present only that part of [`Engine::frame_buffer`].
+    /// `None` means nothing changed.
+    pub fn take_dirty_rect(&mut self) -> Option<Rect> {
+        self.renderer.take_dirty_rect()
     }
 
-    /// Returns the rendered frame as bytes (ARGB8888 format)
+    /// Returns the front buffer as bytes (ARGB8888 format), downsampled when supersampling.
+    ///
+    /// [`Engine::render`] draws into a back buffer; this only shows the new frame
+    /// after [`Engine::swap_buffers`], so it never exposes a half-drawn frame.
     pub fn frame_buffer(&self) -> &[u8] {
         self.renderer.as_bytes()
     }
 
+    /// Make the frame last drawn by [`Engine::render`] the one [`Engine::frame_buffer`] returns.
+    pub fn swap_buffers(&mut self) {
+        self.renderer.swap_buffers();
+    }
+
     pub fn set_texture(&mut self, texture: Texture) {
         self.texture = Some(texture);
     }
@@ -205,48 +1168,176 @@ impl Engine {
         self.texture_mode
     }
 
-    /// Update the engine state - transforms vertices and builds triangles to render.
-    pub fn update(&mut self) {
-        let faces = self.mesh.faces().to_vec();
-        let vertices = self.mesh.vertices().to_vec();
-        let rotation = self.mesh.rotation();
-        let translation = self.mesh.translation();
-        let scale = self.mesh().scale();
-        let buffer_width = self.renderer.width();
-        let buffer_height = self.renderer.height();
-        let camera_position = self.camera.position();
-        let view_matrix = self.camera.view_matrix();
-        let view_projection = self.projection_matrix * view_matrix;
-        let backface_culling = self.backface_culling;
-        let shading_mode = self.shading_mode;
+    /// Set a tangent-space normal map for [`ShadingMode::Phong`].
+    ///
+    /// Untextured Phong triangles then light each pixel with the normal sampled
+    /// at its UV, bent by the mesh's tangents. See [`NormalMappedShader`] for
+    /// the encoding.
+    pub fn set_normal_map(&mut self, normal_map: Texture) {
+        self.normal_map = Some(normal_map);
+    }
 
-        let mut triangles = Vec::new();
+    pub fn clear_normal_map(&mut self) {
+        self.normal_map = None;
+    }
 
-        // Full world matrix for positions
-        let world_matrix = Mat4::translation(translation.x, translation.y, translation.z)
-            * Mat4::rotation_x(rotation.x)
-            * Mat4::rotation_y(rotation.y)
-            * Mat4::rotation_z(rotation.z)
-            * Mat4::scaling(scale.x, scale.y, scale.z);
+    pub fn normal_map(&self) -> Option<&Texture> {
+        self.normal_map.as_ref()
+    }
 
-        // Normal matrix = inverse transpose of model matrix (without translation)
-        // This correctly handles non-uniform scaling
-        let model_matrix = Mat4::rotation_x(rotation.x)
-            * Mat4::rotation_y(rotation.y)
-            * Mat4::rotation_z(rotation.z)
-            * Mat4::scaling(scale.x, scale.y, scale.z);
+    /// Update the engine state - transforms vertices and builds triangles to render.
+    ///
+    /// `delta_time` is the time in seconds since the last update and drives the
+    /// mesh's [angular velocity](Self::set_mesh_angular_velocity); pass `0.0` to
+    /// rebuild the triangles without animating.
+    ///
+    /// Triangles from every mesh in the scene are collected into one list, so depth
+    /// sorting orders them together.
+    pub fn update(&mut self, delta_time: f32) {
+        let velocity = self.mesh_angular_velocity;
+        if let Some((_, mesh)) = self.meshes.first_mut() {
+            let rotation = mesh.rotation_mut();
+            *rotation = *rotation + velocity * delta_time;
+        }
 
-        let normal_matrix = model_matrix
-            .inverse()
-            .unwrap_or(Mat4::identity())
-            .transpose();
+        // Reuse last frame's allocations; meshes are borrowed, never copied
+        let mut triangles = std::mem::take(&mut self.triangles_to_render);
+        triangles.clear();
+        let mut stats = RenderStats::default();
+        for (_, mesh) in &self.meshes {
+            self.append_mesh_triangles(mesh, &mut triangles, &mut stats);
+        }
+        stats.triangles_drawn = triangles.len();
+        self.stats = stats;
 
-        for face in faces.iter() {
-            let face_vertices = [
-                vertices[face.a as usize],
-                vertices[face.b as usize],
-                vertices[face.c as usize],
-            ];
+        let mut silhouette_lines = std::mem::take(&mut self.silhouette_lines);
+        let mut front_facing = std::mem::take(&mut self.front_facing);
+        silhouette_lines.clear();
+        if self.silhouette_edges {
+            for (_, mesh) in &self.meshes {
+                self.append_silhouette_lines(mesh, &mut silhouette_lines, &mut front_facing);
+            }
+        }
+        self.silhouette_lines = silhouette_lines;
+        self.front_facing = front_facing;
+
+        // The depth buffer handles hidden surface removal; sorting is only needed
+        // when later triangles must draw over earlier ones (blending, painter's algorithm).
+        if self.depth_sort {
+            // Back to front: furthest first. Stable, so ties keep mesh order.
+            triangles.sort_by(|a, b| b.avg_depth.total_cmp(&a.avg_depth));
+        }
+        self.triangles_to_render = triangles;
+    }
+
+    /// Whether a world-space face normal at `point` on the face points away from the camera.
+    fn faces_away(&self, normal: Vec3, point: Vec3) -> bool {
+        // Orthographic view rays are all parallel to the camera's forward axis
+        let camera_ray = if self.projection_mode == ProjectionMode::Orthographic {
+            -self.camera.forward()
+        } else {
+            self.camera.position() - point
+        };
+        normal.dot(camera_ray) < 0.0
+    }
+
+    /// Project one mesh's silhouette edges, appending screen-space lines.
+    ///
+    /// `front_facing` is scratch space, overwritten with each face's facing.
+    fn append_silhouette_lines(
+        &self,
+        mesh: &Mesh,
+        lines: &mut Vec<(Vec3, Vec3, u32)>,
+        front_facing: &mut Vec<bool>,
+    ) {
+        let view_projection = self.projection_matrix * self.camera.view_matrix();
+        let (center, radius) = mesh.world_bounding_sphere();
+        if sphere_outside_frustum(&view_projection, center, radius) {
+            return;
+        }
+        let (width, height) = (self.renderer.width(), self.renderer.height());
+        let orthographic = self.projection_mode == ProjectionMode::Orthographic;
+        let color = mesh.wireframe_color().unwrap_or(self.wireframe_color);
+        let world_matrix = mesh.model_matrix();
+        let normal_matrix = mesh.normal_matrix();
+        let vertices = mesh.vertices();
+        let world = |i: u32| world_matrix * vertices[i as usize].position;
+
+        front_facing.clear();
+        front_facing.extend(mesh.faces().iter().enumerate().map(|(index, face)| {
+            !self.faces_away(normal_matrix * mesh.face_normal(index), world(face.a))
+        }));
+
+        for edge in mesh.edges() {
+            let [first, second] = edge.faces.map(|face| front_facing[face as usize]);
+            let silhouette = if edge.shared { first != second } else { first };
+            if !silhouette {
+                continue;
+            }
+            let to_clip = |i: u32| view_projection * Vec4::from_vec3(world(i), 1.0);
+            if let Some((a, b)) = clip_line(to_clip(edge.a), to_clip(edge.b)) {
+                lines.push((
+                    clip_to_screen(a, width, height, orthographic),
+                    clip_to_screen(b, width, height, orthographic),
+                    color,
+                ));
+            }
+        }
+    }
+
+    /// Transform, cull, light and clip one mesh, appending its screen-space triangles.
+    ///
+    /// Counts the mesh's faces into `stats`; the caller counts the drawn triangles.
+    fn append_mesh_triangles(
+        &self,
+        mesh: &Mesh,
+        triangles: &mut Vec<Triangle>,
+        stats: &mut RenderStats,
+    ) {
+        let vertices = mesh.vertices();
+        let mesh_colors = mesh.vertex_colors();
+        let mesh_tangents = mesh.tangents();
+        let wireframe_color = mesh.wireframe_color().unwrap_or(self.wireframe_color);
+        let buffer_width = self.renderer.width();
+        let buffer_height = self.renderer.height();
+        let view_matrix = self.camera.view_matrix();
+        let view_projection = self.projection_matrix * view_matrix;
+
+        // Skip the whole mesh when its bounds lie entirely outside the view volume
+        stats.triangles_submitted += mesh.faces().len();
+        let (center, radius) = mesh.world_bounding_sphere();
+        if sphere_outside_frustum(&view_projection, center, radius) {
+            stats.triangles_culled += mesh.faces().len();
+            return;
+        }
+
+        let cull_fill = self.backface_culling;
+        let cull_wireframe = self.wireframe_culls_back_faces();
+        let shading_mode = self.shading_mode;
+        let normal_mapped = shading_mode == ShadingMode::Phong && self.normal_map.is_some();
+        let orthographic = self.projection_mode == ProjectionMode::Orthographic;
+        let modulate = if self.gamma_correct {
+            colors::modulate_linear
+        } else {
+            colors::modulate
+        };
+
+        // Full world matrix for positions (scale -> rotate -> translate)
+        let world_matrix = mesh.model_matrix();
+
+        // Inverse transpose of the linear part, so non-uniform scale keeps normals perpendicular
+        let normal_matrix = mesh.normal_matrix();
+
+        // View-space depth range of the mesh's bounds, for the depth heatmap
+        let center_depth = (view_matrix * center).z;
+        let (nearest, depth_range) = (center_depth - radius, 2.0 * radius);
+
+        for (face_index, face) in mesh.faces().iter().enumerate() {
+            let face_vertices = [
+                vertices[face.a as usize],
+                vertices[face.b as usize],
+                vertices[face.c as usize],
+            ];
 
             let face_texcoords = [
                 face_vertices[0].texel,
@@ -261,26 +1352,61 @@ impl Engine {
                 world_matrix * face_vertices[2].position,
             ];
 
-            // Calculate face normal (needed for backface culling)
-            let vec_ab = transformed_positions[1] - transformed_positions[0];
-            let vec_ac = transformed_positions[2] - transformed_positions[0];
-            let face_normal = vec_ab.cross(vec_ac);
+            // Cached model-space face normal rotated into world space, once per face
+            // and shared by backface culling and flat shading
+            let face_normal = normal_matrix * mesh.face_normal(face_index);
 
-            // Apply backface culling
-            if backface_culling {
-                let camera_ray = camera_position - transformed_positions[0];
-                if face_normal.dot(camera_ray) < 0.0 {
-                    continue;
-                }
+            // Apply backface culling. Back faces culled from only one of fill and
+            // wireframe are kept and skipped by the other in `render`.
+            let back_facing = (cull_fill || cull_wireframe)
+                && self.faces_away(face_normal, transformed_positions[0]);
+            if back_facing && cull_fill && cull_wireframe {
+                stats.triangles_culled += 1;
+                continue;
             }
+            let wireframe_color = if back_facing {
+                colors::modulate(wireframe_color, BACK_FACE_WIREFRAME_INTENSITY)
+            } else {
+                wireframe_color
+            };
 
             // Calculate colors based on shading mode
             // Use white for textured modulate mode so lighting doesn't darken the texture
+            let material = mesh.material_for_face(face_index);
+            let ambient_coefficient = material.map_or(1.0, |material| material.ambient);
             let base_color = if self.texture_mode == TextureMode::Modulate {
                 0xFFFFFFFF // White - full brightness when lit
             } else {
-                colors::FILL
+                material.map_or(colors::FILL, |material| material.diffuse)
+            };
+            let base_color = match self.face_color_mode {
+                FaceColorMode::Uniform => base_color,
+                FaceColorMode::PerFace => face_hash_color(face_index),
+                FaceColorMode::DepthHeatmap => {
+                    let centroid = (transformed_positions[0]
+                        + transformed_positions[1]
+                        + transformed_positions[2])
+                        * (1.0 / 3.0);
+                    let t = if depth_range > 0.0 {
+                        ((view_matrix * centroid).z - nearest) / depth_range
+                    } else {
+                        0.0
+                    };
+                    // Hue sweeps from blue (240) through green to red (0)
+                    colors::hsv_to_rgb(240.0 * (1.0 - t.clamp(0.0, 1.0)), 1.0, 1.0)
+                }
             };
+            // World-space vertex normals; meshes without them fall back to the face normal
+            let world_normals: [Vec3; 3] = std::array::from_fn(|i| {
+                let world_normal = (normal_matrix * face_vertices[i].normal).normalize();
+                if world_normal == Vec3::ZERO {
+                    face_normal.normalize()
+                } else {
+                    world_normal
+                }
+            });
+
+            let corners = [face.a, face.b, face.c].map(|i| i as usize);
             let (flat_color, vertex_colors) = match shading_mode {
                 ShadingMode::None => {
                     // No lighting - use base color
@@ -288,86 +1414,348 @@ impl Engine {
                 }
                 ShadingMode::Flat => {
                     // Flat shading - one color per face based on face normal
-                    let normal = face_normal.normalize();
-                    let diffuse = self.light.intensity(normal) * self.light.diffuse_strength;
-                    let intensity = (diffuse + self.light.ambient_intensity).min(1.0);
-                    let color = colors::modulate(base_color, intensity);
+                    let center = (transformed_positions[0]
+                        + transformed_positions[1]
+                        + transformed_positions[2])
+                        * (1.0 / 3.0);
+                    let intensity = self.light_intensity(center, face_normal, ambient_coefficient);
+                    let color = modulate(base_color, intensity);
                     (color, [color, color, color])
                 }
-                ShadingMode::Gouraud => {
-                    // Gouraud shading - per-vertex lighting
-                    let mut vert_colors = [0u32; 3];
-                    for i in 0..3 {
-                        let world_normal = (normal_matrix * face_vertices[i].normal).normalize();
-                        let diffuse =
-                            self.light.intensity(world_normal) * self.light.diffuse_strength;
-                        let intensity = (diffuse + self.light.ambient_intensity).min(1.0);
-                        vert_colors[i] = colors::modulate(base_color, intensity);
-                    }
-                    let avg_color = vert_colors[0];
-                    (avg_color, vert_colors)
+                ShadingMode::Gouraud | ShadingMode::Phong => {
+                    // Gouraud shading - per-vertex lighting. Phong relights per pixel when
+                    // untextured, but textured Phong falls back to these vertex colors.
+                    // Authored vertex colors tint the material color (white leaves it as is)
+                    let vert_colors: [u32; 3] = std::array::from_fn(|i| {
+                        let tinted = colors::color_mul(base_color, mesh_colors[corners[i]]);
+                        let intensity = self.light_intensity(
+                            transformed_positions[i],
+                            world_normals[i],
+                            ambient_coefficient,
+                        );
+                        modulate(tinted, intensity)
+                    });
+                    // Phong keeps the unlit base color for its per-pixel lighting
+                    let color = if shading_mode == ShadingMode::Phong {
+                        base_color
+                    } else {
+                        vert_colors[0]
+                    };
+                    (color, vert_colors)
                 }
             };
 
-            // Projected vertices will store screen space coordinates where (x, y) represents the pixel coordinates and z represents the original depth value in world space.
-            let mut projected_vertices = Vec::new();
-            for vertex in &transformed_positions {
-                // Transform to clip space: view_projection = projection * view
-                let clip_space_vertex =
-                    view_projection * Vec4::new(vertex.x, vertex.y, vertex.z, 1.0);
-
-                // w <= 0 means vertex is behind or on the near plane.
-                if clip_space_vertex.w <= 0.0 {
-                    continue;
+            // Tangents follow the surface like positions do; only normal mapping needs them
+            let world_tangents: [Vec4; 3] = std::array::from_fn(|i| {
+                let tangent = mesh_tangents[corners[i]];
+                if !normal_mapped {
+                    return tangent;
                 }
+                let direction = (world_matrix * Vec4 { w: 0.0, ..tangent })
+                    .xyz()
+                    .normalize();
+                Vec4::new(direction.x, direction.y, direction.z, tangent.w)
+            });
+
+            // Transform to clip space: view_projection = projection * view
+            let clip_vertices: [ClipVertex; 3] = std::array::from_fn(|i| {
+                let p = transformed_positions[i];
+                ClipVertex::new(
+                    view_projection * Vec4::new(p.x, p.y, p.z, 1.0),
+                    face_texcoords[i],
+                    vertex_colors[i],
+                )
+                .with_world_attributes(p, world_normals[i])
+                .with_tangent(world_tangents[i])
+            });
 
-                // NDC coordinates should now be normalized to the range [-1, 1]
-                let ndc_vertex = Vec3::new(
-                    clip_space_vertex.x / clip_space_vertex.w,
-                    clip_space_vertex.y / clip_space_vertex.w,
-                    clip_space_vertex.z / clip_space_vertex.w,
+            // Clip-space W is the view-space depth, so this stays correct as the camera moves.
+            // Orthographic W is always 1, but clip-space z grows with depth instead.
+            let avg_depth = clip_vertices
+                .iter()
+                .map(|v| {
+                    if orthographic {
+                        v.position.z
+                    } else {
+                        v.position.w
+                    }
+                })
+                .sum::<f32>()
+                / 3.0;
+
+            // Clip against the near and far planes so triangles crossing them are split rather than dropped
+            // Most faces lie between the planes; only clip (and allocate) the rest
+            let inside = clip_vertices
+                .iter()
+                .all(|v| v.near_distance() >= 0.0 && v.far_distance() >= 0.0);
+            let clipped_triangles = if inside {
+                Vec::new()
+            } else {
+                clip_triangle(clip_vertices)
+            };
+            let pieces = if inside {
+                std::slice::from_ref(&clip_vertices)
+            } else {
+                clipped_triangles.as_slice()
+            };
+            if pieces.is_empty() {
+                stats.triangles_culled += 1;
+            } else if !inside {
+                stats.triangles_clipped += 1;
+            }
+            for &clipped in pieces {
+                // Projected vertices store pixel coordinates and a depth value in z
+                let projected_vertices = clipped
+                    .map(|v| clip_to_screen(v.position, buffer_width, buffer_height, orthographic));
+
+                triangles.push(
+                    Triangle::new(
+                        projected_vertices,
+                        flat_color,
+                        clipped.map(|v| v.color),
+                        clipped.map(|v| v.texel),
+                        avg_depth,
+                        shading_mode,
+                        self.texture_mode,
+                    )
+                    .with_world_attributes(
+                        clipped.map(|v| v.world_position),
+                        clipped.map(|v| v.normal),
+                    )
+                    .with_tangents(clipped.map(|v| v.tangent))
+                    .with_blend_mode(self.blend_mode)
+                    .with_perspective_correct(!orthographic)
+                    .with_gamma_correct(self.gamma_correct)
+                    .with_wireframe_color(wireframe_color)
+                    .with_ambient_coefficient(ambient_coefficient)
+                    .with_back_facing(back_facing),
                 );
+            }
+        }
+    }
+
+    /// Project and draw the grid lines, axes first so they win where lines cross.
+    fn draw_world_grid(&mut self, grid: GridConfig) {
+        let view_projection = self.projection_matrix * self.camera.view_matrix();
+        let (width, height) = (self.renderer.width(), self.renderer.height());
+        let orthographic = self.projection_mode == ProjectionMode::Orthographic;
+        let extent = grid.spacing * grid.half_lines as f32;
 
-                let screen_x = (ndc_vertex.x + 1.0) * 0.5 * buffer_width as f32;
-                let screen_y = (1.0 - ndc_vertex.y) * 0.5 * buffer_height as f32;
-                projected_vertices.push(Vec3::new(screen_x, screen_y, clip_space_vertex.w));
+        let mut draw_line = |start: Vec3, end: Vec3, color: u32| {
+            let to_clip = |p: Vec3| view_projection * Vec4::from_vec3(p, 1.0);
+            if let Some((a, b)) = clip_line(to_clip(start), to_clip(end)) {
+                let a = clip_to_screen(a, width, height, orthographic);
+                let b = clip_to_screen(b, width, height, orthographic);
+                self.renderer.draw_line_bresenham(
+                    a.x as i32, a.y as i32, a.z, b.x as i32, b.y as i32, b.z, color,
+                );
             }
+        };
 
-            if projected_vertices.len() == 3 {
-                let avg_depth = (transformed_positions[0].z
-                    + transformed_positions[1].z
-                    + transformed_positions[2].z)
-                    / 3.0;
-
-                triangles.push(Triangle::new(
-                    [
-                        projected_vertices[0],
-                        projected_vertices[1],
-                        projected_vertices[2],
-                    ],
-                    flat_color,
-                    vertex_colors,
-                    face_texcoords,
-                    avg_depth,
-                    shading_mode,
-                    self.texture_mode,
-                ));
+        draw_line(
+            Vec3::new(-extent, 0.0, 0.0),
+            Vec3::new(extent, 0.0, 0.0),
+            grid.x_axis_color,
+        );
+        draw_line(
+            Vec3::new(0.0, 0.0, -extent),
+            Vec3::new(0.0, 0.0, extent),
+            grid.z_axis_color,
+        );
+        let half_lines = grid.half_lines as i32;
+        for i in (-half_lines..=half_lines).filter(|&i| i != 0) {
+            let offset = i as f32 * grid.spacing;
+            draw_line(
+                Vec3::new(offset, 0.0, -extent),
+                Vec3::new(offset, 0.0, extent),
+                grid.color,
+            );
+            draw_line(
+                Vec3::new(-extent, 0.0, offset),
+                Vec3::new(extent, 0.0, offset),
+                grid.color,
+            );
+        }
+    }
+
+    /// Fill the triangles from the last update, fogging them if enabled.
+    fn fill_triangles(&mut self) {
+        let cull_fill = self.backface_culling;
+        let filled = || {
+            self.triangles_to_render
+                .iter()
+                .filter(move |triangle| !(cull_fill && triangle.back_facing))
+        };
+        for triangle in filled() {
+            self.renderer.mark_dirty(screen_bounds(triangle.points));
+        }
+        let fog = self.fog.map(|fog| {
+            // Stored depth is clip W under perspective. Orthographic stores
+            // 1 / (2 - NDC z), so there view depth maps to 3 - 2 * (depth - near) / range
+            if self.projection_mode == ProjectionMode::Orthographic {
+                let range = self.z_far - self.z_near;
+                let stored = |depth: f32| 3.0 - 2.0 * (depth - self.z_near) / range;
+                DepthFog {
+                    color: fog.color,
+                    start: stored(fog.near),
+                    end: stored(fog.far),
+                }
+            } else {
+                DepthFog {
+                    color: fog.color,
+                    start: fog.near,
+                    end: fog.far,
+                }
+            }
+        });
+        let mut fb = self.renderer.as_framebuffer();
+        fb.set_fog(fog);
+        if self.depth_prepass {
+            fb.set_depth_pass(DepthPass::DepthOnly);
+            for triangle in filled() {
+                if triangle.blend_mode == BlendMode::Opaque {
+                    self.rasterizer
+                        .fill_triangle(triangle, &mut fb, triangle.color, None);
+                }
             }
+            fb.set_depth_pass(DepthPass::Equal);
         }
+        for triangle in filled() {
+            let textured = triangle.texture_mode != TextureMode::None && self.texture.is_some();
+            if triangle.shading_mode == ShadingMode::Phong && !textured {
+                let params = PhongParams {
+                    ambient: self.light.ambient_intensity * triangle.ambient_coefficient,
+                    diffuse: self.light.diffuse_strength,
+                    ..self.phong_params
+                };
+                let shader = PhongShader::new(
+                    triangle.color,
+                    triangle.world_positions,
+                    triangle.normals,
+                    PhongLight::Directional(self.light.direction),
+                    self.camera.position(),
+                    params,
+                )
+                .with_gamma_correct(triangle.gamma_correct);
+                fb.set_blend_mode(triangle.blend_mode);
+                if let Some(normal_map) = &self.normal_map {
+                    let shader = NormalMappedShader::new(
+                        shader,
+                        normal_map,
+                        triangle.texture_coords,
+                        triangle.tangents,
+                    );
+                    let shader = if triangle.perspective_correct {
+                        shader.with_perspective_correct(triangle.points)
+                    } else {
+                        shader
+                    };
+                    self.rasterizer
+                        .fill_triangle_with_shader(triangle.points, &mut fb, &shader);
+                } else {
+                    self.rasterizer
+                        .fill_triangle_with_shader(triangle.points, &mut fb, &shader);
+                }
+                continue;
+            }
+            self.rasterizer
+                .fill_triangle(triangle, &mut fb, triangle.color, self.texture.as_ref());
+        }
+        self.stats.pixels_shaded = fb.shaded_fragments();
+    }
 
-        // No sorting needed - depth buffer handles hidden surface removal
-        self.triangles_to_render = triangles;
+    /// Draw wireframe edges, or only silhouette edges if enabled.
+    fn draw_edges(&mut self) {
+        // Overlay sizes are in output pixels
+        let scale = self.renderer.supersampling() as i32;
+        if self.silhouette_edges {
+            for &(a, b, color) in &self.silhouette_lines {
+                if self.wireframe_width > 1.0 {
+                    let width = self.wireframe_width * scale as f32;
+                    self.renderer.draw_line_thick_with_depth(a, b, width, color);
+                } else if self.antialiased_lines {
+                    self.renderer.draw_line_aa(a, b, color);
+                } else {
+                    self.renderer.draw_line_bresenham(
+                        a.x as i32, a.y as i32, a.z, b.x as i32, b.y as i32, b.z, color,
+                    );
+                }
+            }
+            return;
+        }
+
+        for triangle in &self.triangles_to_render {
+            if triangle.back_facing && self.wireframe_culls_back_faces() {
+                continue;
+            }
+            if self.wireframe_width > 1.0 {
+                self.renderer.draw_triangle_wireframe_thick(
+                    triangle,
+                    self.wireframe_width * scale as f32,
+                    triangle.wireframe_color,
+                );
+            } else if self.antialiased_lines {
+                self.renderer
+                    .draw_triangle_wireframe_aa(triangle, triangle.wireframe_color);
+            } else {
+                self.renderer
+                    .draw_triangle_wireframe(triangle, triangle.wireframe_color);
+            }
+        }
+    }
+
+    /// Draw a marker on every vertex of the triangles whose edges are drawn.
+    fn draw_vertex_markers(&mut self) {
+        // Overlay sizes are in output pixels
+        let scale = self.renderer.supersampling() as i32;
+        let VertexMarker { size, color, shape } = self.vertex_marker;
+        let size = size as i32 * scale;
+        for triangle in &self.triangles_to_render {
+            if triangle.back_facing && self.wireframe_culls_back_faces() {
+                continue;
+            }
+            for vertex in &triangle.points {
+                match shape {
+                    VertexMarkerShape::Square => {
+                        let half = size as f32 / 2.0;
+                        self.renderer.draw_rect(
+                            (vertex.x - half).round() as i32,
+                            (vertex.y - half).round() as i32,
+                            size,
+                            size,
+                            color,
+                        );
+                    }
+                    VertexMarkerShape::Circle => {
+                        self.renderer
+                            .fill_circle(vertex.x, vertex.y, size as f32 / 2.0, color);
+                    }
+                }
+            }
+        }
     }
 
     /// Render the current frame
     pub fn render(&mut self) {
-        self.renderer.clear(colors::BACKGROUND);
-        self.renderer.clear_depth();
+        // Overlay sizes are in output pixels
+        let scale = self.renderer.supersampling() as i32;
 
-        if self.draw_grid {
-            self.renderer.draw_grid(50, colors::GRID);
+        // The screen grid is background; the world grid is drawn like geometry
+        let screen_grid = self.draw_grid && self.grid.is_none();
+        let background = (self.clear_color, screen_grid);
+        if self.incremental_clear && self.cleared_background == Some(background) {
+            let cleared = self.renderer.clear_touched(self.clear_color);
+            if let (Some(region), true) = (cleared, screen_grid) {
+                self.renderer.draw_grid_in(50 * scale, colors::GRID, region);
+            }
+        } else {
+            self.renderer.clear(self.clear_color);
+            self.renderer.clear_depth(0.0);
+            if screen_grid {
+                self.renderer.draw_grid(50 * scale, colors::GRID);
+            }
+            self.cleared_background = Some(background);
         }
-
         // Determine what to draw based on render mode
         let (draw_filled, draw_wireframe, draw_vertices) = match self.render_mode {
             RenderMode::Wireframe => (false, true, false),
@@ -377,31 +1765,1307 @@ impl Engine {
             RenderMode::Filled => (true, false, false),
         };
 
-        // Fill triangles first (requires framebuffer borrow)
+        // Edges under the fill are drawn first and their depth discarded, so the
+        // fill covers (or blends over) them wherever it is drawn
+        let edges_under_fill = draw_wireframe && draw_filled && !self.wireframe_over_fill;
+        if edges_under_fill {
+            self.draw_edges();
+            self.renderer.clear_depth(0.0);
+        }
+        if let (true, Some(grid)) = (self.draw_grid, self.grid) {
+            self.draw_world_grid(grid);
+        }
         if draw_filled {
-            let mut fb = self.renderer.as_framebuffer();
-            for triangle in &self.triangles_to_render {
-                self.rasterizer.fill_triangle(
-                    triangle,
-                    &mut fb,
-                    triangle.color,
-                    self.texture.as_ref(),
-                );
+            self.fill_triangles();
+        } else {
+            self.stats.pixels_shaded = 0;
+        }
+        if draw_wireframe && !edges_under_fill {
+            self.draw_edges();
+        }
+        // Vertex markers always go on top, whichever side of the fill the edges are
+        if draw_vertices {
+            self.draw_vertex_markers();
+        }
+
+        for effect in &self.post_processes {
+            self.renderer.apply_post_process(effect.as_ref());
+        }
+
+        if self.draw_overlay {
+            let text = self.overlay_lines();
+            self.renderer
+                .draw_text_scaled(8 * scale, 8 * scale, &text, colors::TEXT, scale);
+        }
+
+        self.renderer.resolve();
+    }
+}
+
+/// Map a clip-space position to screen space for the rasterizers.
+///
+/// x and y become pixel coordinates, and z keeps the clip-space W for depth
+/// testing. Rasterizers interpolate 1/z, which must fall linearly with depth under
+/// orthographic projection, so there z holds 1 / (2 - NDC z) instead. Geometry so
+/// far past the far plane that NDC z reaches 2 then fails every depth test.
+fn clip_to_screen(clip: Vec4, width: u32, height: u32, orthographic: bool) -> Vec3 {
+    // NDC coordinates are now normalized to the range [-1, 1]
+    let ndc = clip.perspective_divide();
+
+    let screen_x = (ndc.x + 1.0) * 0.5 * width as f32;
+    let screen_y = (1.0 - ndc.y) * 0.5 * height as f32;
+    let depth = if orthographic {
+        1.0 / (2.0 - ndc.z)
+    } else {
+        clip.w
+    };
+    Vec3::new(screen_x, screen_y, depth)
+}
+
+/// A stable, saturated color for a face index, for [`FaceColorMode::PerFace`].
+///
+/// Fibonacci hashing spreads consecutive indices far apart on the hue circle.
+fn face_hash_color(face_index: usize) -> u32 {
+    let hash = (face_index as u32).wrapping_mul(0x9E37_79B9);
+    let hue = (hash >> 8) as f32 / (1u32 << 24) as f32 * 360.0;
+    colors::hsv_to_rgb(hue, 0.7, 1.0)
+}
+
+/// Whether a world-space sphere lies entirely outside one of the six frustum planes.
+///
+/// Each plane is a sum or difference of the view-projection's last row with
+/// one of the others (Gribb-Hartmann), so this works for both projections.
+/// The test is conservative: spheres near a frustum corner may pass even though
+/// nothing is visible, but visible spheres are never rejected.
+fn sphere_outside_frustum(view_projection: &Mat4, center: Vec3, radius: f32) -> bool {
+    let row = |r: usize| {
+        Vec4::new(
+            view_projection.get(r, 0),
+            view_projection.get(r, 1),
+            view_projection.get(r, 2),
+            view_projection.get(r, 3),
+        )
+    };
+    let w = row(3);
+    let point = Vec4::new(center.x, center.y, center.z, 1.0);
+    [0, 1, 2].into_iter().any(|axis| {
+        let r = row(axis);
+        [w + r, w - r].into_iter().any(|plane| {
+            let normal_length = Vec3::new(plane.x, plane.y, plane.z).magnitude();
+            plane.dot(point) < -radius * normal_length
+        })
+    })
+}
+
+/// Pixel bounding box of a screen-space triangle.
+fn screen_bounds(points: [Vec3; 3]) -> Rect {
+    let [a, b, c] = points;
+    // Pixels are filled where their centers fall inside, so only the pixels
+    // whose centers lie within the vertex bounds can be touched
+    let left = (a.x.min(b.x).min(c.x) - 0.5).ceil() as i32;
+    let top = (a.y.min(b.y).min(c.y) - 0.5).ceil() as i32;
+    let right = (a.x.max(b.x).max(c.x) - 0.5).floor() as i32;
+    let bottom = (a.y.max(b.y).max(c.y) - 0.5).floor() as i32;
+    Rect::new(
+        left,
+        top,
+        (right - left + 1).max(0) as u32,
+        (bottom - top + 1).max(0) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::{Face, Vertex};
+    use crate::prelude::Vec2;
+    use crate::render::renderer::DEFAULT_WIREFRAME_DEPTH_BIAS;
+
+    fn vertex(position: Vec3, normal: Vec3) -> Vertex {
+        Vertex {
+            position,
+            normal,
+            texel: Vec2::ZERO,
+        }
+    }
+
+    /// A single triangle at z = 0 facing the default camera at (0, 0, -5).
+    fn facing_triangle(normals: [Vec3; 3]) -> Mesh {
+        Mesh::new(
+            vec![
+                vertex(Vec3::new(-1.0, -1.0, 0.0), normals[0]),
+                vertex(Vec3::new(0.0, 1.0, 0.0), normals[1]),
+                vertex(Vec3::new(1.0, -1.0, 0.0), normals[2]),
+            ],
+            vec![Face::new(0, 1, 2)],
+            Vec3::ZERO,
+            Vec3::ONE,
+            Vec3::ZERO,
+        )
+    }
+
+    #[test]
+    fn gouraud_lights_each_vertex_from_its_normal() {
+        let normals = [
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(-1.0, 0.0, -1.0).normalize(),
+            Vec3::new(0.0, 0.0, 1.0),
+        ];
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = facing_triangle(normals);
+        engine.set_light_direction(Vec3::new(0.0, 0.0, 1.0));
+        engine.set_shading_mode(ShadingMode::Gouraud);
+        engine.update(0.0);
+
+        assert_eq!(engine.triangles_to_render.len(), 1);
+        let light = engine.light();
+        let expected = normals.map(|n| {
+            let diffuse = n.dot(-light.direction).max(0.0);
+            colors::modulate(colors::FILL, (diffuse + light.ambient_intensity).min(1.0))
+        });
+        assert_eq!(engine.triangles_to_render[0].vertex_colors, expected);
+    }
+
+    #[test]
+    fn point_light_brightens_nearby_vertices_most() {
+        let toward_camera = Vec3::new(0.0, 0.0, -1.0);
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = facing_triangle([toward_camera; 3]);
+        engine.set_shading_mode(ShadingMode::Gouraud);
+        // The directional light faces away, leaving only its ambient floor
+        engine.set_light(DirectionalLight::new(toward_camera));
+        // Just in front of vertex 0, with vertex 1 near the edge of its range
+        engine.add_point_light(PointLight::new(Vec3::new(-1.0, -1.0, -1.0), 1.0, 2.5));
+        engine.update(0.0);
+
+        let red = |color: u32| (color >> 16) & 0xFF;
+        let [near, edge, _] = engine.triangles_to_render[0].vertex_colors.map(red);
+        let ambient = red(colors::modulate(colors::FILL, 0.1));
+        assert!(near > edge && edge >= ambient, "{near} {edge} {ambient}");
+        assert!(near > 2 * ambient);
+    }
+
+    #[test]
+    fn faces_turned_from_the_light_keep_the_ambient_level() {
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+        // The face points at the camera, the light shines away from it
+        engine.set_light(DirectionalLight::new(Vec3::new(0.0, 0.0, -1.0)));
+        engine.set_ambient(0.3);
+        engine.set_shading_mode(ShadingMode::Flat);
+        engine.set_render_mode(RenderMode::Filled);
+        engine.update(0.0);
+        engine.render();
+
+        let ambient = colors::modulate(colors::FILL, 0.3);
+        assert_eq!(engine.triangles_to_render[0].color, ambient);
+        assert!(frame_colors(&engine).contains(&ambient));
+    }
+
+    #[test]
+    fn fog_fades_distant_surfaces_toward_its_color() {
+        let fog = FogConfig {
+            color: 0xFFFFFFFF,
+            near: 5.0,
+            far: 20.0,
+        };
+        // Brightness of the triangle's center pixel with the triangle pushed back by `z`
+        let center_brightness = |z: f32| {
+            let mut engine = Engine::builder().with_size(64, 64).with_grid(false).build();
+            *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+            *engine.mesh_mut().translation_mut() = Vec3::new(0.0, 0.0, z);
+            engine.set_shading_mode(ShadingMode::None);
+            engine.set_render_mode(RenderMode::Filled);
+            engine.set_fog(fog);
+            engine.update(0.0);
+            engine.render();
+            frame_pixels(&engine)[32 * 64 + 32] & 0xFF
+        };
+
+        // The near triangle sits at the fog's start, so it keeps its own color
+        let near = center_brightness(0.0);
+        let far = center_brightness(10.0);
+        assert_eq!(near, colors::FILL & 0xFF);
+        // Depth 15 is two thirds of the way into the fog
+        let expected = near + (255 - near) * 2 / 3;
+        assert!(far.abs_diff(expected) <= 1, "near {near} far {far}");
+    }
+
+    #[test]
+    fn fog_leaves_the_world_grid_alone() {
+        let grid = GridConfig::default();
+        let mut engine = Engine::new(96, 96);
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        engine.set_render_mode(RenderMode::Filled);
+        engine.set_grid(grid);
+        engine.set_camera_position(Vec3::new(0.0, 3.0, -5.0));
+        engine.camera_mut().look_at(Vec3::ZERO);
+        // Everything past the near plane is fully fogged
+        let fog_color = 0xFF123456;
+        engine.set_fog(FogConfig {
+            color: fog_color,
+            near: 0.0,
+            far: 0.01,
+        });
+        engine.update(0.0);
+        engine.render();
+
+        let pixels = frame_pixels(&engine);
+        assert!(pixels.contains(&fog_color));
+        assert!(pixels.contains(&grid.x_axis_color));
+        assert!(pixels.contains(&grid.color));
+    }
+
+    #[test]
+    fn gouraud_without_vertex_normals_uses_face_normal() {
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+        engine.set_light_direction(Vec3::new(0.0, 0.0, 1.0));
+        engine.set_shading_mode(ShadingMode::Gouraud);
+        engine.update(0.0);
+
+        // The face points straight back at the light, so it is fully lit
+        assert_eq!(
+            engine.triangles_to_render[0].vertex_colors,
+            [colors::FILL; 3]
+        );
+    }
+
+    #[test]
+    fn screenshot_round_trips_through_png() {
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+        engine.set_shading_mode(ShadingMode::None);
+        engine.draw_grid = false;
+        engine.set_render_mode(RenderMode::Filled);
+        engine.update(0.0);
+        engine.render();
+
+        // Unique per process, so concurrent test runs don't share the file
+        let path =
+            std::env::temp_dir().join(format!("russsty_screenshot_{}.png", std::process::id()));
+        engine.save_screenshot(&path).unwrap();
+        let image = image::open(&path);
+        std::fs::remove_file(&path).unwrap();
+        let image = image.unwrap().to_rgba8();
+
+        assert_eq!(image.dimensions(), (64, 64));
+        let [a, r, g, b] = colors::FILL.to_be_bytes();
+        assert_eq!(image.get_pixel(32, 32).0, [r, g, b, a]);
+        let [a, r, g, b] = colors::BACKGROUND.to_be_bytes();
+        assert_eq!(image.get_pixel(0, 0).0, [r, g, b, a]);
+    }
+
+    /// Distinct colors in the presented frame.
+    fn frame_colors(engine: &Engine) -> std::collections::HashSet<u32> {
+        frame_pixels(engine).into_iter().collect()
+    }
+
+    /// The last rendered frame as ARGB pixels.
+    fn frame_pixels(engine: &Engine) -> Vec<u32> {
+        engine
+            .renderer
+            .to_rgba8()
+            .chunks_exact(4)
+            .map(|rgba| u32::from_be_bytes([rgba[3], rgba[0], rgba[1], rgba[2]]))
+            .collect()
+    }
+
+    #[test]
+    fn supersampling_antialiases_edges() {
+        let render = |factor| {
+            let mut engine = Engine::new(32, 32);
+            engine.set_supersampling(factor);
+            *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+            engine.set_shading_mode(ShadingMode::None);
+            engine.draw_grid = false;
+            engine.set_render_mode(RenderMode::Filled);
+            engine.update(0.0);
+            engine.render();
+            assert_eq!(engine.frame_buffer().len(), 32 * 32 * 4);
+            frame_colors(&engine)
+        };
+
+        // 1x: every pixel is either inside or outside the diagonal edges
+        let aliased = render(1);
+        assert_eq!(aliased.len(), 2);
+
+        // 4x: edge pixels blend the fill and background colors
+        let smoothed = render(4);
+        assert!(smoothed.contains(&colors::FILL));
+        assert!(smoothed.contains(&colors::BACKGROUND));
+        assert!(smoothed.len() > 2, "no intermediate edge colors");
+    }
+
+    #[test]
+    fn depth_sort_orders_triangles_back_to_front() {
+        // Three stacked triangles submitted nearest first
+        let vertices = [0.0, 2.0, 4.0]
+            .iter()
+            .flat_map(|&z| {
+                [
+                    vertex(Vec3::new(-1.0, -1.0, z), Vec3::ZERO),
+                    vertex(Vec3::new(0.0, 1.0, z), Vec3::ZERO),
+                    vertex(Vec3::new(1.0, -1.0, z), Vec3::ZERO),
+                ]
+            })
+            .collect();
+        let faces = (0..3)
+            .map(|i| Face::new(i * 3, i * 3 + 1, i * 3 + 2))
+            .collect();
+
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = Mesh::new(vertices, faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO);
+        engine.set_depth_sort(true);
+        engine.update(0.0);
+
+        let depths: Vec<f32> = engine
+            .triangles_to_render
+            .iter()
+            .map(|t| t.avg_depth)
+            .collect();
+        assert_eq!(depths.len(), 3);
+        assert!(
+            depths.windows(2).all(|pair| pair[0] >= pair[1]),
+            "{:?}",
+            depths
+        );
+        assert!(depths[0] > depths[2]);
+    }
+
+    #[test]
+    fn wireframe_edges_draw_over_their_fill() {
+        let render = |mode, bias| {
+            let mut engine = Engine::new(64, 64);
+            *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+            engine.set_shading_mode(ShadingMode::None);
+            engine.draw_grid = false;
+            engine.set_render_mode(mode);
+            engine.set_wireframe_depth_bias(bias);
+            engine.update(0.0);
+            engine.render();
+            frame_pixels(&engine)
+        };
+
+        // Wireframe alone marks which pixels the edges cover
+        let edges: Vec<usize> = render(RenderMode::Wireframe, 0.0)
+            .iter()
+            .enumerate()
+            .filter(|(_, &color)| color == colors::WIREFRAME)
+            .map(|(i, _)| i)
+            .collect();
+        assert!(!edges.is_empty());
+
+        let biased = render(RenderMode::FilledWireframe, DEFAULT_WIREFRAME_DEPTH_BIAS);
+        assert!(edges.iter().all(|&i| biased[i] == colors::WIREFRAME));
+
+        // Without the bias the fill wins some of the depth ties
+        let unbiased = render(RenderMode::FilledWireframe, 0.0);
+        assert!(edges.iter().any(|&i| unbiased[i] == colors::FILL));
+    }
+
+    #[test]
+    fn translated_mesh_projects_to_shifted_screen_position() {
+        let screen_points = |translation: Vec3| {
+            let mut engine = Engine::new(64, 64);
+            *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+            *engine.mesh_mut().translation_mut() = translation;
+            engine.update(0.0);
+            assert_eq!(engine.triangles_to_render.len(), 1);
+            engine.triangles_to_render[0].points
+        };
+
+        let centered = screen_points(Vec3::ZERO);
+        let shifted = screen_points(Vec3::new(2.0, 0.0, 0.0));
+        for (before, after) in centered.iter().zip(&shifted) {
+            // +X is to the right on screen; same depth, so every vertex moves equally
+            assert!(after.x - before.x > 1.0);
+            assert!((after.x - before.x - (shifted[0].x - centered[0].x)).abs() < 1e-3);
+            assert!((after.y - before.y).abs() < 1e-4);
+            assert_eq!(after.z, before.z);
+        }
+    }
+
+    #[test]
+    fn every_mesh_in_the_scene_contributes_triangles() {
+        let mut engine = Engine::new(64, 64);
+        engine.backface_culling = false;
+
+        let mut left = facing_triangle([Vec3::ZERO; 3]);
+        *left.translation_mut() = Vec3::new(-1.5, 0.0, 0.0);
+        let mut right = facing_triangle([Vec3::ZERO; 3]);
+        *right.translation_mut() = Vec3::new(1.5, 0.0, 2.0);
+        let left_id = engine.add_mesh(left);
+        let right_id = engine.add_mesh(right);
+        assert_ne!(left_id, right_id);
+        assert_eq!(engine.mesh_count(), 3); // Plus the initial empty mesh
+
+        engine.update(0.0);
+        let centers: Vec<f32> = engine
+            .triangles_to_render
+            .iter()
+            .map(|t| t.centroid().x)
+            .collect();
+        assert_eq!(centers.len(), 2);
+        assert!(centers[0] < 32.0 && centers[1] > 32.0, "{:?}", centers);
+
+        // Removing a mesh drops its triangles; its id no longer resolves
+        assert!(engine.remove_mesh(left_id).is_some());
+        assert!(engine.mesh_by_id(left_id).is_none());
+        engine.update(0.0);
+        assert_eq!(engine.triangles_to_render.len(), 1);
+        assert!(engine.mesh_by_id_mut(right_id).is_some());
+    }
+
+    #[test]
+    fn meshes_outside_the_frustum_produce_no_triangles() {
+        let mut engine = Engine::new(64, 64);
+        engine.backface_culling = false;
+        let mut mesh = facing_triangle([Vec3::ZERO; 3]);
+        *mesh.translation_mut() = Vec3::new(100.0, 0.0, 0.0);
+        let id = engine.add_mesh(mesh);
+
+        engine.update(0.0);
+        assert!(engine.triangles_to_render.is_empty());
+
+        // Partly in view (the screen edge is near x = 2.07) keeps the whole mesh
+        let translate = |engine: &mut Engine, to: Vec3| {
+            *engine.mesh_by_id_mut(id).unwrap().translation_mut() = to;
+        };
+        translate(&mut engine, Vec3::new(2.5, 0.0, 0.0));
+        engine.update(0.0);
+        assert_eq!(engine.triangles_to_render.len(), 1);
+
+        // Behind the camera is culled too
+        translate(&mut engine, Vec3::new(0.0, 0.0, -20.0));
+        engine.update(0.0);
+        assert!(engine.triangles_to_render.is_empty());
+    }
+
+    #[test]
+    fn depth_prepass_shades_each_visible_pixel_once() {
+        // Eight stacked full-screen quads, drawn back to front so each one overwrites
+        let mut obj = String::new();
+        for layer in 0..8 {
+            let z = 8.0 - layer as f32;
+            for (x, y) in [(-9, -9), (9, -9), (9, 9), (-9, 9)] {
+                obj.push_str(&format!("v {x} {y} {z}\n"));
             }
+            let base = layer * 4;
+            obj.push_str(&format!("f {} {} {}\n", base + 1, base + 3, base + 2));
+            obj.push_str(&format!("f {} {} {}\n", base + 1, base + 4, base + 3));
         }
+        let mut engine = Engine::new(32, 32);
+        engine.draw_grid = false;
+        engine.set_render_mode(RenderMode::Filled);
+        engine.set_shading_mode(ShadingMode::Phong);
+        *engine.mesh_mut() = obj_mesh(&obj);
+        engine.update(0.0);
+        engine.render();
+        let single_pass = (engine.shaded_fragments(), frame_pixels(&engine));
 
-        // Wireframe and vertices (uses renderer methods)
-        for triangle in &self.triangles_to_render {
-            if draw_wireframe {
-                self.renderer
-                    .draw_triangle_wireframe(triangle, colors::WIREFRAME);
+        engine.set_depth_prepass(true);
+        engine.render();
+        assert_eq!(engine.shaded_fragments(), 32 * 32);
+        assert!(single_pass.0 >= 8 * 32 * 32, "{}", single_pass.0);
+        assert_eq!(frame_pixels(&engine), single_pass.1);
+    }
+
+    #[test]
+    fn orthographic_size_does_not_depend_on_depth() {
+        let screen_widths = |projection| {
+            let mut engine = Engine::new(64, 64);
+            engine.set_projection(projection);
+            let mut far = facing_triangle([Vec3::ZERO; 3]);
+            *far.translation_mut() = Vec3::new(0.0, 0.0, 5.0);
+            *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+            engine.add_mesh(far);
+            engine.update(0.0);
+
+            let widths: Vec<f32> = engine
+                .triangles_to_render
+                .iter()
+                .map(|t| t.points[2].x - t.points[0].x)
+                .collect();
+            assert_eq!(widths.len(), 2);
+            (widths[0], widths[1])
+        };
+
+        let (near, far) = screen_widths(ProjectionMode::Perspective);
+        assert!(far < near * 0.75, "perspective: {} vs {}", near, far);
+
+        let (near, far) = screen_widths(ProjectionMode::Orthographic);
+        assert!(
+            (near - far).abs() < 1e-4,
+            "orthographic: {} vs {}",
+            near,
+            far
+        );
+        // 2 world units across a 4 unit tall, square view
+        assert!((near - 32.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn orthographic_depth_test_keeps_the_nearer_triangle() {
+        // Flat shading tells the triangles apart: the far one is turned away from the light
+        let near = facing_triangle([Vec3::ZERO; 3]);
+        let mut far = facing_triangle([Vec3::ZERO; 3]);
+        *far.rotation_mut() = Vec3::new(0.0, 0.8, 0.0);
+        *far.translation_mut() = Vec3::new(0.0, 0.0, 3.0);
+
+        let center_color = |meshes: &[&Mesh]| {
+            let mut engine = Engine::new(64, 64);
+            engine.set_projection(ProjectionMode::Orthographic);
+            engine.set_render_mode(RenderMode::Filled);
+            engine.backface_culling = false;
+            engine.draw_grid = false;
+            for mesh in meshes {
+                engine.add_mesh((*mesh).clone());
             }
-            if draw_vertices {
-                for vertex in &triangle.points {
-                    self.renderer
-                        .draw_rect(vertex.x as i32, vertex.y as i32, 4, 4, colors::VERTEX);
+            engine.update(0.0);
+            engine.render();
+            frame_pixels(&engine)[36 * 64 + 32]
+        };
+
+        let near_color = center_color(&[&near]);
+        assert_ne!(near_color, center_color(&[&far]));
+        assert_eq!(center_color(&[&near, &far]), near_color);
+        assert_eq!(center_color(&[&far, &near]), near_color);
+    }
+
+    #[test]
+    fn empty_frame_is_filled_with_the_clear_color() {
+        let mut engine = Engine::new(32, 24);
+        engine.draw_grid = false;
+        assert_eq!(engine.clear_color(), colors::BACKGROUND);
+
+        engine.set_clear_color(0xFFFF0000);
+        engine.update(0.0);
+        engine.render();
+        assert!(frame_pixels(&engine)
+            .iter()
+            .all(|&pixel| pixel == 0xFFFF0000));
+    }
+
+    /// Screen-space width and height of the first triangle's bounding box.
+    fn screen_extent(engine: &mut Engine) -> (f32, f32) {
+        engine.update(0.0);
+        let [a, b, c] = engine.triangles_to_render[0].points;
+        let span = |v: [f32; 3]| v[0].max(v[1]).max(v[2]) - v[0].min(v[1]).min(v[2]);
+        (span([a.x, b.x, c.x]), span([a.y, b.y, c.y]))
+    }
+
+    #[test]
+    fn resizing_keeps_geometry_proportions() {
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+        let (width, height) = screen_extent(&mut engine);
+
+        // Halving only the height halves the vertical scale, and the horizontal with it
+        engine.resize(64, 32);
+        let (resized_width, resized_height) = screen_extent(&mut engine);
+        assert!((resized_height - height / 2.0).abs() < 1e-3);
+        assert!((resized_width / resized_height - width / height).abs() < 1e-3);
+    }
+
+    #[test]
+    fn narrower_fov_magnifies() {
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+        assert!((engine.fov_degrees() - DEFAULT_FOV_Y_DEGREES).abs() < 1e-4);
+        let (width, _) = screen_extent(&mut engine);
+
+        engine.set_fov_degrees(20.0);
+        let (zoomed, _) = screen_extent(&mut engine);
+        let expected = (22.5f32.to_radians().tan()) / (10.0f32.to_radians().tan());
+        assert!((zoomed / width - expected).abs() < 1e-3);
+    }
+
+    #[test]
+    fn builder_applies_every_option() {
+        let engine = Engine::builder()
+            .with_size(120, 90)
+            .with_fov_degrees(70.0)
+            .with_projection(ProjectionMode::Orthographic)
+            .with_rasterizer(RasterizerType::Scanline)
+            .with_render_mode(RenderMode::Wireframe)
+            .with_shading_mode(ShadingMode::Phong)
+            .with_clear_color(0xFF00FF00)
+            .with_camera_position(Vec3::new(1.0, 2.0, -8.0))
+            .with_backface_culling(false)
+            .with_grid(false)
+            .build();
+
+        assert_eq!(
+            (engine.renderer.width(), engine.renderer.height()),
+            (120, 90)
+        );
+        assert!((engine.fov_degrees() - 70.0).abs() < 1e-4);
+        assert_eq!(engine.projection(), ProjectionMode::Orthographic);
+        assert_eq!(engine.rasterizer(), RasterizerType::Scanline);
+        assert_eq!(engine.render_mode(), RenderMode::Wireframe);
+        assert_eq!(engine.shading_mode(), ShadingMode::Phong);
+        assert_eq!(engine.clear_color(), 0xFF00FF00);
+        assert_eq!(engine.camera().position(), Vec3::new(1.0, 2.0, -8.0));
+        assert!(!engine.backface_culling);
+        assert!(!engine.draw_grid);
+
+        // The projection was built for the final size and mode
+        let mut expected = Engine::new(120, 90);
+        expected.set_fov_degrees(70.0);
+        expected.set_projection(ProjectionMode::Orthographic);
+        assert_eq!(engine.projection_matrix, expected.projection_matrix);
+    }
+
+    /// Outward-wound cube with quad faces, spanning -1..1 on each axis.
+    const CUBE_OBJ: &str = "\
+v -1 -1 -1
+v 1 -1 -1
+v 1 1 -1
+v -1 1 -1
+v -1 -1 1
+v 1 -1 1
+v 1 1 1
+v -1 1 1
+f 1 4 3 2
+f 5 6 7 8
+f 1 5 8 4
+f 2 3 7 6
+f 4 8 7 3
+f 1 2 6 5
+";
+
+    fn obj_mesh(obj: &str) -> Mesh {
+        Mesh::from_obj_reader(
+            obj.as_bytes(),
+            std::path::Path::new(""),
+            &ObjOptions::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_a_cube_headlessly() {
+        let mut engine = Engine::builder().with_size(48, 32).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+
+        let image = engine.render_to_image();
+        assert_eq!(image.dimensions(), (48, 32));
+        let rgba = image.into_raw();
+        assert_eq!(rgba.len(), 48 * 32 * 4);
+
+        let [a, r, g, b] = colors::BACKGROUND.to_be_bytes();
+        let background = [r, g, b, a];
+        let covered = rgba
+            .chunks_exact(4)
+            .filter(|&pixel| pixel != background)
+            .count();
+        assert!(
+            covered > 48 * 32 / 10,
+            "cube covers only {} pixels",
+            covered
+        );
+    }
+
+    /// Whether pixel `i` of a square frame is within one pixel of the background
+    /// (or the frame's border), i.e. on the outline of what was drawn.
+    fn near_background(pixels: &[u32], i: usize) -> bool {
+        let size = (pixels.len() as f32).sqrt() as i32;
+        let (x, y) = (i as i32 % size, i as i32 / size);
+        (-1..=1).any(|dy| {
+            (-1..=1).any(|dx| {
+                let (x, y) = (x + dx, y + dy);
+                !(0..size).contains(&x)
+                    || !(0..size).contains(&y)
+                    || pixels[(y * size + x) as usize] == colors::BACKGROUND
+            })
+        })
+    }
+
+    /// The cube turned to show three faces, in a 64x64 frame.
+    fn cube_engine() -> Engine {
+        let mut engine = Engine::builder().with_size(64, 64).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+        engine
+    }
+
+    fn render_mode(engine: &mut Engine, mode: RenderMode) -> Vec<u32> {
+        engine.set_render_mode(mode);
+        engine.update(0.0);
+        engine.render();
+        frame_pixels(engine)
+    }
+
+    #[test]
+    fn silhouette_mode_draws_only_the_cube_outline() {
+        let mut engine = cube_engine();
+        let filled = render_mode(&mut engine, RenderMode::Filled);
+        let all_edges = render_mode(&mut engine, RenderMode::Wireframe);
+        engine.set_silhouette_edges(true);
+        let outline = render_mode(&mut engine, RenderMode::Wireframe);
+
+        // Three faces are visible, so the outline is a hexagon
+        assert_eq!(engine.silhouette_lines.len(), 6);
+        let drawn = |pixels: &[u32]| -> Vec<usize> {
+            (0..pixels.len())
+                .filter(|&i| pixels[i] != colors::BACKGROUND)
+                .collect()
+        };
+        assert!(drawn(&outline).len() > 40);
+        // Every outline pixel sits on the boundary of the filled cube
+        assert!(drawn(&outline)
+            .into_iter()
+            .all(|i| near_background(&filled, i)));
+        // The full wireframe also has edges across the cube's faces
+        assert!(drawn(&all_edges)
+            .into_iter()
+            .any(|i| !near_background(&filled, i)));
+    }
+
+    #[test]
+    fn wireframe_under_an_opaque_fill_is_hidden() {
+        let mut engine = cube_engine();
+        let filled = render_mode(&mut engine, RenderMode::Filled);
+        let edges = |pixels: Vec<u32>| -> Vec<usize> {
+            (0..pixels.len())
+                .filter(|&i| pixels[i] == colors::WIREFRAME)
+                .collect()
+        };
+        let over = edges(render_mode(&mut engine, RenderMode::FilledWireframe));
+        assert!(over.iter().any(|&i| !near_background(&filled, i)));
+
+        // Under the fill, only edges poking out past its coverage still show
+        engine.set_wireframe_over_fill(false);
+        let under = edges(render_mode(&mut engine, RenderMode::FilledWireframe));
+        assert!(under.len() < over.len());
+        assert!(under.into_iter().all(|i| near_background(&filled, i)));
+    }
+
+    #[test]
+    fn vertex_markers_stay_over_the_fill_with_edges_under_it() {
+        let mut engine = cube_engine();
+        engine.set_vertex_marker(4, 0xFF00FFFF, VertexMarkerShape::Square);
+        let markers = |pixels: Vec<u32>| -> Vec<usize> {
+            (0..pixels.len())
+                .filter(|&i| pixels[i] == 0xFF00FFFF)
+                .collect()
+        };
+        let over = markers(render_mode(
+            &mut engine,
+            RenderMode::FilledWireframeVertices,
+        ));
+        assert!(!over.is_empty());
+
+        engine.set_wireframe_over_fill(false);
+        let under = markers(render_mode(
+            &mut engine,
+            RenderMode::FilledWireframeVertices,
+        ));
+        assert_eq!(under, over);
+    }
+
+    #[test]
+    fn frame_stats_account_for_every_cube_face() {
+        let mut engine = Engine::builder().with_size(48, 32).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+        engine.update(0.016);
+        engine.render();
+
+        let stats = engine.last_frame_stats();
+        assert_eq!(stats.triangles_submitted, engine.mesh().faces().len());
+        assert_eq!(stats.triangles_culled + stats.triangles_drawn, 12);
+        assert!(stats.triangles_culled > 0 && stats.triangles_drawn > 0);
+        assert_eq!(stats.triangles_clipped, 0);
+        assert_eq!(stats.pixels_shaded, engine.shaded_fragments());
+        assert!(stats.pixels_shaded > 0);
+
+        // From inside the cube every face is a back face, and the near plane cuts some
+        engine.backface_culling = false;
+        engine.camera_mut().set_position(Vec3::new(0.0, 0.0, -0.9));
+        engine.update(0.0);
+        assert!(engine.last_frame_stats().triangles_clipped > 0);
+    }
+
+    #[test]
+    fn geometry_beyond_the_far_plane_is_clipped() {
+        let mut engine = Engine::builder().with_size(48, 32).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        engine.backface_culling = false;
+        engine.update(0.0);
+        assert!(engine.last_frame_stats().triangles_drawn > 0);
+        assert_eq!(engine.last_frame_stats().triangles_clipped, 0);
+
+        // The cube spans 4 to 6 units from the camera
+        engine.set_clip_planes(0.1, 5.0);
+        engine.update(0.0);
+        assert_eq!(engine.clip_planes(), (0.1, 5.0));
+        assert!(engine.last_frame_stats().triangles_clipped > 0);
+        assert!(engine.last_frame_stats().triangles_drawn > 0);
+
+        engine.set_clip_planes(0.1, 3.0);
+        engine.update(0.0);
+        engine.render();
+        assert_eq!(engine.last_frame_stats().triangles_drawn, 0);
+        assert!(frame_colors(&engine)
+            .iter()
+            .all(|&c| c == colors::BACKGROUND));
+    }
+
+    #[test]
+    #[should_panic(expected = "0 < near < far")]
+    fn clip_planes_must_be_ordered() {
+        Engine::new(8, 8).set_clip_planes(1.0, 0.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "finite far")]
+    fn clip_planes_must_be_finite() {
+        Engine::new(8, 8).set_clip_planes(0.1, f32::INFINITY);
+    }
+
+    #[test]
+    fn per_face_colors_are_distinct_and_deterministic() {
+        let face_colors = || {
+            let mut engine = Engine::builder().with_size(48, 32).with_grid(false).build();
+            *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+            engine.backface_culling = false;
+            engine.set_shading_mode(ShadingMode::None);
+            engine.set_face_color_mode(FaceColorMode::PerFace);
+            engine.update(0.0);
+            engine
+                .triangles_to_render
+                .iter()
+                .map(|t| t.color)
+                .collect::<Vec<_>>()
+        };
+
+        let colors = face_colors();
+        assert_eq!(colors.len(), 12);
+        assert_ne!(colors[0], colors[1]);
+        assert_eq!(colors, face_colors());
+    }
+
+    #[test]
+    fn engine_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<Engine>();
+    }
+
+    #[test]
+    fn invert_post_process_complements_the_frame() {
+        let mut engine = Engine::builder().with_size(48, 32).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+        engine.update(0.016);
+        engine.render();
+        let before = frame_pixels(&engine);
+
+        engine.add_post_process(crate::render::Invert);
+        engine.render();
+        let after = frame_pixels(&engine);
+        assert!(frame_colors(&engine).len() > 2, "the cube was not drawn");
+        assert!(before
+            .iter()
+            .zip(&after)
+            .all(|(&b, &a)| a == (!b & 0x00FFFFFF) | (b & 0xFF000000)));
+    }
+
+    #[test]
+    fn minimized_window_size_does_not_stop_rendering() {
+        let mut engine = Engine::builder().with_size(48, 32).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+        engine.update(0.016);
+        engine.render();
+        let before = frame_pixels(&engine);
+
+        // A minimized window reports a zero size
+        engine.resize(0, 0);
+        engine.update(0.016);
+        engine.render();
+        assert_eq!(frame_pixels(&engine).len(), 1);
+
+        engine.resize(48, 32);
+        engine.update(0.016);
+        engine.render();
+        assert_eq!(frame_pixels(&engine), before);
+
+        let mut empty = Engine::new(0, 0);
+        empty.update(0.016);
+        empty.render();
+        assert_eq!(frame_pixels(&empty).len(), 1);
+    }
+
+    #[test]
+    fn update_reads_each_face_normal_once_for_culling_and_shading() {
+        let mut engine = Engine::builder().with_size(64, 64).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+        engine.set_shading_mode(ShadingMode::Flat);
+        // Light shining along the view direction lights exactly the faces that survive culling
+        engine.set_light(DirectionalLight::new(Vec3::new(0.0, 0.0, 1.0)));
+
+        crate::mesh::FACE_NORMAL_LOOKUPS.with(|count| count.set(0));
+        engine.update(0.0);
+        let lookups = crate::mesh::FACE_NORMAL_LOOKUPS.with(|count| count.get());
+        assert_eq!(lookups, 12);
+
+        let ambient = colors::modulate(colors::FILL, engine.ambient());
+        let triangles = &engine.triangles_to_render;
+        assert_eq!(triangles.len(), 6);
+        assert!(triangles.iter().all(|t| t.color != ambient));
+    }
+
+    #[test]
+    fn antialiased_wireframe_blends_over_the_fill() {
+        // Pixels where the green wireframe partially covers the gray fill
+        let blended_over_fill = |antialiased| {
+            let mut engine = Engine::builder().with_size(64, 64).with_grid(false).build();
+            *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+            engine.set_shading_mode(ShadingMode::None);
+            engine.set_render_mode(RenderMode::FilledWireframe);
+            engine.set_antialiased_lines(antialiased);
+            engine.update(0.0);
+            engine.render();
+            frame_pixels(&engine)
+                .into_iter()
+                .filter(|&pixel| {
+                    let (r, g) = ((pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF);
+                    // Red above the background's rules out blends over the background
+                    r > 0x1E && r < 0x88 && g > 0x88 && g < 0xFF
+                })
+                .count()
+        };
+
+        assert_eq!(blended_over_fill(false), 0);
+        assert!(blended_over_fill(true) > 10);
+    }
+
+    #[test]
+    fn back_faces_can_stay_in_the_wireframe_while_fills_are_culled() {
+        let mut engine = Engine::builder().with_size(64, 64).with_grid(false).build();
+        *engine.mesh_mut() = obj_mesh(CUBE_OBJ);
+        *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+        engine.set_render_mode(RenderMode::Wireframe);
+        engine.update(0.0);
+        engine.render();
+        let culled_edges = frame_pixels(&engine)
+            .iter()
+            .filter(|&&c| c != colors::BACKGROUND)
+            .count();
+
+        engine.wireframe_backface_culling = Some(false);
+        engine.update(0.0);
+        let triangles = &engine.triangles_to_render;
+        let filled = triangles.iter().filter(|t| !t.back_facing).count();
+        assert_eq!((filled, triangles.len()), (6, 12));
+        // Back-face edges are dimmed
+        let dimmed = colors::modulate(colors::WIREFRAME, BACK_FACE_WIREFRAME_INTENSITY);
+        assert!(triangles
+            .iter()
+            .all(|t| t.back_facing == (t.wireframe_color == dimmed)));
+
+        engine.render();
+        let all_edges = frame_pixels(&engine)
+            .iter()
+            .filter(|&&c| c != colors::BACKGROUND)
+            .count();
+        assert!(all_edges > culled_edges, "{all_edges} <= {culled_edges}");
+
+        // Without an override, the wireframe follows fill culling
+        engine.wireframe_backface_culling = None;
+        engine.backface_culling = false;
+        assert!(!engine.wireframe_culls_back_faces());
+        engine.update(0.0);
+        assert_eq!(engine.triangles_to_render.len(), 12);
+    }
+
+    #[test]
+    fn world_grid_axes_use_their_configured_colors() {
+        let grid = GridConfig {
+            x_axis_color: 0xFFFF00FF,
+            z_axis_color: 0xFF00FFFF,
+            ..GridConfig::default()
+        };
+        let mut engine = Engine::new(96, 96);
+        engine.set_grid(grid);
+        engine.set_camera_position(Vec3::new(0.0, 3.0, -5.0));
+        engine.camera_mut().look_at(Vec3::ZERO);
+        engine.update(0.0);
+        engine.render();
+
+        let pixels = frame_pixels(&engine);
+        let view_projection = engine.projection_matrix * engine.camera.view_matrix();
+        // Some pixel around a point on the grid plane has `color`
+        let color_near = |world: Vec3, color: u32| {
+            let clip = view_projection * Vec4::from_vec3(world, 1.0);
+            let p = clip_to_screen(clip, 96, 96, false);
+            let (x, y) = (p.x as i32, p.y as i32);
+            (-1..=1).any(|dy| (-1..=1).any(|dx| pixels[((y + dy) * 96 + x + dx) as usize] == color))
+        };
+        // Away from the origin and from the crossing grid lines
+        assert!(color_near(Vec3::new(-1.5, 0.0, 0.0), grid.x_axis_color));
+        assert!(color_near(Vec3::new(0.0, 0.0, 1.5), grid.z_axis_color));
+        assert!(color_near(Vec3::new(0.5, 0.0, 1.0), grid.color));
+        assert!(
+            color_near(Vec3::ZERO, grid.x_axis_color) && color_near(Vec3::ZERO, grid.z_axis_color)
+        );
+    }
+
+    #[test]
+    fn obj_vertex_colors_reach_gouraud_triangles() {
+        let obj = "\
+v -1 -1 0 1 0 0
+v 0 1 0 0 1 0
+v 1 -1 0 0 0 1
+vn 0 0 -1
+f 1//1 2//1 3//1
+";
+        let mut engine = Engine::new(64, 64);
+        *engine.mesh_mut() = Mesh::from_obj_reader(
+            obj.as_bytes(),
+            std::path::Path::new(""),
+            &ObjOptions::default(),
+        )
+        .unwrap();
+        engine.set_shading_mode(ShadingMode::Gouraud);
+        engine.light.ambient_intensity = 1.0;
+        engine.update(0.0);
+
+        // Fully lit, so each corner is the default fill tinted by its own color
+        let triangle = &engine.triangles_to_render[0];
+        assert_eq!(triangle.vertex_colors, [0xFF880000, 0xFF008800, 0xFF000088]);
+
+        // Other shading modes ignore them
+        engine.set_shading_mode(ShadingMode::None);
+        engine.update(0.0);
+        assert_eq!(
+            engine.triangles_to_render[0].vertex_colors,
+            [colors::FILL; 3]
+        );
+    }
+
+    #[test]
+    fn update_advances_rotation_by_angular_velocity() {
+        let mut engine = Engine::new(64, 64);
+        engine.set_mesh_angular_velocity(Vec3::new(0.0, 1.0, 0.0));
+
+        engine.update(1.0);
+        assert_eq!(engine.mesh().rotation(), Vec3::new(0.0, 1.0, 0.0));
+        engine.update(0.5);
+        assert_eq!(engine.mesh().rotation().y, 1.5);
+
+        // Manual edits still apply on top
+        engine.mesh_mut().rotation_mut().y = 0.0;
+        engine.update(0.0);
+        assert_eq!(engine.mesh().rotation().y, 0.0);
+    }
+
+    #[test]
+    fn fixed_winding_renders_like_an_outward_cube() {
+        let render = |mesh: Mesh| {
+            let mut engine = Engine::builder().with_size(48, 32).with_grid(false).build();
+            *engine.mesh_mut() = mesh;
+            *engine.mesh_mut().rotation_mut() = Vec3::new(0.5, 0.7, 0.0);
+            engine.render_to_image();
+            engine.frame_hash()
+        };
+        // Same quads listed in the opposite order, keeping each fan's diagonal
+        let reversed = CUBE_OBJ
+            .replace("f 1 4 3 2", "f 1 2 3 4")
+            .replace("f 5 6 7 8", "f 5 8 7 6")
+            .replace("f 1 5 8 4", "f 1 4 8 5")
+            .replace("f 2 3 7 6", "f 2 6 7 3")
+            .replace("f 4 8 7 3", "f 4 3 7 8")
+            .replace("f 1 2 6 5", "f 1 5 6 2");
+
+        let expected = render(obj_mesh(CUBE_OBJ));
+        let mut mesh = obj_mesh(&reversed);
+        // Culling keeps the far inside faces instead
+        assert_ne!(render(mesh.clone()), expected);
+
+        assert_eq!(mesh.fix_winding(), 12);
+        assert_eq!(render(mesh), expected);
+    }
+
+    #[test]
+    fn receding_texture_is_foreshortened() {
+        // Floor quad from w = 8 to w = 32, red on its near half and blue on its far half
+        let floor = "\
+v -6 -2 3
+v 6 -2 3
+v 6 -2 27
+v -6 -2 27
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 1/1 4/4 3/3 2/2
+";
+        let mut engine = Engine::builder()
+            .with_size(64, 64)
+            .with_grid(false)
+            .with_render_mode(RenderMode::Filled)
+            .build();
+        *engine.mesh_mut() = obj_mesh(floor);
+        engine.set_texture(Texture::from_rgba(&[0, 0, 255, 255, 255, 0, 0, 255], 1, 2).unwrap());
+        engine.set_texture_mode(TextureMode::Replace);
+
+        for rasterizer in [
+            RasterizerType::Scanline,
+            RasterizerType::EdgeFunction,
+            RasterizerType::EdgeFunctionSimd,
+        ] {
+            engine.set_rasterizer(rasterizer);
+            let image = engine.render_to_image();
+
+            let column: Vec<[u8; 4]> = (0..64).map(|y| image.get_pixel(32, y).0).collect();
+            let [a, r, g, b] = colors::BACKGROUND.to_be_bytes();
+            let covered: Vec<usize> = (0..64).filter(|&y| column[y] != [r, g, b, a]).collect();
+            let (top, bottom) = (covered[0], *covered.last().unwrap());
+            let boundary = (top..=bottom)
+                .find(|&y| column[y] == [255, 0, 0, 255])
+                .unwrap();
+
+            // Screen height goes with 1/w, so the halfway line at w = 20 sits a fifth of
+            // the way down from the far edge. Affine interpolation would put it midway.
+            let fraction = (boundary - top) as f32 / (bottom - top) as f32;
+            assert!(
+                fraction < 0.3,
+                "{:?}: boundary {} of the way down",
+                rasterizer,
+                fraction
+            );
+        }
+    }
+
+    #[test]
+    fn dirty_rect_bounds_a_small_triangle() {
+        let mut engine = Engine::builder()
+            .with_size(64, 64)
+            .with_render_mode(RenderMode::Filled)
+            .with_shading_mode(ShadingMode::None)
+            .build();
+        engine.draw_overlay = false;
+        engine.set_incremental_clear(true);
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+        engine.mesh_mut().translation_mut().z = 20.0;
+        engine.update(0.0);
+
+        // The first frame clears everything
+        engine.render();
+        assert_eq!(engine.take_dirty_rect(), Some(Rect::new(0, 0, 64, 64)));
+
+        // Later frames only clear and redraw the triangle's footprint
+        engine.render();
+        let dirty = engine.take_dirty_rect().unwrap();
+        let pixels = frame_pixels(&engine);
+        let covered: Vec<(i32, i32)> = (0..64 * 64)
+            .filter(|&i| pixels[i] == colors::FILL)
+            .map(|i| ((i % 64) as i32, (i / 64) as i32))
+            .collect();
+        assert!(!covered.is_empty());
+        assert!(covered.iter().all(|&(x, y)| dirty.contains(x, y)));
+        let (xs, ys): (Vec<i32>, Vec<i32>) = covered.into_iter().unzip();
+        let drawn = Rect::new(
+            *xs.iter().min().unwrap(),
+            *ys.iter().min().unwrap(),
+            (xs.iter().max().unwrap() - xs.iter().min().unwrap() + 1) as u32,
+            (ys.iter().max().unwrap() - ys.iter().min().unwrap() + 1) as u32,
+        );
+        // The dirty rect covers every pixel center within the vertex bounds, so
+        // only the triangle's corners may leave a pixel per side untouched
+        assert!(dirty.width <= drawn.width + 2 && dirty.height <= drawn.height + 2);
+        assert!(dirty.width < 32 && dirty.height < 32);
+    }
+
+    #[test]
+    fn rendering_leaves_the_front_buffer_until_swapped() {
+        let mut engine = Engine::builder().with_size(16, 16).build();
+        engine.set_clear_color(0xFF000000);
+        engine.render();
+        engine.swap_buffers();
+        let front = engine.frame_buffer().to_vec();
+
+        engine.set_clear_color(0xFFFFFFFF);
+        engine.render();
+        assert_eq!(engine.frame_buffer(), front);
+
+        engine.swap_buffers();
+        assert_ne!(engine.frame_buffer(), front);
+    }
+
+    #[test]
+    fn wireframe_edges_use_their_own_color() {
+        let mut engine = Engine::builder()
+            .with_size(64, 64)
+            .with_grid(false)
+            .with_render_mode(RenderMode::FilledWireframe)
+            .with_shading_mode(ShadingMode::None)
+            .build();
+        engine.draw_overlay = false;
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+        engine.set_wireframe_color(0xFFFF00FF);
+
+        let edge_and_interior = |engine: &mut Engine| {
+            engine.render_to_image();
+            let [a, b, c] = engine.triangles_to_render[0].points;
+            let centroid = (a + b + c) * (1.0 / 3.0);
+            let pixels = frame_pixels(engine);
+            let pixel = |p: Vec3| pixels[p.y as usize * 64 + p.x as usize];
+            (pixel(a), pixel(centroid))
+        };
+        assert_eq!(edge_and_interior(&mut engine), (0xFFFF00FF, colors::FILL));
+
+        // A mesh override wins over the engine-wide color
+        engine.mesh_mut().set_wireframe_color(Some(0xFF00FFFF));
+        assert_eq!(edge_and_interior(&mut engine), (0xFF00FFFF, colors::FILL));
+    }
+
+    #[test]
+    fn vertex_markers_are_centered_on_the_vertex() {
+        let mut engine = Engine::builder()
+            .with_size(64, 64)
+            .with_grid(false)
+            .with_render_mode(RenderMode::WireframeVertices)
+            .build();
+        engine.draw_overlay = false;
+        *engine.mesh_mut() = facing_triangle([Vec3::ZERO; 3]);
+
+        for shape in [VertexMarkerShape::Square, VertexMarkerShape::Circle] {
+            engine.set_vertex_marker(8, 0xFF00FFFF, shape);
+            assert_eq!(
+                engine.vertex_marker(),
+                VertexMarker {
+                    size: 8,
+                    color: 0xFF00FFFF,
+                    shape
                 }
+            );
+            engine.render_to_image();
+            let vertex = engine.triangles_to_render[0].points[1]; // The top corner
+            let pixels = frame_pixels(&engine);
+
+            // Marker pixels in the vertex's row and column
+            let row = vertex.y as usize;
+            let col = vertex.x as usize;
+            let xs: Vec<usize> = (0..64)
+                .filter(|&x| pixels[row * 64 + x] == 0xFF00FFFF)
+                .collect();
+            let ys: Vec<usize> = (0..64)
+                .filter(|&y| pixels[y * 64 + col] == 0xFF00FFFF)
+                .collect();
+            for span in [&xs, &ys] {
+                assert_eq!(span.len(), 8, "{:?}: {:?}", shape, span);
+                assert_eq!(span[7] - span[0], 7, "{:?}: not contiguous", shape);
             }
+            let center_x = (xs[0] + xs[7] + 1) as f32 / 2.0;
+            let center_y = (ys[0] + ys[7] + 1) as f32 / 2.0;
+            assert!((center_x - vertex.x).abs() <= 0.5, "{:?}", shape);
+            assert!((center_y - vertex.y).abs() <= 0.5, "{:?}", shape);
         }
     }
 }
@@ -19,17 +19,22 @@ pub mod colors;
 pub mod engine;
 pub mod light;
 pub mod math;
+pub mod render;
+pub mod texture;
 pub mod window;
 
 // Internal modules - used within the crate only
 pub(crate) mod mesh;
-pub(crate) mod render;
 pub(crate) mod sorting;
-pub mod texture;
 
 // Re-export commonly needed types at crate root for convenience
-pub use engine::{Engine, RasterizerType, RenderMode, ShadingMode};
-pub use mesh::{LoadError, Mesh};
+pub use engine::{
+    Engine, EngineBuilder, FaceColorMode, FogConfig, GridConfig, MeshId, ProjectionMode,
+    RasterizerType, RenderMode, RenderStats, ShadingMode, TextureMode, VertexMarker,
+    VertexMarkerShape,
+};
+pub use mesh::{LoadError, Material, Mesh, ObjOptions};
+pub use render::Rect;
 
 /// Prelude module for convenient imports.
 ///
@@ -42,7 +47,14 @@ pub mod prelude {
     pub use crate::camera::{FpsCamera, FpsCameraController};
 
     // Engine
-    pub use crate::engine::{Engine, RenderMode, ShadingMode, TextureMode};
+    pub use crate::engine::{
+        Engine, FaceColorMode, FogConfig, GridConfig, MeshId, ProjectionMode, RenderMode,
+        RenderStats, ShadingMode, TextureMode, VertexMarker, VertexMarkerShape,
+    };
+
+    // Lighting
+    pub use crate::light::{DirectionalLight, PointLight};
+    pub use crate::render::{PhongLight, PhongParams};
 
     // Math
     pub use crate::math::mat4::Mat4;
@@ -51,15 +63,22 @@ pub mod prelude {
     pub use crate::math::vec4::Vec4;
 
     // Rendering
-    pub use crate::render::RasterizerType;
+    pub use crate::render::{
+        BlendMode, BoxBlur, ColorGrade, Grayscale, ImageView, Invert, PostProcess, RasterizerType,
+        Rect, SobelEdgeDetect, Vignette,
+    };
 
     // Window & Input
     pub use crate::window::{FpsCounter, FrameLimiter, InputState, Key, Window, WindowEvent};
 }
 
-/// Module exposing internals for benchmarking. Not part of the stable API.
+/// Rasterizer types used by the benchmarks, also available from [`render`].
 pub mod bench {
     pub use crate::render::{
-        EdgeFunctionRasterizer, FrameBuffer, Rasterizer, ScanlineRasterizer, Triangle,
+        EdgeFunctionRasterizer, EdgeFunctionSimdRasterizer, FrameBuffer, Rasterizer, Rect,
+        ScanlineRasterizer, Triangle,
     };
+
+    #[cfg(feature = "parallel")]
+    pub use crate::render::TiledRasterizer;
 }
@@ -6,9 +6,11 @@ use crate::prelude::Vec3;
 ///
 /// Directional lights are ideal for simulating distant light sources like the sun,
 /// where all rays are effectively parallel.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct DirectionalLight {
     /// The normalized direction the light is pointing (not where it comes from).
     pub direction: Vec3,
+    /// Minimum brightness for surfaces facing away from the light (default: 0.1)
     pub ambient_intensity: f32,
     /// Multiplier for the diffuse lighting contribution (default: 1.0)
     pub diffuse_strength: f32,
@@ -33,6 +35,58 @@ impl DirectionalLight {
         // Negate direction: light pointing at surface = positive dot product
         (-self.direction).dot(normal.normalize()).max(0.0)
     }
+
+    /// Calculate the final lighting factor for a surface.
+    ///
+    /// Combines the Lambertian diffuse term (scaled by `diffuse_strength`) with the
+    /// ambient floor, clamped to [0.0, 1.0]. Pass the result to `colors::modulate`.
+    pub fn shade(&self, normal: Vec3) -> f32 {
+        (self.intensity(normal) * self.diffuse_strength + self.ambient_intensity).min(1.0)
+    }
+}
+
+/// A light radiating from a point, fading out with distance.
+///
+/// Useful for localized sources like lamps. Contributions from several point
+/// lights add up with the directional light and saturate at full brightness.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointLight {
+    /// World-space position of the light.
+    pub position: Vec3,
+    /// Brightness of a surface facing the light from up close.
+    pub intensity: f32,
+    /// Distance at which the light's contribution reaches zero.
+    pub range: f32,
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, intensity: f32, range: f32) -> Self {
+        PointLight {
+            position,
+            intensity,
+            range,
+        }
+    }
+
+    /// Distance falloff: 1 at the light, easing smoothly to 0 at `range`.
+    ///
+    /// `(1 - (d / range)^2)^2` behaves roughly like inverse-square up close but
+    /// reaches exactly zero at the range, so lights can be skipped beyond it.
+    pub fn attenuation(&self, distance: f32) -> f32 {
+        if self.range <= 0.0 {
+            return 0.0;
+        }
+        let window = (1.0 - (distance / self.range).powi(2)).max(0.0);
+        window * window
+    }
+
+    /// Lambertian contribution to a surface point with the given normal, before
+    /// the ambient floor. Zero for surfaces facing away or out of range.
+    pub fn intensity_at(&self, position: Vec3, normal: Vec3) -> f32 {
+        let to_light = self.position - position;
+        let lambert = to_light.normalize().dot(normal.normalize()).max(0.0);
+        lambert * self.attenuation(to_light.magnitude()) * self.intensity
+    }
 }
 
 #[cfg(test)]
@@ -64,4 +118,30 @@ mod tests {
         let intensity = light.intensity(normal);
         assert!((intensity - 0.707).abs() < 0.01);
     }
+
+    #[test]
+    fn point_light_fades_to_zero_at_its_range() {
+        let light = PointLight::new(Vec3::new(0.0, 0.0, -1.0), 1.0, 10.0);
+        let normal = Vec3::new(0.0, 0.0, -1.0);
+        let near = light.intensity_at(Vec3::ZERO, normal);
+        let far = light.intensity_at(Vec3::new(0.0, 0.0, 8.0), normal);
+        assert!(near > 0.95 && far < near && far > 0.0, "{near} {far}");
+        assert_eq!(light.intensity_at(Vec3::new(0.0, 0.0, 9.0), normal), 0.0);
+        // Facing away
+        assert_eq!(light.intensity_at(Vec3::ZERO, -normal), 0.0);
+    }
+
+    #[test]
+    fn test_shade_facing_light_is_fully_lit() {
+        let light = DirectionalLight::new(Vec3::new(0.0, 0.0, 1.0));
+        let normal = Vec3::new(0.0, 0.0, -1.0);
+        assert!((light.shade(normal) - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_shade_facing_away_gets_ambient_floor() {
+        let light = DirectionalLight::new(Vec3::new(0.0, 0.0, 1.0));
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        assert!((light.shade(normal) - light.ambient_intensity).abs() < 0.001);
+    }
 }
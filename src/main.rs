@@ -7,12 +7,20 @@ use russsty::window::{
 };
 use russsty::ShadingMode;
 
+/// Simulation step, in seconds, independent of the frame rate.
+const FIXED_DT: f32 = 1.0 / 60.0;
+
 fn format_window_title(fps: f64, engine: &Engine, mouse_captured: bool) -> String {
     format!(
-        "Russsty | FPS: {:.1} | {} | Cull: {} | render: {:?} | shade: {:?} | tex: {:?} | {}",
+        "Russsty | FPS: {:.1} | {} | Cull: {} | Wire cull: {} | render: {:?} | shade: {:?} | tex: {:?} | {}",
         fps,
         engine.rasterizer(),
         if engine.backface_culling { "ON" } else { "OFF" },
+        if engine.wireframe_culls_back_faces() {
+            "ON"
+        } else {
+            "OFF"
+        },
         engine.render_mode(),
         engine.shading_mode(),
         engine.texture_mode(),
@@ -26,7 +34,12 @@ fn format_window_title(fps: f64, engine: &Engine, mouse_captured: bool) -> Strin
 
 fn main() -> Result<(), String> {
     let mut window = Window::new("Russsty", WINDOW_WIDTH, WINDOW_HEIGHT)?;
-    let mut engine = Engine::new(window.width(), window.height());
+
+    // Camera pulled back far enough to see the mesh
+    let mut engine = Engine::builder()
+        .with_size(window.width(), window.height())
+        .with_camera_position(Vec3::new(0.0, 0.0, -10.0))
+        .build();
 
     engine
         .load_mesh("assets/crab.obj")
@@ -38,9 +51,6 @@ fn main() -> Result<(), String> {
     // Start with texture mode enabled so we can see it
     engine.set_texture_mode(TextureMode::Replace);
 
-    // Position camera to see the mesh
-    engine.camera_mut().set_position(Vec3::new(0.0, 0.0, -10.0));
-
     // Camera controller for FPS-style movement
     let camera_controller = FpsCameraController::default();
 
@@ -62,12 +72,17 @@ fn main() -> Result<(), String> {
                 Key::Num4 => engine.set_render_mode(RenderMode::FilledWireframeVertices),
                 Key::Num5 => engine.set_render_mode(RenderMode::Filled),
                 Key::C => engine.backface_culling = !engine.backface_culling,
+                Key::B => {
+                    engine.wireframe_backface_culling = Some(!engine.wireframe_culls_back_faces())
+                }
                 Key::G => engine.draw_grid = !engine.draw_grid,
+                Key::H => engine.draw_overlay = !engine.draw_overlay,
                 Key::M => window.toggle_mouse_capture(),
                 Key::R => {
                     let next = match engine.rasterizer() {
                         RasterizerType::Scanline => RasterizerType::EdgeFunction,
-                        RasterizerType::EdgeFunction => RasterizerType::Scanline,
+                        RasterizerType::EdgeFunction => RasterizerType::EdgeFunctionSimd,
+                        RasterizerType::EdgeFunctionSimd => RasterizerType::Scanline,
                     };
                     engine.set_rasterizer(next);
                 }
@@ -75,7 +90,8 @@ fn main() -> Result<(), String> {
                     let next = match engine.shading_mode() {
                         ShadingMode::None => ShadingMode::Flat,
                         ShadingMode::Flat => ShadingMode::Gouraud,
-                        ShadingMode::Gouraud => ShadingMode::None,
+                        ShadingMode::Gouraud => ShadingMode::Phong,
+                        ShadingMode::Phong => ShadingMode::None,
                     };
                     engine.set_shading_mode(next);
                 }
@@ -100,11 +116,19 @@ fn main() -> Result<(), String> {
             camera_controller.update(engine.camera_mut(), window.input_state(), delta_time_sec);
         }
 
-        engine.update();
+        // Animation advances in fixed steps. The last update also rebuilds the
+        // frame for the current camera, so it runs with no time left to simulate.
+        let steps = frame_limiter.fixed_steps(FIXED_DT);
+        for _ in 1..steps {
+            engine.update(FIXED_DT);
+        }
+        engine.update(if steps > 0 { FIXED_DT } else { 0.0 });
         engine.render();
+        engine.swap_buffers();
         window.present(engine.frame_buffer())?;
 
         if let Some(fps) = fps_counter.tick() {
+            engine.set_overlay_text(&format!("FPS: {:.1}", fps));
             window.set_title(&format_window_title(
                 fps,
                 &engine,
@@ -95,10 +95,23 @@ impl Mat4 {
     }
 
     /// Creates a perspective matrix with left-handed coordinate system.
+    ///
+    /// # Arguments
+    ///
+    /// * `fov` - Vertical field of view in radians.
+    /// * `aspect_ratio` - Viewport width divided by height. Only the x axis is scaled by it.
+    /// * `near`, `far` - Distances to the clip planes along +Z.
+    ///
+    /// # Depth Convention
+    ///
+    /// After the perspective divide, z maps to the OpenGL-style range [-1, 1]:
+    /// points on the near plane land at -1 and points on the far plane at +1.
+    /// Clip-space `w` equals the view-space z, which the rasterizers use for
+    /// depth testing and perspective-correct interpolation.
     pub fn perspective_lh(fov: f32, aspect_ratio: f32, near: f32, far: f32) -> Self {
         let t = near * (fov / 2.0).tan();
         let r = t * aspect_ratio;
-        let a = (far + near) / (near - far);
+        let a = (far + near) / (far - near);
         let b = -2.0 * far * near / (far - near);
         Mat4::new([
             [near / r, 0.0, 0.0, 0.0],
@@ -108,6 +121,30 @@ impl Mat4 {
         ])
     }
 
+    /// Creates a left-handed orthographic projection matrix.
+    ///
+    /// Maps the box `[left, right] x [bottom, top] x [near, far]` onto the NDC
+    /// cube, with z in the same [-1, 1] range as [`Mat4::perspective_lh`]. There is
+    /// no foreshortening: clip-space `w` is always 1, so parallel lines stay parallel.
+    pub fn orthographic(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
+        Mat4::new([
+            [
+                2.0 / (right - left),
+                0.0,
+                0.0,
+                -(right + left) / (right - left),
+            ],
+            [
+                0.0,
+                2.0 / (top - bottom),
+                0.0,
+                -(top + bottom) / (top - bottom),
+            ],
+            [0.0, 0.0, 2.0 / (far - near), -(far + near) / (far - near)],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
     /// Creates a view matrix with left-handed coordinate system.
     ///
     /// # Arguments
@@ -260,6 +297,24 @@ impl Mat4 {
         ]))
     }
 
+    /// The matrix that takes normals to the space this matrix takes points to.
+    ///
+    /// This is the inverse transpose of the upper-left 3x3 (translation does not
+    /// apply to directions). It equals the rotation for rigid transforms, but keeps
+    /// normals perpendicular to their surfaces under non-uniform scale. Singular
+    /// matrices fall back to the identity.
+    pub fn normal_matrix(&self) -> Mat4 {
+        let mut linear = *self;
+        for i in 0..3 {
+            linear.data[i][3] = 0.0;
+            linear.data[3][i] = 0.0;
+        }
+        linear.data[3][3] = 1.0;
+        linear
+            .inverse()
+            .map_or(Mat4::identity(), |inverse| inverse.transpose())
+    }
+
     /// Access element at [row][col].
     #[inline]
     pub fn get(&self, row: usize, col: usize) -> f32 {
@@ -271,20 +326,16 @@ impl Mat4 {
     pub fn set(&mut self, row: usize, col: usize, value: f32) {
         self.data[row][col] = value;
     }
-}
 
-/// Matrix multiplication: Mat4 * Mat4.
-///
-/// For column-major convention, `A * B * v` applies B first, then A.
-impl Mul<Mat4> for Mat4 {
-    type Output = Mat4;
-
-    fn mul(self, rhs: Mat4) -> Self::Output {
+    /// Multiplies two matrices: `self * rhs`.
+    ///
+    /// The result applies `rhs` first, then `self`.
+    pub fn mul_mat4(&self, rhs: &Mat4) -> Mat4 {
         let mut result = [[0.0f32; 4]; 4];
 
-        for row in 0..4 {
-            for col in 0..4 {
-                result[row][col] = self.data[row][0] * rhs.data[0][col]
+        for (row, result_row) in result.iter_mut().enumerate() {
+            for (col, cell) in result_row.iter_mut().enumerate() {
+                *cell = self.data[row][0] * rhs.data[0][col]
                     + self.data[row][1] * rhs.data[1][col]
                     + self.data[row][2] * rhs.data[2][col]
                     + self.data[row][3] * rhs.data[3][col];
@@ -293,13 +344,9 @@ impl Mul<Mat4> for Mat4 {
 
         Mat4::new(result)
     }
-}
-
-/// Transform a Vec4 by a matrix: Mat4 * Vec4 (column vector).
-impl Mul<Vec4> for Mat4 {
-    type Output = Vec4;
 
-    fn mul(self, v: Vec4) -> Self::Output {
+    /// Transforms a column vector: `self * v`.
+    pub fn mul_vec4(&self, v: Vec4) -> Vec4 {
         Vec4::new(
             self.data[0][0] * v.x
                 + self.data[0][1] * v.y
@@ -321,6 +368,26 @@ impl Mul<Vec4> for Mat4 {
     }
 }
 
+/// Matrix multiplication: Mat4 * Mat4.
+///
+/// For column-major convention, `A * B * v` applies B first, then A.
+impl Mul<Mat4> for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Mat4) -> Self::Output {
+        self.mul_mat4(&rhs)
+    }
+}
+
+/// Transform a Vec4 by a matrix: Mat4 * Vec4 (column vector).
+impl Mul<Vec4> for Mat4 {
+    type Output = Vec4;
+
+    fn mul(self, v: Vec4) -> Self::Output {
+        self.mul_vec4(v)
+    }
+}
+
 /// Transform a point: Mat4 * Vec3 (treats Vec3 as column vector with w=1).
 ///
 /// Applies perspective division if w != 1.
@@ -344,3 +411,150 @@ impl Mul<Vec3> for Mat4 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn assert_vec4_eq(a: Vec4, b: Vec4) {
+        assert_relative_eq!(a.x, b.x, epsilon = 1e-5);
+        assert_relative_eq!(a.y, b.y, epsilon = 1e-5);
+        assert_relative_eq!(a.z, b.z, epsilon = 1e-5);
+        assert_relative_eq!(a.w, b.w, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn identity_leaves_vector_unchanged() {
+        let v = Vec4::point(1.0, 2.0, 3.0);
+        assert_eq!(Mat4::identity() * v, v);
+    }
+
+    #[test]
+    fn translation_moves_points_but_not_directions() {
+        let t = Mat4::translation(1.0, 2.0, 3.0);
+        assert_eq!(t * Vec4::point(0.0, 0.0, 0.0), Vec4::point(1.0, 2.0, 3.0));
+        assert_eq!(
+            t * Vec4::direction(1.0, 0.0, 0.0),
+            Vec4::direction(1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn translate_then_rotate_composes_right_to_left() {
+        let translate = Mat4::translation(1.0, 0.0, 0.0);
+        let rotate = Mat4::rotation_z(FRAC_PI_2);
+        let p = Vec4::point(0.0, 0.0, 0.0);
+
+        // Translation applied first, then the rotation carries the point around Z.
+        let rotated = (rotate * translate) * p;
+        assert_vec4_eq(rotated, rotate * Vec4::point(1.0, 0.0, 0.0));
+        assert_vec4_eq(rotated, Vec4::point(0.0, -1.0, 0.0));
+
+        // Rotation applied first leaves the origin in place, then translation moves it.
+        let translated = (translate * rotate) * p;
+        assert_vec4_eq(translated, Vec4::point(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn orthographic_maps_box_corners_to_ndc_cube() {
+        let proj = Mat4::orthographic(-4.0, 2.0, -1.0, 3.0, 0.5, 10.0);
+        assert_vec4_eq(
+            proj * Vec4::point(-4.0, -1.0, 0.5),
+            Vec4::point(-1.0, -1.0, -1.0),
+        );
+        assert_vec4_eq(
+            proj * Vec4::point(2.0, 3.0, 10.0),
+            Vec4::point(1.0, 1.0, 1.0),
+        );
+        // Depth doesn't affect x or y
+        assert_vec4_eq(
+            proj * Vec4::point(-1.0, 1.0, 5.25),
+            Vec4::point(0.0, 0.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn perspective_maps_near_and_far_planes_to_ndc_range() {
+        let proj = Mat4::perspective_lh(60.0_f32.to_radians(), 4.0 / 3.0, 0.1, 100.0);
+
+        let near = proj * Vec4::point(0.0, 0.0, 0.1);
+        assert_relative_eq!(near.z / near.w, -1.0, epsilon = 1e-5);
+        assert_relative_eq!(near.w, 0.1, epsilon = 1e-6);
+
+        let far = proj * Vec4::point(0.0, 0.0, 100.0);
+        assert_relative_eq!(far.z / far.w, 1.0, epsilon = 1e-5);
+        assert_relative_eq!(far.w, 100.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn perspective_applies_aspect_ratio_to_x_only() {
+        let fov = 90.0_f32.to_radians();
+        let square = Mat4::perspective_lh(fov, 1.0, 0.1, 100.0);
+        let wide = Mat4::perspective_lh(fov, 2.0, 0.1, 100.0);
+
+        assert_relative_eq!(wide.get(0, 0), square.get(0, 0) / 2.0, epsilon = 1e-5);
+        assert_relative_eq!(wide.get(1, 1), square.get(1, 1), epsilon = 1e-5);
+
+        // With a 90 degree FOV, a point at 45 degrees lands on the top edge of the screen.
+        let p = wide * Vec4::point(1.0, 1.0, 1.0);
+        assert_relative_eq!(p.x / p.w, 0.5, epsilon = 1e-5);
+        assert_relative_eq!(p.y / p.w, 1.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn inverse_undoes_a_composed_transform() {
+        let m = Mat4::translation(3.0, -2.0, 5.0)
+            * Mat4::rotation_x(0.4)
+            * Mat4::rotation_y(-1.1)
+            * Mat4::rotation_z(2.3)
+            * Mat4::scaling(2.0, 0.5, 3.0);
+        let product = m * m.inverse().unwrap();
+        let identity = Mat4::identity();
+        for row in 0..4 {
+            for col in 0..4 {
+                assert_relative_eq!(
+                    product.get(row, col),
+                    identity.get(row, col),
+                    epsilon = 1e-5
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn singular_matrix_has_no_inverse() {
+        assert_eq!(Mat4::scaling(1.0, 0.0, 1.0).inverse(), None);
+        let mut repeated_row = Mat4::rotation_y(0.7);
+        for col in 0..4 {
+            repeated_row.set(2, col, repeated_row.get(0, col));
+        }
+        assert_eq!(repeated_row.inverse(), None);
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular_under_non_uniform_scale() {
+        let m =
+            Mat4::translation(1.0, 2.0, 3.0) * Mat4::rotation_z(0.5) * Mat4::scaling(4.0, 1.0, 1.0);
+        // A 45 degree slope in the XY plane: its tangent and normal
+        let tangent = Vec4::direction(1.0, 1.0, 0.0);
+        let normal = Vec4::direction(1.0, -1.0, 0.0);
+
+        let world_tangent = m * tangent;
+        let world_normal = m.normal_matrix() * normal;
+        assert_relative_eq!(world_tangent.dot(world_normal), 0.0, epsilon = 1e-5);
+        assert_relative_eq!(world_normal.w, 0.0);
+        // Transforming the normal like a direction would not stay perpendicular
+        assert!((m * normal).dot(world_tangent).abs() > 1.0);
+    }
+
+    #[test]
+    fn mul_methods_match_operators() {
+        let a = Mat4::rotation_x(0.3) * Mat4::scaling(2.0, 3.0, 4.0);
+        let b = Mat4::translation(-1.0, 5.0, 2.0);
+        let v = Vec4::new(1.0, -2.0, 3.0, 1.0);
+        assert_eq!(a.mul_mat4(&b), a * b);
+        assert_eq!(a.mul_vec4(v), a * v);
+    }
+}
@@ -2,6 +2,12 @@
 
 use crate::prelude::Vec2;
 
+/// Whether `a` and `b` differ by at most `eps`.
+#[inline]
+pub(crate) fn approx_eq_f32(a: f32, b: f32, eps: f32) -> bool {
+    (a - b).abs() <= eps
+}
+
 /// Compute the edge function value for point p relative to edge (a -> b).
 ///
 /// Returns a positive value if p is to the right of the edge (clockwise),
@@ -24,14 +30,72 @@ pub fn triangle_area(v0: Vec2, v1: Vec2, v2: Vec2) -> f32 {
 /// Compute barycentric coordinates for point p within triangle (v0, v1, v2).
 ///
 /// Returns (lambda0, lambda1, lambda2) where each lambda is the weight
-/// for the corresponding vertex. For points inside the triangle,
-/// all values are in [0, 1] and sum to 1.
+/// for the corresponding vertex, or `None` if the triangle is degenerate
+/// (near-zero area, e.g. collinear vertices).
+///
+/// The edge functions are divided by the triangle's signed area, so the
+/// result does not depend on winding: for both clockwise and counter-clockwise
+/// triangles, points inside have all weights in [0, 1] summing to 1, and a
+/// point outside has a negative weight for each vertex opposite an edge it
+/// lies beyond.
 #[inline]
-pub fn barycentric_coordinates(p: Vec2, v0: Vec2, v1: Vec2, v2: Vec2) -> (f32, f32, f32) {
+pub fn barycentric_coordinates(p: Vec2, v0: Vec2, v1: Vec2, v2: Vec2) -> Option<(f32, f32, f32)> {
+    let area = triangle_area(v0, v1, v2);
+    if area.abs() <= f32::EPSILON {
+        return None;
+    }
+
     let w0 = edge_function(v1, v2, p);
     let w1 = edge_function(v2, v0, p);
     let w2 = edge_function(v0, v1, p);
+    Some((w0 / area, w1 / area, w2 / area))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approx_eq_f32_respects_the_tolerance() {
+        assert!(approx_eq_f32(1.0, 1.0009, 1e-3));
+        assert!(approx_eq_f32(-2.0, -2.0, 0.0));
+        assert!(!approx_eq_f32(1.0, 1.0011, 1e-3));
+        assert!(!approx_eq_f32(f32::NAN, f32::NAN, 1.0));
+    }
+
+    #[test]
+    fn barycentric_coordinates_of_a_degenerate_triangle_are_none() {
+        let p = Vec2::new(1.0, 1.0);
+        let collinear = (
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 2.0),
+        );
+        assert_eq!(
+            barycentric_coordinates(p, collinear.0, collinear.1, collinear.2),
+            None
+        );
+    }
+
+    #[test]
+    fn barycentric_coordinates_are_the_same_for_either_winding() {
+        let (v0, v1, v2) = (
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(0.0, 4.0),
+        );
+        let p = Vec2::new(1.0, 2.0);
+
+        let (l0, l1, l2) = barycentric_coordinates(p, v0, v1, v2).unwrap();
+        assert!(approx_eq_f32(l0, 0.25, 1e-6));
+        assert!(approx_eq_f32(l1, 0.25, 1e-6));
+        assert!(approx_eq_f32(l2, 0.5, 1e-6));
+
+        // Swapping two vertices flips the winding but not the weights
+        let (m0, m2, m1) = barycentric_coordinates(p, v0, v2, v1).unwrap();
+        assert_eq!((m0, m1, m2), (l0, l1, l2));
 
-    let area = w0 + w1 + w2;
-    (w0 / area, w1 / area, w2 / area)
+        let (o0, _, _) = barycentric_coordinates(Vec2::new(3.0, 3.0), v0, v1, v2).unwrap();
+        assert!(o0 < 0.0);
+    }
 }
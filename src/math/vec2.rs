@@ -1,5 +1,7 @@
 use std::ops::{Add, Div, Mul, Sub};
 
+use super::utils::approx_eq_f32;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vec2 {
     pub x: f32,
@@ -62,6 +64,11 @@ impl Vec2 {
         self.x * other.x + self.y * other.y
     }
 
+    /// Whether every component differs from `other`'s by at most `eps`.
+    pub fn approx_eq(&self, other: Self, eps: f32) -> bool {
+        approx_eq_f32(self.x, other.x, eps) && approx_eq_f32(self.y, other.y, eps)
+    }
+
     /// Returns the area of the parallelogram formed by the two vectors.
     /// In 2D, this is equivalent to the magnitude of the cross product.
     pub fn cross(&self, other: Self) -> f32 {
@@ -112,3 +119,16 @@ impl Div<f32> for Vec2 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approx_eq_compares_each_component() {
+        let v = Vec2::new(0.25, -4.0);
+        assert!(v.approx_eq(Vec2::new(0.2505, -4.0005), 1e-3));
+        assert!(!v.approx_eq(Vec2::new(0.25, -4.002), 1e-3));
+        assert!(!v.approx_eq(Vec2::new(0.252, -4.0), 1e-3));
+    }
+}
@@ -1,5 +1,8 @@
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
+use super::utils::approx_eq_f32;
+use super::vec2::Vec2;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Vec3 {
     pub x: f32,
@@ -86,8 +89,17 @@ impl Vec3 {
         }
     }
 
+    /// Returns the length of the vector.
     pub fn magnitude(&self) -> f32 {
-        (self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt()
+        self.magnitude_squared().sqrt()
+    }
+
+    /// Returns the squared length of the vector.
+    ///
+    /// Cheaper than [`Vec3::magnitude`] since it avoids the square root, which makes
+    /// it the better choice for comparing lengths.
+    pub fn magnitude_squared(&self) -> f32 {
+        self.x * self.x + self.y * self.y + self.z * self.z
     }
 
     pub fn add(&self, other: Self) -> Self {
@@ -114,8 +126,16 @@ impl Vec3 {
         }
     }
 
+    /// Returns a unit-length vector pointing in the same direction.
+    ///
+    /// A zero-length vector has no direction, so [`Vec3::ZERO`] is returned instead
+    /// of a vector full of NaNs. This keeps culling and lighting math stable on
+    /// degenerate faces.
     pub fn normalize(&self) -> Self {
         let magnitude = self.magnitude();
+        if magnitude <= f32::EPSILON {
+            return Self::ZERO;
+        }
         Self {
             x: self.x / magnitude,
             y: self.y / magnitude,
@@ -123,12 +143,15 @@ impl Vec3 {
         }
     }
 
+    /// Returns the dot product of two vectors.
     pub fn dot(&self, other: Self) -> f32 {
         self.x * other.x + self.y * other.y + self.z * other.z
     }
 
     /// Returns the cross product of two vectors.
     /// The resulting vector is perpendicular to both input vectors.
+    ///
+    /// In the engine's left-handed coordinate system, `RIGHT.cross(UP)` yields `FORWARD`.
     pub fn cross(&self, other: Self) -> Self {
         Self {
             x: self.y * other.z - self.z * other.y,
@@ -136,6 +159,36 @@ impl Vec3 {
             z: self.x * other.y - self.y * other.x,
         }
     }
+
+    /// Linearly interpolates between `self` and `other`.
+    ///
+    /// Returns `self` when `t = 0` and `other` when `t = 1`. The factor is not
+    /// clamped, so values outside [0, 1] extrapolate along the same line.
+    pub fn lerp(self, other: Vec3, t: f32) -> Vec3 {
+        self + (other - self) * t
+    }
+
+    /// The x and y components, e.g. the pixel position of a screen-space vertex.
+    pub fn xy(self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    /// Whether every component differs from `other`'s by at most `eps`.
+    ///
+    /// Handy for tests, where transformed points rarely match exactly.
+    pub fn approx_eq(&self, other: Self, eps: f32) -> bool {
+        approx_eq_f32(self.x, other.x, eps)
+            && approx_eq_f32(self.y, other.y, eps)
+            && approx_eq_f32(self.z, other.z, eps)
+    }
+
+    /// Reflects the vector about a surface with the given normal.
+    ///
+    /// Computes `self - 2 * dot(self, normal) * normal`. The normal is expected to be
+    /// unit length; the result is typically used as the specular reflection vector.
+    pub fn reflect(self, normal: Vec3) -> Vec3 {
+        self - normal * (2.0 * self.dot(normal))
+    }
 }
 
 /// Component-wise addition of two vectors.
@@ -202,3 +255,75 @@ impl Neg for Vec3 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn approx_eq_compares_each_component() {
+        let v = Vec3::new(1.0, -2.0, 3.0);
+        assert!(v.approx_eq(Vec3::new(1.0005, -2.0005, 2.9995), 1e-3));
+        assert!(!v.approx_eq(Vec3::new(1.0, -2.0, 3.002), 1e-3));
+        assert!(!v.approx_eq(Vec3::new(0.998, -2.0, 3.0), 1e-3));
+    }
+
+    #[test]
+    fn magnitude_of_3_4_5_triangle() {
+        let v = Vec3::new(3.0, 4.0, 0.0);
+        assert_relative_eq!(v.magnitude(), 5.0);
+        assert_relative_eq!(v.magnitude_squared(), 25.0);
+    }
+
+    #[test]
+    fn normalize_produces_unit_vector() {
+        let v = Vec3::new(3.0, 4.0, 0.0).normalize();
+        assert_relative_eq!(v.x, 0.6);
+        assert_relative_eq!(v.y, 0.8);
+        assert_relative_eq!(v.magnitude(), 1.0);
+    }
+
+    #[test]
+    fn normalize_zero_vector_returns_zero() {
+        assert_eq!(Vec3::ZERO.normalize(), Vec3::ZERO);
+    }
+
+    #[test]
+    fn cross_product_of_orthonormal_basis() {
+        assert_eq!(Vec3::RIGHT.cross(Vec3::UP), Vec3::FORWARD);
+        assert_eq!(Vec3::UP.cross(Vec3::FORWARD), Vec3::RIGHT);
+        assert_eq!(Vec3::FORWARD.cross(Vec3::RIGHT), Vec3::UP);
+        assert_eq!(Vec3::UP.cross(Vec3::RIGHT), Vec3::BACK);
+    }
+
+    #[test]
+    fn operators_match_component_wise_math() {
+        let a = Vec3::new(1.0, 2.0, 3.0);
+        let b = Vec3::new(4.0, 5.0, 6.0);
+        assert_eq!(a + b, Vec3::new(5.0, 7.0, 9.0));
+        assert_eq!(b - a, Vec3::new(3.0, 3.0, 3.0));
+        assert_eq!(a * 2.0, Vec3::new(2.0, 4.0, 6.0));
+        assert_eq!(b / 2.0, Vec3::new(2.0, 2.5, 3.0));
+        assert_eq!(-a, Vec3::new(-1.0, -2.0, -3.0));
+        assert_relative_eq!(a.dot(b), 32.0);
+    }
+
+    #[test]
+    fn lerp_interpolates_and_extrapolates() {
+        let a = Vec3::new(0.0, 0.0, 0.0);
+        let b = Vec3::new(2.0, 4.0, -6.0);
+        assert_eq!(a.lerp(b, 0.0), a);
+        assert_eq!(a.lerp(b, 1.0), b);
+        assert_eq!(a.lerp(b, 0.5), Vec3::new(1.0, 2.0, -3.0));
+        assert_eq!(a.lerp(b, 2.0), Vec3::new(4.0, 8.0, -12.0));
+    }
+
+    #[test]
+    fn reflect_off_axis_aligned_plane() {
+        // Incoming ray travelling down and forward hits the floor (normal +Y)
+        let incoming = Vec3::new(1.0, -1.0, 0.5);
+        let reflected = incoming.reflect(Vec3::UP);
+        assert_eq!(reflected, Vec3::new(1.0, 1.0, 0.5));
+    }
+}
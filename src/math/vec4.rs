@@ -35,12 +35,25 @@ impl Vec4 {
         Self::new(v.x, v.y, v.z, w)
     }
 
-    /// Convert to Vec3, discarding w.
-    pub const fn to_vec3(self) -> Vec3 {
+    /// The x, y and z components, discarding w.
+    pub const fn xyz(self) -> Vec3 {
         Vec3::new(self.x, self.y, self.z)
     }
 
-    /// Convert to Vec3 with perspective division (divide by w).
+    /// Convert to Vec3, discarding w. Same as [`Vec4::xyz`].
+    pub const fn to_vec3(self) -> Vec3 {
+        self.xyz()
+    }
+
+    /// Divide x, y and z by w: clip space to normalized device coordinates.
+    ///
+    /// Unlike [`Vec4::to_vec3_perspective`], w is always divided by, so a zero w
+    /// gives infinities. Clip near-plane geometry before dividing.
+    pub fn perspective_divide(self) -> Vec3 {
+        self.xyz() / self.w
+    }
+
+    /// Convert to Vec3 with perspective division, skipping it for w = 0 or w = 1.
     pub fn to_vec3_perspective(self) -> Vec3 {
         if self.w != 0.0 && self.w != 1.0 {
             Vec3::new(self.x / self.w, self.y / self.w, self.z / self.w)
@@ -85,6 +98,14 @@ impl Add<Vec4> for Vec4 {
     }
 }
 
+impl Mul<Vec4> for f32 {
+    type Output = Vec4;
+
+    fn mul(self, rhs: Vec4) -> Self::Output {
+        rhs.scale(self)
+    }
+}
+
 impl Sub<Vec4> for Vec4 {
     type Output = Vec4;
 
@@ -135,3 +156,35 @@ impl From<Vec4> for Vec3 {
         v.to_vec3()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn from_vec3_and_xyz_round_trip() {
+        let v = Vec3::new(1.5, -2.0, 3.25);
+        let homogeneous = Vec4::from_vec3(v, 7.0);
+        assert_eq!(homogeneous.w, 7.0);
+        assert_eq!(homogeneous.xyz(), v);
+    }
+
+    #[test]
+    fn perspective_divide_by_w() {
+        let ndc = Vec4::new(2.0, -4.0, 1.0, 2.0).perspective_divide();
+        assert_eq!(ndc, Vec3::new(1.0, -2.0, 0.5));
+    }
+
+    #[test]
+    fn operators_are_componentwise() {
+        let a = Vec4::new(1.0, 2.0, 3.0, 4.0);
+        let b = Vec4::new(0.5, 0.5, 0.5, 0.5);
+        assert_eq!(a + b, Vec4::new(1.5, 2.5, 3.5, 4.5));
+        assert_eq!(a - b, Vec4::new(0.5, 1.5, 2.5, 3.5));
+        assert_eq!(2.0 * a, a * 2.0);
+        assert_eq!(a / 2.0, Vec4::new(0.5, 1.0, 1.5, 2.0));
+        assert_eq!(-a, Vec4::new(-1.0, -2.0, -3.0, -4.0));
+        assert_relative_eq!(a.dot(b), 5.0);
+    }
+}
@@ -1,11 +1,26 @@
 //! 3D mesh representation and loading.
 //!
 //! Provides the [`Mesh`] struct for storing vertices, normals, and faces, along with
-//! OBJ file loading support via the `tobj` crate.
+//! OBJ and STL file loading support (and glTF with the `gltf` feature).
 
+#[cfg(feature = "gltf")]
+mod gltf_import;
+mod mtl;
+mod obj;
+mod primitives;
+mod stl;
+
+pub use mtl::Material;
+
+#[cfg(test)]
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
 
-use crate::{math::vec3::Vec3, prelude::Vec2};
+use crate::{math::mat4::Mat4, math::vec3::Vec3, math::vec4::Vec4, prelude::Vec2};
 
 /// Represents a triangle face with indices into the vertex array.
 /// Uses 0-based indexing.
@@ -22,21 +37,61 @@ impl Face {
     }
 }
 
+/// An edge and the faces on either side of it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Edge {
+    /// Vertex indices of the endpoints, as they appear in the first face.
+    pub a: u32,
+    pub b: u32,
+    pub faces: [u32; 2],
+    /// False on an open mesh's boundary, where `faces` repeats the only face.
+    pub shared: bool,
+}
+
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum LoadError {
-    Tobj(tobj::LoadError),
-    NoModels,
+    /// The file could not be read.
+    Io(io::Error),
+    /// A statement in the file is malformed.
+    ParseError {
+        /// 1-based line number.
+        line: usize,
+        /// The offending line, trimmed.
+        content: String,
+        reason: String,
+    },
     NoVertices,
-    InvalidFaces,
+    /// A binary file ends before the data its header describes.
+    Truncated {
+        expected: usize,
+        found: usize,
+    },
+    /// The glTF document or its buffers could not be loaded.
+    #[cfg(feature = "gltf")]
+    Gltf(gltf::Error),
+    /// The file uses a feature the importer does not handle.
+    Unsupported(String),
 }
 
 impl fmt::Display for LoadError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            LoadError::Tobj(e) => write!(f, "failed to load OBJ: {}", e),
-            LoadError::NoModels => write!(f, "OBJ file contains no models"),
+            LoadError::Io(e) => write!(f, "failed to read mesh file: {}", e),
+            LoadError::ParseError {
+                line,
+                content,
+                reason,
+            } => write!(f, "line {}: {} in '{}'", line, reason, content),
             LoadError::NoVertices => write!(f, "mesh has no vertices"),
-            LoadError::InvalidFaces => write!(f, "face indices not divisible by 3"),
+            LoadError::Truncated { expected, found } => write!(
+                f,
+                "file is truncated: expected {} bytes, found {}",
+                expected, found
+            ),
+            #[cfg(feature = "gltf")]
+            LoadError::Gltf(e) => write!(f, "failed to load glTF: {}", e),
+            LoadError::Unsupported(reason) => write!(f, "unsupported: {}", reason),
         }
     }
 }
@@ -44,18 +99,37 @@ impl fmt::Display for LoadError {
 impl std::error::Error for LoadError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            LoadError::Tobj(e) => Some(e),
+            LoadError::Io(e) => Some(e),
+            #[cfg(feature = "gltf")]
+            LoadError::Gltf(e) => Some(e),
             _ => None,
         }
     }
 }
 
-impl From<tobj::LoadError> for LoadError {
-    fn from(e: tobj::LoadError) -> Self {
-        LoadError::Tobj(e)
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+#[cfg(feature = "gltf")]
+impl From<gltf::Error> for LoadError {
+    fn from(e: gltf::Error) -> Self {
+        LoadError::Gltf(e)
     }
 }
 
+/// Options controlling how OBJ files are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ObjOptions {
+    /// Replace each `vt` v coordinate with `1.0 - v`.
+    ///
+    /// OBJ places the UV origin at the bottom-left, which [`Texture::sample`](crate::texture::Texture::sample)
+    /// already accounts for. Enable this for exporters that write top-left-origin UVs.
+    pub flip_v: bool,
+}
+
 /// A vertex with position and normal attributes.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub(crate) struct Vertex {
@@ -68,6 +142,15 @@ pub(crate) struct Vertex {
 pub struct Mesh {
     vertices: Vec<Vertex>,
     faces: Vec<Face>,
+    face_normals: Vec<Vec3>,      // Model space, one per face
+    edges: Vec<Edge>,             // Each edge once, with its adjacent faces
+    bounding_sphere: (Vec3, f32), // Model-space center and radius
+    materials: Vec<Material>,
+    face_materials: Vec<Option<usize>>, // Index into `materials`, one per face
+    vertex_colors: Vec<u32>,            // ARGB8888, one per vertex (white unless authored)
+    tangents: Vec<Vec4>,                // Model space, one per vertex, w = bitangent sign
+    wireframe_color: Option<u32>,       // Overrides the engine's wireframe color
+    name: Option<String>,               // OBJ object/group name, if loaded from one
     rotation: Vec3,
     scale: Vec3,
     translation: Vec3,
@@ -81,91 +164,249 @@ impl Mesh {
         scale: Vec3,
         translation: Vec3,
     ) -> Self {
+        let face_normals = compute_face_normals(&vertices, &faces);
+        let edges = compute_edges(&vertices, &faces);
+        let bounding_sphere = compute_bounding_sphere(&vertices);
+        let vertex_colors = vec![0xFFFFFFFF; vertices.len()]; // White leaves materials untinted
+        let tangents = compute_tangents(&vertices, &faces);
         Self {
             vertices,
             faces,
+            face_normals,
+            edges,
+            bounding_sphere,
+            materials: Vec::new(),
+            face_materials: Vec::new(),
+            vertex_colors,
+            tangents,
+            wireframe_color: None,
+            name: None,
             rotation,
             scale,
             translation,
         }
     }
 
-    pub(crate) fn from_obj(file_path: &str) -> Result<Self, LoadError> {
-        let load_options = tobj::LoadOptions {
-            triangulate: true,
-            single_index: true,
-            ..Default::default()
-        };
+    /// Load an OBJ file as one mesh per object or group (`o`/`g` statements).
+    ///
+    /// Each mesh holds only the vertices its faces use and is named after its
+    /// group; faces before the first `o`/`g` form an unnamed mesh. Files without
+    /// faces load as a single mesh, like [`Mesh::from_obj_merged`].
+    pub fn from_obj<P: AsRef<Path>>(path: P, options: &ObjOptions) -> Result<Vec<Self>, LoadError> {
+        let path = path.as_ref();
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        Self::from_obj_reader_grouped(BufReader::new(File::open(path)?), base_dir, options)
+    }
 
-        let (models, _materials) = tobj::load_obj(file_path, &load_options)?;
+    /// Load an OBJ file as a single mesh, ignoring object and group boundaries.
+    pub fn from_obj_merged<P: AsRef<Path>>(
+        path: P,
+        options: &ObjOptions,
+    ) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+        Self::from_obj_reader(BufReader::new(File::open(path)?), base_dir, options)
+    }
 
-        let model = models.into_iter().next().ok_or(LoadError::NoModels)?;
-        let mesh = model.mesh;
+    /// Load a mesh from an ASCII or binary STL file.
+    ///
+    /// Facet corners at the same position become one vertex with the averaged
+    /// facet normal. STL has no texture coordinates, so every UV is zero.
+    pub fn from_stl<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        let data = stl::parse(&std::fs::read(path)?)?;
+        if data.vertices.is_empty() {
+            return Err(LoadError::NoVertices);
+        }
+        Ok(Self::new(
+            data.vertices,
+            data.faces,
+            Vec3::ZERO,
+            Vec3::ONE,
+            Vec3::ZERO,
+        ))
+    }
 
-        if mesh.positions.is_empty() {
+    /// Load a mesh from OBJ data.
+    ///
+    /// Authored `vn` normals are kept per face corner. Corners without one get a
+    /// smooth normal averaged from the surrounding faces.
+    ///
+    /// `mtllib` files are looked up in `base_dir`. Missing libraries and unknown
+    /// `usemtl` names are skipped, leaving those faces with the default color.
+    pub(crate) fn from_obj_reader<R: BufRead>(
+        reader: R,
+        base_dir: &Path,
+        options: &ObjOptions,
+    ) -> Result<Self, LoadError> {
+        let data = obj::parse(reader, options)?;
+        if data.vertices.is_empty() {
             return Err(LoadError::NoVertices);
         }
+        let (library, lookup) = load_obj_materials(&data, base_dir)?;
+        Ok(Self::from_obj_parts(
+            data.vertices,
+            data.faces,
+            data.vertex_colors,
+            &data.face_materials,
+            &lookup,
+            library,
+        ))
+    }
 
-        if mesh.indices.len() % 3 != 0 {
-            return Err(LoadError::InvalidFaces);
-        }
-
-        // With single_index: true, tobj aligns all vertex attributes by index.
-        // This means vertex i's data is found at:
-        //   - positions[i*3 .. i*3+3]  (x, y, z)
-        //   - normals[i*3 .. i*3+3]    (nx, ny, nz)
-        //   - texcoords[i*2 .. i*2+2]  (u, v)
-        //
-        // The flat arrays look like:
-        //   positions:  [x0, y0, z0, x1, y1, z1, x2, y2, z2, ...]
-        //   normals:    [nx0, ny0, nz0, nx1, ny1, nz1, ...]
-        //   texcoords:  [u0, v0, u1, v1, u2, v2, ...]
-        let has_normals = !mesh.normals.is_empty();
-        let has_texcoords = !mesh.texcoords.is_empty();
-        let vertices: Vec<Vertex> = mesh
-            .positions
-            // chunks_exact(3) yields [x, y, z] slices for each vertex
-            .chunks_exact(3)
-            // enumerate gives (vertex_index, position_slice)
-            .enumerate()
-            .map(|(i, p)| {
-                // Normals have 3 components, so vertex i starts at i * 3
-                let normal = if has_normals {
-                    let n = &mesh.normals[i * 3..i * 3 + 3];
-                    Vec3::new(n[0], n[1], n[2])
-                } else {
-                    Vec3::ZERO
-                };
+    /// Load OBJ data as one mesh per object or group, like [`Mesh::from_obj`].
+    pub(crate) fn from_obj_reader_grouped<R: BufRead>(
+        reader: R,
+        base_dir: &Path,
+        options: &ObjOptions,
+    ) -> Result<Vec<Self>, LoadError> {
+        let data = obj::parse(reader, options)?;
+        if data.vertices.is_empty() {
+            return Err(LoadError::NoVertices);
+        }
+        let (library, lookup) = load_obj_materials(&data, base_dir)?;
+        if data.groups.is_empty() {
+            return Ok(vec![Self::from_obj_parts(
+                data.vertices,
+                data.faces,
+                data.vertex_colors,
+                &data.face_materials,
+                &lookup,
+                library,
+            )]);
+        }
 
-                // Texcoords have 2 components (u, v), so vertex i starts at i * 2
-                let texel = if has_texcoords {
-                    let t = &mesh.texcoords[i * 2..i * 2 + 2];
-                    Vec2::new(t[0], t[1])
-                } else {
-                    Vec2::ZERO
+        let meshes = data
+            .groups
+            .iter()
+            .map(|group| {
+                // Renumber the group's vertices in order of first use
+                let mut remap: HashMap<u32, u32> = HashMap::new();
+                let mut vertices = Vec::new();
+                let mut vertex_colors = Vec::new();
+                let mut index = |i: u32| {
+                    *remap.entry(i).or_insert_with(|| {
+                        vertices.push(data.vertices[i as usize]);
+                        vertex_colors.push(data.vertex_colors[i as usize]);
+                        (vertices.len() - 1) as u32
+                    })
                 };
+                let faces: Vec<Face> = data.faces[group.faces.clone()]
+                    .iter()
+                    .map(|face| Face::new(index(face.a), index(face.b), index(face.c)))
+                    .collect();
 
-                Vertex {
-                    position: Vec3::new(p[0], p[1], p[2]),
-                    normal,
-                    texel,
-                }
+                let mut mesh = Self::from_obj_parts(
+                    vertices,
+                    faces,
+                    vertex_colors,
+                    &data.face_materials[group.faces.clone()],
+                    &lookup,
+                    library.clone(),
+                );
+                mesh.name = (!group.name.is_empty()).then(|| group.name.clone());
+                mesh
             })
             .collect();
+        Ok(meshes)
+    }
 
-        let faces: Vec<Face> = mesh
-            .indices
-            .chunks_exact(3)
-            .map(|c| Face::new(c[0], c[1], c[2]))
+    /// Build a mesh from parsed OBJ geometry, resolving materials and filling in
+    /// missing vertex normals.
+    fn from_obj_parts(
+        vertices: Vec<Vertex>,
+        faces: Vec<Face>,
+        vertex_colors: Vec<u32>,
+        face_materials: &[Option<usize>],
+        lookup: &[Option<usize>],
+        library: Vec<Material>,
+    ) -> Self {
+        let mut mesh = Self::new(vertices, faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO);
+        mesh.face_materials = face_materials
+            .iter()
+            .map(|m| m.and_then(|i| lookup[i]))
             .collect();
+        mesh.materials = library;
+        mesh.vertex_colors = vertex_colors;
+        if mesh.vertices.iter().any(|v| v.normal == Vec3::ZERO) {
+            // Smooth shading needs vertex normals, so synthesize the missing ones
+            let smooth_normals = mesh.smooth_normals();
+            for (vertex, normal) in mesh.vertices.iter_mut().zip(smooth_normals) {
+                if vertex.normal == Vec3::ZERO {
+                    vertex.normal = normal;
+                }
+            }
+        }
+        mesh
+    }
 
-        Ok(Self::new(
-            vertices,
-            faces,
-            Vec3::ZERO,
-            Vec3::ONE,
-            Vec3::ZERO,
-        ))
+    /// Replace every vertex normal with the area-weighted average of its incident face normals.
+    ///
+    /// The unnormalized cross product of a face's edges has a magnitude of twice its
+    /// area, so summing those gives large faces proportionally more influence.
+    /// Vertices not referenced by any face get a zero normal.
+    pub fn compute_vertex_normals(&mut self) {
+        let smooth_normals = self.smooth_normals();
+        for (vertex, normal) in self.vertices.iter_mut().zip(smooth_normals) {
+            vertex.normal = normal;
+        }
+        self.tangents = compute_tangents(&self.vertices, &self.faces);
+    }
+
+    /// Flip faces wound inward so every face normal points away from the centroid.
+    ///
+    /// Backface culling treats `(b - a) x (c - a)` as the outward side, so meshes
+    /// exported with the opposite winding render inside-out. The reference is
+    /// only reliable for closed, roughly convex meshes. Vertex normals pointing
+    /// against the corrected faces around them are flipped too, and tangents are
+    /// recomputed to match. Returns the number of faces flipped.
+    pub fn fix_winding(&mut self) -> usize {
+        if self.vertices.is_empty() {
+            return 0;
+        }
+        let centroid = self
+            .vertices
+            .iter()
+            .fold(Vec3::ZERO, |sum, vertex| sum + vertex.position)
+            * (1.0 / self.vertices.len() as f32);
+
+        let mut flipped = 0;
+        for (face, normal) in self.faces.iter_mut().zip(&mut self.face_normals) {
+            let center = (self.vertices[face.a as usize].position
+                + self.vertices[face.b as usize].position
+                + self.vertices[face.c as usize].position)
+                * (1.0 / 3.0);
+            if normal.dot(center - centroid) < 0.0 {
+                std::mem::swap(&mut face.b, &mut face.c);
+                *normal = -*normal;
+                flipped += 1;
+            }
+        }
+
+        if flipped > 0 {
+            let smooth_normals = self.smooth_normals();
+            for (vertex, normal) in self.vertices.iter_mut().zip(smooth_normals) {
+                if vertex.normal.dot(normal) < 0.0 {
+                    vertex.normal = -vertex.normal;
+                }
+            }
+            self.tangents = compute_tangents(&self.vertices, &self.faces);
+        }
+        flipped
+    }
+
+    /// Area-weighted average of the face normals around each vertex.
+    fn smooth_normals(&self) -> Vec<Vec3> {
+        let mut sums = vec![Vec3::ZERO; self.vertices.len()];
+        for face in &self.faces {
+            let [a, b, c] = [face.a, face.b, face.c].map(|i| i as usize);
+            let p0 = self.vertices[a].position;
+            let weighted_normal =
+                (self.vertices[b].position - p0).cross(self.vertices[c].position - p0);
+            for i in [a, b, c] {
+                sums[i] = sums[i] + weighted_normal;
+            }
+        }
+        sums.iter().map(Vec3::normalize).collect()
     }
 
     /// Get the rotation vector
@@ -198,6 +439,56 @@ impl Mesh {
         &mut self.translation
     }
 
+    /// The model (world) matrix: scale, then rotate (Z, Y, then X), then translate.
+    ///
+    /// ```text
+    /// M = T * Rx * Ry * Rz * S
+    /// ```
+    pub fn model_matrix(&self) -> Mat4 {
+        let Vec3 { x, y, z } = self.translation;
+        Mat4::translation(x, y, z) * self.linear_matrix()
+    }
+
+    /// The matrix that takes model-space normals to world space.
+    ///
+    /// This is the inverse transpose of the model matrix without translation.
+    /// Rotation alone would do for uniform scale, but non-uniform scale skews
+    /// normals: scaling a surface by 2 along X must scale its normal by 1/2.
+    pub fn normal_matrix(&self) -> Mat4 {
+        self.linear_matrix().normal_matrix()
+    }
+
+    /// Rotation and scale, without translation.
+    fn linear_matrix(&self) -> Mat4 {
+        Mat4::rotation_x(self.rotation.x)
+            * Mat4::rotation_y(self.rotation.y)
+            * Mat4::rotation_z(self.rotation.z)
+            * Mat4::scaling(self.scale.x, self.scale.y, self.scale.z)
+    }
+
+    /// Model-space center and radius of a sphere enclosing every vertex.
+    ///
+    /// The center is the middle of the axis-aligned bounding box, which is not the
+    /// tightest fit but is cheap and never misses a vertex. Empty meshes have a
+    /// zero radius at the origin.
+    pub fn bounding_sphere(&self) -> (Vec3, f32) {
+        self.bounding_sphere
+    }
+
+    /// World-space bounding sphere, following the model matrix.
+    ///
+    /// Rotation preserves distances, so only the largest scale factor grows the radius.
+    pub fn world_bounding_sphere(&self) -> (Vec3, f32) {
+        let (center, radius) = self.bounding_sphere;
+        let scale = self
+            .scale
+            .x
+            .abs()
+            .max(self.scale.y.abs())
+            .max(self.scale.z.abs());
+        (self.model_matrix() * center, radius * scale)
+    }
+
     /// Get a reference to the vertices
     pub(crate) fn vertices(&self) -> &[Vertex] {
         &self.vertices
@@ -207,4 +498,503 @@ impl Mesh {
     pub(crate) fn faces(&self) -> &[Face] {
         &self.faces
     }
+
+    /// Per-vertex colors (ARGB8888), white unless the file authored them.
+    ///
+    /// Under Gouraud shading they tint each vertex's material color before lighting.
+    pub fn vertex_colors(&self) -> &[u32] {
+        &self.vertex_colors
+    }
+
+    /// Replace the per-vertex colors, one per vertex.
+    ///
+    /// # Panics
+    ///
+    /// If `colors` has a different length than the vertex list.
+    pub fn set_vertex_colors(&mut self, colors: Vec<u32>) {
+        assert_eq!(colors.len(), self.vertices.len(), "one color per vertex");
+        self.vertex_colors = colors;
+    }
+
+    /// Per-vertex tangents for normal mapping, in model space.
+    ///
+    /// `xyz` points along increasing U and `w` is the bitangent's sign, so the
+    /// bitangent (increasing V) is `w * (normal x tangent)`. Vertices without
+    /// usable UVs have a zero tangent.
+    pub fn tangents(&self) -> &[Vec4] {
+        &self.tangents
+    }
+
+    /// Get the material assigned to the face at `index`, if any.
+    pub fn material_for_face(&self, index: usize) -> Option<&Material> {
+        self.face_materials
+            .get(index)
+            .copied()
+            .flatten()
+            .map(|m| &self.materials[m])
+    }
+
+    /// Materials referenced by the mesh's faces, editable in place.
+    pub fn materials_mut(&mut self) -> &mut [Material] {
+        &mut self.materials
+    }
+
+    /// Edge color for this mesh in wireframe modes, overriding
+    /// [`Engine::set_wireframe_color`](crate::Engine::set_wireframe_color) when set.
+    pub fn wireframe_color(&self) -> Option<u32> {
+        self.wireframe_color
+    }
+
+    pub fn set_wireframe_color(&mut self, color: Option<u32>) {
+        self.wireframe_color = color;
+    }
+
+    /// The OBJ object or group this mesh was loaded from, if it was named.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Get the cached normal of the face at `index`.
+    ///
+    /// Normals are unit length and in model space, so transform them with the
+    /// model's normal matrix to get world-space normals. Degenerate faces have a
+    /// zero normal.
+    pub fn face_normal(&self, index: usize) -> Vec3 {
+        #[cfg(test)]
+        FACE_NORMAL_LOOKUPS.with(|count| count.set(count.get() + 1));
+        self.face_normals[index]
+    }
+
+    /// Every edge once, with the faces on either side, for silhouette detection.
+    pub(crate) fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Calls to [`Mesh::face_normal`] on this thread, for checking the update loop's work.
+    pub(crate) static FACE_NORMAL_LOOKUPS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Loads the `mtllib` files an OBJ references and maps each parsed `usemtl`
+/// name to its index in the loaded library (`None` when no library defines it).
+fn load_obj_materials(
+    data: &obj::ObjData,
+    base_dir: &Path,
+) -> Result<(Vec<Material>, Vec<Option<usize>>), LoadError> {
+    let mut library = Vec::new();
+    for file_name in &data.material_libraries {
+        let path = base_dir.join(file_name);
+        if let Ok(file) = File::open(&path) {
+            let mtl_dir = path.parent().unwrap_or(base_dir);
+            library.extend(mtl::parse(BufReader::new(file), mtl_dir)?);
+        }
+    }
+
+    // Keep only the materials the faces use, indexed the way the parser numbered them
+    let lookup = data
+        .material_names
+        .iter()
+        .map(|name| library.iter().position(|m| &m.name == name))
+        .collect();
+    Ok((library, lookup))
+}
+
+/// Computes the model-space normal of each face from its winding: `(b - a) x (c - a)`.
+fn compute_face_normals(vertices: &[Vertex], faces: &[Face]) -> Vec<Vec3> {
+    faces
+        .iter()
+        .map(|face| {
+            let a = vertices[face.a as usize].position;
+            let b = vertices[face.b as usize].position;
+            let c = vertices[face.c as usize].position;
+            (b - a).cross(c - a).normalize()
+        })
+        .collect()
+}
+
+/// Finds each edge and the faces on either side of it.
+///
+/// Endpoints are matched by position rather than index, so seams where a vertex
+/// is split for its UVs or normals still join their faces. Edges shared by more
+/// than two faces keep the first two.
+fn compute_edges(vertices: &[Vertex], faces: &[Face]) -> Vec<Edge> {
+    // Adding 0.0 turns -0.0 into 0.0, so both keys match
+    let key = |i: u32| {
+        let p = vertices[i as usize].position;
+        [p.x, p.y, p.z].map(|c| (c + 0.0).to_bits())
+    };
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut lookup = HashMap::new();
+    for (face_index, face) in faces.iter().enumerate() {
+        for (a, b) in [(face.a, face.b), (face.b, face.c), (face.c, face.a)] {
+            let (ka, kb) = (key(a), key(b));
+            let sorted = if ka <= kb { (ka, kb) } else { (kb, ka) };
+            match lookup.get(&sorted) {
+                Some(&edge_index) => {
+                    let edge: &mut Edge = &mut edges[edge_index];
+                    if !edge.shared {
+                        edge.faces[1] = face_index as u32;
+                        edge.shared = true;
+                    }
+                }
+                None => {
+                    lookup.insert(sorted, edges.len());
+                    edges.push(Edge {
+                        a,
+                        b,
+                        faces: [face_index as u32; 2],
+                        shared: false,
+                    });
+                }
+            }
+        }
+    }
+    edges
+}
+
+/// Computes per-vertex tangents from each face's UV layout.
+///
+/// Solving `e1 = du1 * T + dv1 * B`, `e2 = du2 * T + dv2 * B` for the face edges
+/// gives the directions of increasing U (`T`) and V (`B`). Both are summed over
+/// the faces around each vertex; the bitangent only survives as the sign of `w`,
+/// measured against the face-derived normal.
+fn compute_tangents(vertices: &[Vertex], faces: &[Face]) -> Vec<Vec4> {
+    let mut tangents = vec![Vec3::ZERO; vertices.len()];
+    let mut bitangents = vec![Vec3::ZERO; vertices.len()];
+    let mut winding_normals = vec![Vec3::ZERO; vertices.len()];
+    for face in faces {
+        let corners = [face.a, face.b, face.c].map(|i| i as usize);
+        let [v0, v1, v2] = corners.map(|i| vertices[i]);
+        let (e1, e2) = (v1.position - v0.position, v2.position - v0.position);
+        let (d1, d2) = (v1.texel - v0.texel, v2.texel - v0.texel);
+        let det = d1.x * d2.y - d2.x * d1.y;
+        let normal = e1.cross(e2);
+        for i in corners {
+            winding_normals[i] = winding_normals[i] + normal;
+        }
+        if det.abs() < f32::EPSILON {
+            continue; // Degenerate UVs say nothing about the tangent
+        }
+        let tangent = (e1 * d2.y - e2 * d1.y) * (1.0 / det);
+        let bitangent = (e2 * d1.x - e1 * d2.x) * (1.0 / det);
+        for i in corners {
+            tangents[i] = tangents[i] + tangent;
+            bitangents[i] = bitangents[i] + bitangent;
+        }
+    }
+
+    tangents
+        .iter()
+        .zip(bitangents.iter().zip(vertices.iter().zip(&winding_normals)))
+        .map(|(tangent, (bitangent, (vertex, winding_normal)))| {
+            // Shaders rebuild the bitangent from the vertex normal, so take the
+            // sign against it, falling back to the winding without one
+            let normal = if vertex.normal == Vec3::ZERO {
+                *winding_normal
+            } else {
+                vertex.normal
+            };
+            let t = tangent.normalize();
+            let w = if normal.cross(t).dot(*bitangent) < 0.0 {
+                -1.0
+            } else {
+                1.0
+            };
+            Vec4::new(t.x, t.y, t.z, w)
+        })
+        .collect()
+}
+
+/// Sphere around the bounding box center reaching the farthest vertex.
+fn compute_bounding_sphere(vertices: &[Vertex]) -> (Vec3, f32) {
+    let Some(first) = vertices.first() else {
+        return (Vec3::ZERO, 0.0);
+    };
+    let (min, max) = vertices
+        .iter()
+        .fold((first.position, first.position), |(min, max), v| {
+            let p = v.position;
+            (
+                Vec3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                Vec3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+            )
+        });
+    let center = (min + max) * 0.5;
+    let radius = vertices
+        .iter()
+        .map(|v| (v.position - center).magnitude())
+        .fold(0.0, f32::max);
+    (center, radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unit cube centered on the origin, wound so normals face outward.
+    fn cube() -> Mesh {
+        let vertices = [
+            (-1.0, -1.0, -1.0),
+            (-1.0, 1.0, -1.0),
+            (1.0, 1.0, -1.0),
+            (1.0, -1.0, -1.0),
+            (1.0, 1.0, 1.0),
+            (1.0, -1.0, 1.0),
+            (-1.0, 1.0, 1.0),
+            (-1.0, -1.0, 1.0),
+        ]
+        .map(|(x, y, z)| Vertex {
+            position: Vec3::new(x, y, z),
+            normal: Vec3::ZERO,
+            texel: Vec2::ZERO,
+        });
+        let faces = [
+            (0, 1, 2), // front (-Z)
+            (0, 2, 3),
+            (3, 2, 4), // right (+X)
+            (3, 4, 5),
+            (5, 4, 6), // back (+Z)
+            (5, 6, 7),
+            (7, 6, 1), // left (-X)
+            (7, 1, 0),
+            (1, 6, 4), // top (+Y)
+            (1, 4, 2),
+            (5, 7, 0), // bottom (-Y)
+            (5, 0, 3),
+        ]
+        .map(|(a, b, c)| Face::new(a, b, c));
+        Mesh::new(
+            vertices.to_vec(),
+            faces.to_vec(),
+            Vec3::ZERO,
+            Vec3::ONE,
+            Vec3::ZERO,
+        )
+    }
+
+    #[test]
+    fn fix_winding_flips_only_reversed_faces() {
+        let mut mesh = cube();
+        assert_eq!(mesh.fix_winding(), 0);
+
+        let expected = mesh.clone();
+        for face in mesh.faces.iter_mut().step_by(2) {
+            std::mem::swap(&mut face.a, &mut face.b);
+        }
+        mesh.face_normals = compute_face_normals(&mesh.vertices, &mesh.faces);
+
+        assert_eq!(mesh.fix_winding(), 6);
+        for i in 0..mesh.faces.len() {
+            assert_eq!(mesh.face_normal(i), expected.face_normal(i));
+        }
+    }
+
+    #[test]
+    fn fix_winding_recomputes_tangent_signs() {
+        let expected = Mesh::sphere(1.0, 8, 16);
+
+        // The same sphere exported inside-out: reversed faces and vertex normals
+        let mut mesh = expected.clone();
+        for face in &mut mesh.faces {
+            std::mem::swap(&mut face.b, &mut face.c);
+        }
+        for vertex in &mut mesh.vertices {
+            vertex.normal = -vertex.normal;
+        }
+        mesh.face_normals = compute_face_normals(&mesh.vertices, &mesh.faces);
+        mesh.tangents = compute_tangents(&mesh.vertices, &mesh.faces);
+
+        assert_eq!(mesh.fix_winding(), mesh.faces.len());
+        for (tangent, expected) in mesh.tangents().iter().zip(expected.tangents()) {
+            assert!((tangent.xyz() - expected.xyz()).magnitude() < 1e-5);
+            assert_eq!(tangent.w, expected.w);
+        }
+    }
+
+    #[test]
+    fn tangents_follow_increasing_u_with_bitangent_sign() {
+        // Quad in the z = 0 plane facing -Z, with U along `u_axis` * x
+        let quad = |u_axis: f32| {
+            let vertices =
+                [(-1.0, -1.0), (-1.0, 1.0), (1.0, 1.0), (1.0, -1.0)].map(|(x, y)| Vertex {
+                    position: Vec3::new(x, y, 0.0),
+                    normal: Vec3::ZERO,
+                    texel: Vec2::new((u_axis * x + 1.0) / 2.0, (y + 1.0) / 2.0),
+                });
+            let faces = vec![Face::new(0, 1, 2), Face::new(0, 2, 3)];
+            Mesh::new(vertices.to_vec(), faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO)
+        };
+        let normal = Vec3::new(0.0, 0.0, -1.0);
+
+        for (u_axis, sign) in [(1.0, -1.0), (-1.0, 1.0)] {
+            for tangent in quad(u_axis).tangents() {
+                assert_eq!(tangent.xyz(), Vec3::new(u_axis, 0.0, 0.0));
+                assert_eq!(tangent.w, sign);
+                // Either way the bitangent points along increasing V (+Y)
+                assert_eq!(normal.cross(tangent.xyz()) * tangent.w, Vec3::UP);
+            }
+        }
+    }
+
+    #[test]
+    fn cube_face_normals_point_along_axes() {
+        let mesh = cube();
+        let expected = [
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+        ];
+        for (side, normal) in expected.iter().enumerate() {
+            // Both triangles of a side share its normal
+            assert_eq!(mesh.face_normal(side * 2), *normal, "side {}", side);
+            assert_eq!(mesh.face_normal(side * 2 + 1), *normal, "side {}", side);
+        }
+    }
+
+    #[test]
+    fn shared_edge_vertex_normals_are_averaged() {
+        // Two equal triangles hinged at 90 degrees along the Y axis:
+        // one faces +Z, the other faces -X.
+        let vertices = [
+            (0.0, 0.0, 0.0),
+            (0.0, 1.0, 0.0),
+            (-1.0, 0.0, 0.0),
+            (0.0, 0.0, 1.0),
+        ]
+        .map(|(x, y, z)| Vertex {
+            position: Vec3::new(x, y, z),
+            normal: Vec3::ZERO,
+            texel: Vec2::ZERO,
+        });
+        let faces = vec![Face::new(0, 1, 2), Face::new(0, 3, 1)];
+        let mut mesh = Mesh::new(vertices.to_vec(), faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO);
+        mesh.compute_vertex_normals();
+
+        let normals: Vec<Vec3> = mesh.vertices().iter().map(|v| v.normal).collect();
+        let between = Vec3::new(-1.0, 0.0, 1.0).normalize();
+        assert!((normals[0] - between).magnitude() < 1e-6);
+        assert!((normals[1] - between).magnitude() < 1e-6);
+        assert_eq!(normals[2], Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(normals[3], Vec3::new(-1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn faces_take_diffuse_color_from_their_material() {
+        // Unique per process, so concurrent test runs don't share the file
+        let dir =
+            std::env::temp_dir().join(format!("russsty_two_material_cube_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("cube.mtl"),
+            "newmtl red\nKd 1 0 0\nnewmtl blue\nKd 0 0 1\n",
+        )
+        .unwrap();
+
+        let obj = "\
+mtllib cube.mtl
+v -1 -1 -1
+v -1 1 -1
+v 1 1 -1
+v 1 -1 -1
+v 1 1 1
+v 1 -1 1
+v -1 1 1
+v -1 -1 1
+usemtl red
+f 1 2 3 4
+f 4 3 5 6
+f 6 5 7 8
+usemtl blue
+f 8 7 2 1
+f 2 7 5 3
+f 6 8 1 4
+";
+        let mesh = Mesh::from_obj_reader(obj.as_bytes(), &dir, &ObjOptions::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+        let mesh = mesh.unwrap();
+        assert_eq!(mesh.faces().len(), 12);
+        for face in 0..6 {
+            assert_eq!(mesh.material_for_face(face).unwrap().diffuse, 0xFFFF0000);
+        }
+        for face in 6..12 {
+            assert_eq!(mesh.material_for_face(face).unwrap().diffuse, 0xFF0000FF);
+        }
+    }
+
+    #[test]
+    fn obj_groups_load_as_separate_meshes() {
+        let obj = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+v 5 0 0
+v 6 0 0
+v 5 1 0
+o Base
+g base_quad
+f 1 2 4 3
+o Marker
+f 5 6 7
+";
+        let options = ObjOptions::default();
+        let meshes =
+            Mesh::from_obj_reader_grouped(obj.as_bytes(), Path::new("."), &options).unwrap();
+        assert_eq!(meshes.len(), 2);
+        assert_eq!(meshes[0].name(), Some("base_quad"));
+        assert_eq!(meshes[0].faces().len(), 2);
+        assert_eq!(meshes[0].vertices().len(), 4);
+        assert_eq!(meshes[1].name(), Some("Marker"));
+        assert_eq!(meshes[1].faces().len(), 1);
+        assert_eq!(meshes[1].vertices()[0].position, Vec3::new(5.0, 0.0, 0.0));
+
+        let merged = Mesh::from_obj_reader(obj.as_bytes(), Path::new("."), &options).unwrap();
+        assert_eq!(merged.faces().len(), 3);
+        assert_eq!(merged.name(), None);
+    }
+
+    #[test]
+    fn missing_material_library_falls_back_to_no_material() {
+        let obj = "mtllib missing.mtl\nusemtl red\nv 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        let mesh =
+            Mesh::from_obj_reader(obj.as_bytes(), Path::new("."), &ObjOptions::default()).unwrap();
+        assert_eq!(mesh.material_for_face(0), None);
+    }
+
+    #[test]
+    fn face_normals_ignore_model_transform() {
+        let mut mesh = cube();
+        mesh.rotation_mut().y = 1.0;
+        *mesh.scale_mut() = Vec3::new(2.0, 3.0, 4.0);
+        assert_eq!(mesh.face_normal(0), Vec3::new(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn normal_matrix_keeps_normals_perpendicular_under_non_uniform_scale() {
+        let mut mesh = cube();
+        *mesh.scale_mut() = Vec3::new(3.0, 1.0, 0.5);
+        *mesh.rotation_mut() = Vec3::new(0.3, -0.7, 0.2);
+        *mesh.translation_mut() = Vec3::new(5.0, -2.0, 1.0);
+
+        let model = mesh.model_matrix();
+        let normal_matrix = mesh.normal_matrix();
+        for (i, face) in mesh.faces().iter().enumerate() {
+            let [a, b, c] =
+                [face.a, face.b, face.c].map(|v| model * mesh.vertices()[v as usize].position);
+            let normal = (normal_matrix * mesh.face_normal(i)).normalize();
+            // Still perpendicular to both world-space edges, and still outward
+            assert!(normal.dot((b - a).normalize()).abs() < 1e-4);
+            assert!(normal.dot((c - a).normalize()).abs() < 1e-4);
+            assert!(normal.dot((b - a).cross(c - a)) > 0.0);
+        }
+    }
 }
@@ -0,0 +1,335 @@
+//! glTF 2.0 mesh import (requires the `gltf` feature).
+//!
+//! Only the first primitive of the first mesh is imported: positions, normals,
+//! the first UV set and vertex color set, indices, and the material's base color
+//! and texture. Accessors are read through the `gltf` crate, so interleaved and
+//! separate vertex buffers, `.gltf` with external or embedded buffers, and binary
+//! `.glb` files all work.
+//!
+//! glTF puts the UV origin at the top-left of the image while the engine samples
+//! with the origin at the bottom-left (like OBJ), so V is flipped on import.
+
+use std::path::Path;
+
+use gltf::image::{Format, Source};
+use gltf::mesh::Mode;
+
+use super::{Face, LoadError, Material, Mesh, Vertex};
+use crate::colors::pack_color_linear;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::texture::Texture;
+
+impl Mesh {
+    /// Load the first mesh primitive from a `.gltf` or `.glb` file.
+    ///
+    /// Missing normals are synthesized as smooth vertex normals. The primitive's
+    /// base color factor becomes the diffuse color of a single material covering
+    /// every face. An external base color texture is recorded as its
+    /// `diffuse_texture`; one embedded in the file is decoded into `diffuse_image`.
+    pub fn from_gltf<P: AsRef<Path>>(path: P) -> Result<Mesh, LoadError> {
+        let path = path.as_ref();
+        let (document, buffers, images) = gltf::import(path)?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        let primitive = document
+            .meshes()
+            .next()
+            .and_then(|mesh| mesh.primitives().next())
+            .ok_or(LoadError::NoVertices)?;
+        if primitive.mode() != Mode::Triangles {
+            return Err(LoadError::Unsupported(format!(
+                "primitive mode {:?}, only triangles are supported",
+                primitive.mode()
+            )));
+        }
+
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+        let positions: Vec<[f32; 3]> = reader
+            .read_positions()
+            .map(|positions| positions.collect())
+            .unwrap_or_default();
+        if positions.is_empty() {
+            return Err(LoadError::NoVertices);
+        }
+        let normals: Option<Vec<[f32; 3]>> = reader.read_normals().map(|n| n.collect());
+        let texcoords: Option<Vec<[f32; 2]>> =
+            reader.read_tex_coords(0).map(|t| t.into_f32().collect());
+        // glTF vertex colors are linear; the engine stores sRGB
+        let vertex_colors: Option<Vec<u32>> = reader.read_colors(0).map(|c| {
+            c.into_rgba_f32()
+                .map(|[r, g, b, a]| pack_color_linear(r, g, b, a))
+                .collect()
+        });
+        // Non-indexed primitives use each run of three vertices as a triangle
+        let indices: Vec<u32> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+
+        if !indices.len().is_multiple_of(3) {
+            return Err(LoadError::Unsupported(format!(
+                "{} indices do not form whole triangles",
+                indices.len()
+            )));
+        }
+        if let Some(&index) = indices.iter().find(|&&i| i as usize >= positions.len()) {
+            return Err(LoadError::Unsupported(format!(
+                "index {} out of range for {} vertices",
+                index,
+                positions.len()
+            )));
+        }
+
+        let vertices = positions
+            .iter()
+            .enumerate()
+            .map(|(i, &[x, y, z])| Vertex {
+                position: Vec3::new(x, y, z),
+                normal: normals
+                    .as_ref()
+                    .map_or(Vec3::ZERO, |n| Vec3::new(n[i][0], n[i][1], n[i][2])),
+                texel: texcoords
+                    .as_ref()
+                    .map_or(Vec2::ZERO, |t| Vec2::new(t[i][0], 1.0 - t[i][1])),
+            })
+            .collect();
+        let faces: Vec<Face> = indices
+            .chunks_exact(3)
+            .map(|corners| Face::new(corners[0], corners[1], corners[2]))
+            .collect();
+
+        let mut mesh = Self::new(vertices, faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO);
+        if normals.is_none() {
+            mesh.compute_vertex_normals();
+        }
+        if let Some(colors) = vertex_colors {
+            mesh.vertex_colors = colors;
+        }
+
+        let material = primitive.material();
+        let pbr = material.pbr_metallic_roughness();
+        // The base color factor is linear too
+        let [r, g, b, a] = pbr.base_color_factor();
+        let mut base = Material::new(material.name().unwrap_or("default"));
+        base.diffuse = pack_color_linear(r, g, b, a);
+        if let Some(info) = pbr.base_color_texture() {
+            let image = info.texture().source();
+            match image.source() {
+                Source::Uri { uri, .. } if !uri.starts_with("data:") => {
+                    base.diffuse_texture = Some(base_dir.join(uri));
+                }
+                _ => base.diffuse_image = Some(embedded_texture(&images[image.index()])?),
+            }
+        }
+        mesh.face_materials = vec![Some(0); mesh.faces.len()];
+        mesh.materials = vec![base];
+
+        Ok(mesh)
+    }
+}
+
+/// Convert an image decoded by `gltf::import` into a texture.
+fn embedded_texture(image: &gltf::image::Data) -> Result<Texture, LoadError> {
+    let rgba: Vec<u8> = match image.format {
+        Format::R8G8B8A8 => image.pixels.clone(),
+        Format::R8G8B8 => image
+            .pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        Format::R8G8 => image
+            .pixels
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        Format::R8 => image.pixels.iter().flat_map(|&l| [l, l, l, 255]).collect(),
+        format => {
+            return Err(LoadError::Unsupported(format!(
+                "embedded image format {format:?}"
+            )))
+        }
+    };
+    Texture::from_rgba(&rgba, image.width, image.height)
+        .map_err(|e| LoadError::Unsupported(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLOATS_PER_VERTEX: usize = 8; // Interleaved position, normal, UV
+
+    /// A 2x2x2 cube with four vertices per side, interleaved into one buffer.
+    fn cube_buffer() -> (Vec<u8>, usize, usize) {
+        let axes = [
+            Vec3::RIGHT,
+            Vec3::LEFT,
+            Vec3::UP,
+            Vec3::DOWN,
+            Vec3::FORWARD,
+            Vec3::BACK,
+        ];
+        let mut vertex_bytes = Vec::new();
+        let mut index_bytes = Vec::new();
+        for (side, normal) in axes.iter().enumerate() {
+            // Tangents with u x v = normal, so (b - a) x (c - a) points outward
+            let u = if normal.y == 0.0 {
+                Vec3::UP.cross(*normal)
+            } else {
+                Vec3::RIGHT
+            };
+            let v = normal.cross(u);
+            let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+            for (s, t) in corners {
+                let p = *normal + u * s + v * t;
+                let floats = [
+                    p.x,
+                    p.y,
+                    p.z,
+                    normal.x,
+                    normal.y,
+                    normal.z,
+                    (s + 1.0) / 2.0,
+                    0.0,
+                ];
+                for f in floats {
+                    vertex_bytes.extend(f32::to_le_bytes(f));
+                }
+            }
+            let base = side as u16 * 4;
+            for i in [0, 1, 2, 0, 2, 3] {
+                index_bytes.extend((base + i).to_le_bytes());
+            }
+        }
+        let vertex_len = vertex_bytes.len();
+        let index_len = index_bytes.len();
+        vertex_bytes.extend(index_bytes);
+        (vertex_bytes, vertex_len, index_len)
+    }
+
+    /// Wrap JSON and a binary buffer into a GLB container.
+    fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+        let mut json = json.as_bytes().to_vec();
+        json.resize(json.len().next_multiple_of(4), b' ');
+        let mut bin = bin.to_vec();
+        bin.resize(bin.len().next_multiple_of(4), 0);
+
+        let total = 12 + 8 + json.len() + 8 + bin.len();
+        let mut out = Vec::with_capacity(total);
+        out.extend(b"glTF");
+        out.extend(2u32.to_le_bytes());
+        out.extend((total as u32).to_le_bytes());
+        out.extend((json.len() as u32).to_le_bytes());
+        out.extend(b"JSON");
+        out.extend(json);
+        out.extend((bin.len() as u32).to_le_bytes());
+        out.extend(b"BIN\0");
+        out.extend(bin);
+        out
+    }
+
+    #[test]
+    fn imports_interleaved_cube() {
+        let (mut bin, vertex_len, index_len) = cube_buffer();
+        let stride = FLOATS_PER_VERTEX * 4;
+
+        // A 2x1 RGB base color texture stored in the binary chunk
+        let mut png = std::io::Cursor::new(Vec::new());
+        image::RgbImage::from_raw(2, 1, vec![255, 0, 0, 0, 0, 255])
+            .unwrap()
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        let png = png.into_inner();
+        let image_offset = bin.len();
+        bin.extend(&png);
+
+        let json = format!(
+            r#"{{
+  "asset": {{ "version": "2.0" }},
+  "buffers": [{{ "byteLength": {total} }}],
+  "bufferViews": [
+    {{ "buffer": 0, "byteOffset": 0, "byteLength": {vertex_len}, "byteStride": {stride}, "target": 34962 }},
+    {{ "buffer": 0, "byteOffset": {vertex_len}, "byteLength": {index_len}, "target": 34963 }},
+    {{ "buffer": 0, "byteOffset": {image_offset}, "byteLength": {image_len} }}
+  ],
+  "accessors": [
+    {{ "bufferView": 0, "byteOffset": 0, "componentType": 5126, "count": 24, "type": "VEC3",
+       "min": [-1, -1, -1], "max": [1, 1, 1] }},
+    {{ "bufferView": 0, "byteOffset": 12, "componentType": 5126, "count": 24, "type": "VEC3" }},
+    {{ "bufferView": 0, "byteOffset": 24, "componentType": 5126, "count": 24, "type": "VEC2" }},
+    {{ "bufferView": 1, "componentType": 5123, "count": 36, "type": "SCALAR" }}
+  ],
+  "images": [{{ "bufferView": 2, "mimeType": "image/png" }}],
+  "textures": [{{ "source": 0 }}],
+  "materials": [{{ "name": "red", "pbrMetallicRoughness": {{
+    "baseColorFactor": [1, 0, 0, 1], "baseColorTexture": {{ "index": 0 }}
+  }} }}],
+  "meshes": [{{ "primitives": [{{
+    "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }},
+    "indices": 3,
+    "material": 0
+  }}] }}]
+}}"#,
+            total = bin.len(),
+            image_len = png.len(),
+        );
+        let path = std::env::temp_dir().join(format!("russsty_cube_{}.glb", std::process::id()));
+        std::fs::write(&path, glb(&json, &bin)).unwrap();
+
+        let mesh = Mesh::from_gltf(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(mesh.vertices().len(), 24);
+        assert_eq!(mesh.faces().len(), 12);
+
+        // Stride-separated normals were read back, and agree with the winding
+        for (i, face) in mesh.faces().iter().enumerate() {
+            let normal = mesh.vertices()[face.a as usize].normal;
+            assert_eq!(normal.magnitude(), 1.0);
+            assert!((mesh.face_normal(i) - normal).magnitude() < 1e-6);
+        }
+        // UVs are flipped into the engine's bottom-left origin
+        assert_eq!(mesh.vertices()[1].texel, Vec2::new(1.0, 1.0));
+        let material = mesh.material_for_face(11).unwrap();
+        assert_eq!(material.diffuse, 0xFFFF0000);
+
+        // The embedded PNG is decoded rather than referenced by path
+        assert_eq!(material.diffuse_texture, None);
+        let texture = material.diffuse_image.as_ref().unwrap();
+        assert_eq!((texture.width(), texture.height()), (2, 1));
+        assert_eq!(texture.sample(0.25, 0.5), 0xFFFF0000);
+        assert_eq!(texture.sample(0.75, 0.5), 0xFF0000FF);
+    }
+
+    #[test]
+    fn base_color_factor_is_linear() {
+        let mut bin = Vec::new();
+        for f in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+            bin.extend(f.to_le_bytes());
+        }
+        let json = r#"{
+  "asset": { "version": "2.0" },
+  "buffers": [{ "byteLength": 36 }],
+  "bufferViews": [{ "buffer": 0, "byteLength": 36 }],
+  "accessors": [{ "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                  "min": [0, 0, 0], "max": [1, 1, 0] }],
+  "materials": [{ "pbrMetallicRoughness": { "baseColorFactor": [0.5, 0.5, 0.5, 1] } }],
+  "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 }, "material": 0 }] }]
+}"#;
+        let path = std::env::temp_dir().join(format!("russsty_gray_{}.glb", std::process::id()));
+        std::fs::write(&path, glb(json, &bin)).unwrap();
+
+        let mesh = Mesh::from_gltf(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        // Linear 0.5 is about 188 in sRGB, not 128
+        assert_eq!(mesh.material_for_face(0).unwrap().diffuse, 0xFFBCBCBC);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(matches!(
+            Mesh::from_gltf("does/not/exist.gltf"),
+            Err(LoadError::Gltf(_))
+        ));
+    }
+}
@@ -0,0 +1,117 @@
+//! Wavefront MTL material parsing.
+//!
+//! Only the diffuse properties are read:
+//!
+//! ```text
+//! newmtl name       start a material
+//! Kd r g b          diffuse color, each channel in [0, 1]
+//! map_Kd path       diffuse texture, relative to the MTL file
+//! ```
+
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use super::LoadError;
+use crate::colors::{self, pack_color};
+use crate::texture::Texture;
+
+/// A surface material referenced by OBJ `usemtl` statements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    pub name: String,
+    /// Diffuse color (ARGB8888), used as the base fill color.
+    pub diffuse: u32,
+    /// Diffuse texture path, resolved against the MTL file's directory.
+    pub diffuse_texture: Option<PathBuf>,
+    /// Diffuse texture decoded from the model file itself (embedded glTF images).
+    pub diffuse_image: Option<Texture>,
+    /// Scales the engine's ambient level on this material's faces (default 1.0).
+    pub ambient: f32,
+}
+
+impl Material {
+    /// A material using the engine's default fill color.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            diffuse: colors::FILL,
+            diffuse_texture: None,
+            diffuse_image: None,
+            ambient: 1.0,
+        }
+    }
+}
+
+/// Parses MTL data. `base_dir` is where texture paths are resolved from.
+pub(crate) fn parse<R: BufRead>(reader: R, base_dir: &Path) -> Result<Vec<Material>, LoadError> {
+    let mut materials: Vec<Material> = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        let error = |reason| LoadError::ParseError {
+            line: i + 1,
+            content: line.to_string(),
+            reason,
+        };
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+
+        match keyword {
+            "newmtl" => materials.push(Material::new(rest)),
+            "Kd" => {
+                let material = current(&mut materials).map_err(error)?;
+                let mut channels = [0.0; 3];
+                let mut tokens = rest.split_whitespace();
+                for channel in channels.iter_mut() {
+                    *channel = tokens
+                        .next()
+                        .and_then(|t| t.parse().ok())
+                        .ok_or_else(|| error(format!("invalid diffuse color '{}'", rest)))?;
+                }
+                material.diffuse = pack_color(channels[0], channels[1], channels[2], 1.0);
+            }
+            "map_Kd" => {
+                current(&mut materials).map_err(error)?.diffuse_texture = Some(base_dir.join(rest));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+/// The material being defined, or an error if no `newmtl` has been seen yet.
+fn current(materials: &mut [Material]) -> Result<&mut Material, String> {
+    materials
+        .last_mut()
+        .ok_or_else(|| "material property before 'newmtl'".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_diffuse_color_and_texture() {
+        let mtl = "\
+newmtl red
+Kd 1.0 0.0 0.0
+
+newmtl crate wood
+Kd 0 0 1
+map_Kd textures/wood.png
+";
+        let materials = parse(mtl.as_bytes(), Path::new("assets")).unwrap();
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0].name, "red");
+        assert_eq!(materials[0].diffuse, 0xFFFF0000);
+        assert_eq!(materials[0].diffuse_texture, None);
+        assert_eq!(materials[1].name, "crate wood");
+        assert_eq!(materials[1].diffuse, 0xFF0000FF);
+        assert_eq!(
+            materials[1].diffuse_texture,
+            Some(PathBuf::from("assets/textures/wood.png"))
+        );
+    }
+}
@@ -0,0 +1,486 @@
+//! Wavefront OBJ parsing.
+//!
+//! Reads the subset of the format the renderer uses:
+//!
+//! ```text
+//! v  x y z [r g b]  vertex position, with an optional color in [0, 1]
+//! vt u v            texture coordinate
+//! vn x y z          vertex normal
+//! f  v/vt/vn ...    face (vt and vn are optional: v, v/vt, v//vn, v/vt/vn)
+//! mtllib file ...   material libraries to load
+//! usemtl name       material for the faces that follow
+//! o name / g name   object or group the faces that follow belong to
+//! ```
+//!
+//! Every other statement (`s`, comments, ...) is skipped.
+//!
+//! # Index Layout
+//!
+//! OBJ indexes positions, UVs, and normals separately, while the renderer wants a
+//! single index per vertex. Each distinct `v/vt/vn` combination becomes one
+//! [`Vertex`], and faces index into those. Corners without a `vn` get a zero
+//! normal so the caller can tell authored normals from missing ones, even when
+//! only some faces reference normals.
+//!
+//! Faces with more than three corners are fan-triangulated.
+//!
+//! The file is streamed line by line through one reused buffer, so peak memory
+//! follows the parsed geometry rather than the file's size.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::ops::Range;
+
+use super::{Face, LoadError, ObjOptions, Vertex};
+use crate::colors::pack_color;
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+/// Position, UV, and normal indices of one face corner (0-based).
+type CornerKey = (usize, Option<usize>, Option<usize>);
+
+/// Geometry and material references read from an OBJ file.
+#[derive(Debug, Default)]
+pub(crate) struct ObjData {
+    pub vertices: Vec<Vertex>,
+    pub faces: Vec<Face>,
+    /// ARGB8888 color for each vertex; white where the `v` line had none.
+    pub vertex_colors: Vec<u32>,
+    /// MTL files named by `mtllib`, relative to the OBJ file.
+    pub material_libraries: Vec<String>,
+    /// Distinct names used by `usemtl`, in order of first use.
+    pub material_names: Vec<String>,
+    /// Index into `material_names` for each face, if one was active.
+    pub face_materials: Vec<Option<usize>>,
+    /// Runs of faces started by `o` or `g`, in file order. Faces before the
+    /// first statement form an unnamed group. Empty when there are no faces.
+    pub groups: Vec<ObjGroup>,
+}
+
+/// A named run of consecutive faces.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ObjGroup {
+    /// The `o`/`g` name, or empty for faces before any such statement.
+    pub name: String,
+    pub faces: Range<usize>,
+}
+
+/// Parses OBJ data into single-indexed vertices and triangle faces.
+pub(crate) fn parse<R: BufRead>(mut reader: R, options: &ObjOptions) -> Result<ObjData, LoadError> {
+    let mut positions: Vec<Vec3> = Vec::new();
+    let mut position_colors: Vec<u32> = Vec::new();
+    let mut texcoords: Vec<Vec2> = Vec::new();
+    let mut normals: Vec<Vec3> = Vec::new();
+
+    let mut data = ObjData::default();
+    let vertices = &mut data.vertices;
+    let vertex_colors = &mut data.vertex_colors;
+    let mut corner_indices: HashMap<CornerKey, u32> = HashMap::new();
+    let mut current_material: Option<usize> = None;
+    let mut corners: Vec<u32> = Vec::new();
+
+    // Stream one line at a time through a single reused buffer
+    let mut line = String::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        line_number += 1;
+        let error = |reason| LoadError::ParseError {
+            line: line_number,
+            content: line.trim().to_string(),
+            reason,
+        };
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("v") => {
+                let [x, y, z] = parse_floats(&mut tokens).map_err(error)?;
+                positions.push(Vec3::new(x, y, z));
+                // A lone fourth value is a homogeneous w, not a color
+                let rest = [tokens.next(), tokens.next(), tokens.next()];
+                let color = if let [Some(r), Some(g), Some(b)] = rest {
+                    let [r, g, b] = parse_floats(&mut [r, g, b].into_iter()).map_err(error)?;
+                    pack_color(r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0), 1.0)
+                } else {
+                    0xFFFFFFFF
+                };
+                position_colors.push(color);
+            }
+            Some("vt") => {
+                let [u, v] = parse_floats(&mut tokens).map_err(error)?;
+                let v = if options.flip_v { 1.0 - v } else { v };
+                texcoords.push(Vec2::new(u, v));
+            }
+            Some("vn") => {
+                let [x, y, z] = parse_floats(&mut tokens).map_err(error)?;
+                normals.push(Vec3::new(x, y, z));
+            }
+            Some("f") => {
+                corners.clear();
+                for token in tokens {
+                    let key =
+                        parse_corner(token, [positions.len(), texcoords.len(), normals.len()])
+                            .map_err(error)?;
+                    let index = *corner_indices.entry(key).or_insert_with(|| {
+                        let (v, vt, vn) = key;
+                        vertices.push(Vertex {
+                            position: positions[v],
+                            normal: vn.map_or(Vec3::ZERO, |n| normals[n]),
+                            texel: vt.map_or(Vec2::ZERO, |t| texcoords[t]),
+                        });
+                        vertex_colors.push(position_colors[v]);
+                        (vertices.len() - 1) as u32
+                    });
+                    corners.push(index);
+                }
+
+                if corners.len() < 3 {
+                    return Err(error(format!(
+                        "face needs at least 3 vertices, found {}",
+                        corners.len()
+                    )));
+                }
+
+                // Fan-triangulate polygons around the first corner
+                for pair in corners[1..].windows(2) {
+                    data.faces.push(Face::new(corners[0], pair[0], pair[1]));
+                    data.face_materials.push(current_material);
+                }
+
+                if data.groups.is_empty() {
+                    data.groups.push(ObjGroup {
+                        name: String::new(),
+                        faces: 0..0,
+                    });
+                }
+                if let Some(group) = data.groups.last_mut() {
+                    group.faces.end = data.faces.len();
+                }
+            }
+            Some(keyword @ ("o" | "g")) => {
+                let name = line.trim()[keyword.len()..].trim().to_string();
+                let start = data.faces.len();
+                match data.groups.last_mut() {
+                    // `o` directly followed by `g` names one run; the later name is more specific
+                    Some(group) if group.faces.is_empty() => group.name = name,
+                    _ => data.groups.push(ObjGroup {
+                        name,
+                        faces: start..start,
+                    }),
+                }
+            }
+            Some("mtllib") => {
+                data.material_libraries.extend(tokens.map(str::to_string));
+            }
+            Some("usemtl") => {
+                let name = line.trim()["usemtl".len()..].trim();
+                let index = match data.material_names.iter().position(|n| n == name) {
+                    Some(index) => index,
+                    None => {
+                        data.material_names.push(name.to_string());
+                        data.material_names.len() - 1
+                    }
+                };
+                current_material = Some(index);
+            }
+            _ => {}
+        }
+    }
+
+    // Trailing or back-to-back statements can leave groups without faces
+    data.groups.retain(|group| !group.faces.is_empty());
+    Ok(data)
+}
+
+/// Parses the next `N` whitespace-separated floats, ignoring any extras (e.g. `w`).
+fn parse_floats<'a, const N: usize>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<[f32; N], String> {
+    let mut values = [0.0; N];
+    for value in values.iter_mut() {
+        let token = tokens
+            .next()
+            .ok_or_else(|| format!("expected {} numbers", N))?;
+        *value = token
+            .parse()
+            .map_err(|_| format!("invalid number '{}'", token))?;
+    }
+    Ok(values)
+}
+
+/// Parses a face corner (`v`, `v/vt`, `v//vn`, or `v/vt/vn`) into 0-based indices.
+///
+/// `counts` holds how many positions, UVs, and normals have been read so far.
+fn parse_corner(token: &str, counts: [usize; 3]) -> Result<CornerKey, String> {
+    let mut parts = token.split('/');
+    let mut next_index = |count: usize| -> Result<Option<usize>, String> {
+        match parts.next() {
+            None | Some("") => Ok(None),
+            Some(raw) => resolve_index(raw, count).map(Some),
+        }
+    };
+
+    let v = next_index(counts[0])?
+        .ok_or_else(|| format!("face corner '{}' has no vertex index", token))?;
+    let vt = next_index(counts[1])?;
+    let vn = next_index(counts[2])?;
+    Ok((v, vt, vn))
+}
+
+/// Converts a 1-based (or negative, relative) OBJ index into a 0-based index.
+fn resolve_index(raw: &str, count: usize) -> Result<usize, String> {
+    let index: i64 = raw
+        .parse()
+        .map_err(|_| format!("invalid index '{}'", raw))?;
+
+    // Negative indices count back from the most recently defined element
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(format!("index {} out of range ({} defined)", index, count));
+    }
+    Ok(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_normals_per_face_corner() {
+        let obj = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+vn 0 0 -1
+vn 0 0 1
+f 1//1 2//1 3//1
+f 2//2 4//2 3//2
+";
+        let ObjData {
+            vertices, faces, ..
+        } = parse(obj.as_bytes(), &ObjOptions::default()).unwrap();
+
+        // Corners 2 and 3 are shared by position but not by normal, so they are split
+        assert_eq!(vertices.len(), 6);
+        assert_eq!(faces.len(), 2);
+        for index in [faces[0].a, faces[0].b, faces[0].c] {
+            assert_eq!(vertices[index as usize].normal, Vec3::new(0.0, 0.0, -1.0));
+        }
+        for index in [faces[1].a, faces[1].b, faces[1].c] {
+            assert_eq!(vertices[index as usize].normal, Vec3::new(0.0, 0.0, 1.0));
+        }
+        assert_eq!(
+            vertices[faces[1].b as usize].position,
+            Vec3::new(1.0, 1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn corners_without_normals_get_zero_normal() {
+        let obj = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+v 1 1 0
+vn 0 0 -1
+f 1//1 2//1 3//1
+f 2 4 3
+";
+        let ObjData {
+            vertices, faces, ..
+        } = parse(obj.as_bytes(), &ObjOptions::default()).unwrap();
+        assert_eq!(
+            vertices[faces[0].a as usize].normal,
+            Vec3::new(0.0, 0.0, -1.0)
+        );
+        assert_eq!(vertices[faces[1].b as usize].normal, Vec3::ZERO);
+    }
+
+    const TEXTURED_QUAD: &str = "\
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+f 1/1 2/2 3/3 4/4
+";
+
+    /// UV of each quad corner, keyed by position.
+    fn corner_uvs(vertices: &[Vertex]) -> Vec<(Vec3, Vec2)> {
+        vertices.iter().map(|v| (v.position, v.texel)).collect()
+    }
+
+    #[test]
+    fn texcoords_land_on_their_corners() {
+        let ObjData {
+            vertices, faces, ..
+        } = parse(TEXTURED_QUAD.as_bytes(), &ObjOptions::default()).unwrap();
+        assert_eq!(faces.len(), 2);
+        assert_eq!(
+            corner_uvs(&vertices),
+            vec![
+                (Vec3::new(-1.0, -1.0, 0.0), Vec2::new(0.0, 0.0)),
+                (Vec3::new(1.0, -1.0, 0.0), Vec2::new(1.0, 0.0)),
+                (Vec3::new(1.0, 1.0, 0.0), Vec2::new(1.0, 1.0)),
+                (Vec3::new(-1.0, 1.0, 0.0), Vec2::new(0.0, 1.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn quad_fans_into_two_triangles_sharing_a_diagonal() {
+        let obj = "\
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 -1
+f 1/1/1 2/2/1 3/3/1 4/4/1
+";
+        let ObjData {
+            vertices, faces, ..
+        } = parse(obj.as_bytes(), &ObjOptions::default()).unwrap();
+
+        // Both triangles share the diagonal from the first corner to the third
+        assert_eq!(faces, vec![Face::new(0, 1, 2), Face::new(0, 2, 3)]);
+        // Every corner keeps its own UV and normal
+        for (vertex, uv) in vertices
+            .iter()
+            .zip([(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)])
+        {
+            assert_eq!(vertex.texel, Vec2::new(uv.0, uv.1));
+            assert_eq!(vertex.normal, Vec3::new(0.0, 0.0, -1.0));
+        }
+    }
+
+    #[test]
+    fn flip_v_mirrors_texcoords() {
+        let options = ObjOptions { flip_v: true };
+        let vertices = parse(TEXTURED_QUAD.as_bytes(), &options).unwrap().vertices;
+        let uvs: Vec<Vec2> = vertices.iter().map(|v| v.texel).collect();
+        assert_eq!(
+            uvs,
+            vec![
+                Vec2::new(0.0, 1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_face_index_reports_its_line() {
+        let obj = "\
+# exported quad
+v 0 0 0
+v 1 0 0
+v 0 1 0
+
+f 1 2 3
+f 1 3 9
+";
+        let err = parse(obj.as_bytes(), &ObjOptions::default()).unwrap_err();
+        match &err {
+            LoadError::ParseError {
+                line,
+                content,
+                reason,
+            } => {
+                assert_eq!(*line, 7);
+                assert_eq!(content, "f 1 3 9");
+                assert_eq!(reason, "index 9 out of range (3 defined)");
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        assert_eq!(
+            err.to_string(),
+            "line 7: index 9 out of range (3 defined) in 'f 1 3 9'"
+        );
+    }
+
+    #[test]
+    fn negative_indices_match_positive_ones() {
+        let positive = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 0 1
+f 1/1 2/2 3/3
+v 1 1 0
+vt 1 1
+f 2/2 4/4 3/3
+";
+        // Each face counts back from the elements defined so far
+        let negative = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 0 1
+f -3/-3 -2/-2 -1/-1
+v 1 1 0
+vt 1 1
+f -3/-3 -1/-1 -2/-2
+";
+        let options = ObjOptions::default();
+        let expected = parse(positive.as_bytes(), &options).unwrap();
+        let actual = parse(negative.as_bytes(), &options).unwrap();
+        assert_eq!(actual.vertices, expected.vertices);
+        assert_eq!(actual.faces, expected.faces);
+    }
+
+    #[test]
+    fn negative_index_before_the_first_vertex_is_an_error() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf -1 -2 -4\n";
+        assert!(matches!(
+            parse(obj.as_bytes(), &ObjOptions::default()),
+            Err(LoadError::ParseError { line: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn vertex_colors_follow_their_positions() {
+        let obj = "\
+v 0 0 0 1 0 0
+v 1 0 0 0 1 0
+v 0 1 0 0 0 0.5
+v 1 1 0 1.0
+f 1 2 3
+f 2 4 3
+";
+        let ObjData {
+            vertices,
+            faces,
+            vertex_colors,
+            ..
+        } = parse(obj.as_bytes(), &ObjOptions::default()).unwrap();
+        assert_eq!(vertex_colors.len(), vertices.len());
+
+        let color_at = |index: u32| vertex_colors[index as usize];
+        assert_eq!(color_at(faces[0].a), 0xFFFF0000);
+        assert_eq!(color_at(faces[0].b), 0xFF00FF00);
+        assert_eq!(color_at(faces[0].c), 0xFF000080);
+        // Lines without a color (here with a w component) default to white
+        assert_eq!(color_at(faces[1].b), 0xFFFFFFFF);
+    }
+}
@@ -0,0 +1,200 @@
+//! Procedurally generated primitive meshes.
+//!
+//! Every primitive is centered on the origin with outward-facing normals and
+//! faces wound so that `(b - a) x (c - a)` points outward, matching the engine's
+//! backface culling.
+
+use std::f32::consts::PI;
+
+use super::{Face, Mesh, Vertex};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+impl Mesh {
+    /// A flat plane in the XZ plane facing +Y.
+    ///
+    /// `subdivisions` is the number of cells along each side (at least 1), giving
+    /// `(subdivisions + 1)^2` vertices and `2 * subdivisions^2` faces.
+    pub fn plane(width: f32, height: f32, subdivisions: u32) -> Mesh {
+        let cells = subdivisions.max(1);
+        let mut vertices = Vec::with_capacity(((cells + 1) * (cells + 1)) as usize);
+        for row in 0..=cells {
+            for col in 0..=cells {
+                let u = col as f32 / cells as f32;
+                let v = row as f32 / cells as f32;
+                vertices.push(Vertex {
+                    position: Vec3::new((u - 0.5) * width, 0.0, (v - 0.5) * height),
+                    normal: Vec3::UP,
+                    texel: Vec2::new(u, v),
+                });
+            }
+        }
+
+        let mut faces = Vec::with_capacity((2 * cells * cells) as usize);
+        for row in 0..cells {
+            for col in 0..cells {
+                let i = row * (cells + 1) + col;
+                let next_row = i + cells + 1;
+                faces.push(Face::new(i, next_row, i + 1));
+                faces.push(Face::new(i + 1, next_row, next_row + 1));
+            }
+        }
+
+        Self::new(vertices, faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO)
+    }
+
+    /// A UV sphere with `rings` latitude bands (at least 2) and `sectors`
+    /// longitude slices (at least 3).
+    ///
+    /// The seam column is duplicated so UVs wrap cleanly, giving
+    /// `(rings + 1) * (sectors + 1)` vertices. The bands touching the poles are
+    /// single triangles, so there are `2 * sectors * (rings - 1)` faces.
+    pub fn sphere(radius: f32, rings: u32, sectors: u32) -> Mesh {
+        let rings = rings.max(2);
+        let sectors = sectors.max(3);
+
+        let mut vertices = Vec::with_capacity(((rings + 1) * (sectors + 1)) as usize);
+        for ring in 0..=rings {
+            // Polar angle from +Y (top) down to -Y (bottom)
+            let phi = PI * ring as f32 / rings as f32;
+            for sector in 0..=sectors {
+                let theta = 2.0 * PI * sector as f32 / sectors as f32;
+                let normal = Vec3::new(phi.sin() * theta.cos(), phi.cos(), phi.sin() * theta.sin());
+                vertices.push(Vertex {
+                    position: normal * radius,
+                    normal,
+                    texel: Vec2::new(
+                        sector as f32 / sectors as f32,
+                        1.0 - ring as f32 / rings as f32,
+                    ),
+                });
+            }
+        }
+
+        let mut faces = Vec::with_capacity((2 * sectors * (rings - 1)) as usize);
+        for ring in 0..rings {
+            for sector in 0..sectors {
+                let top = ring * (sectors + 1) + sector;
+                let bottom = top + sectors + 1;
+                if ring != 0 {
+                    faces.push(Face::new(top, top + 1, bottom));
+                }
+                if ring != rings - 1 {
+                    faces.push(Face::new(top + 1, bottom + 1, bottom));
+                }
+            }
+        }
+
+        Self::new(vertices, faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO)
+    }
+
+    /// A capped cylinder along the Y axis with `segments` sides (at least 3).
+    ///
+    /// The side and caps have separate vertices so each keeps its own normal,
+    /// giving `4 * (segments + 1)` vertices and `4 * segments` faces.
+    pub fn cylinder(radius: f32, height: f32, segments: u32) -> Mesh {
+        let segments = segments.max(3);
+        let half_height = height * 0.5;
+        let mut vertices = Vec::with_capacity((4 * (segments + 1)) as usize);
+        let mut faces = Vec::with_capacity((4 * segments) as usize);
+
+        // Side: a bottom/top vertex pair per segment boundary, seam duplicated for UVs
+        for segment in 0..=segments {
+            let u = segment as f32 / segments as f32;
+            let theta = 2.0 * PI * u;
+            let normal = Vec3::new(theta.cos(), 0.0, theta.sin());
+            for (y, v) in [(-half_height, 0.0), (half_height, 1.0)] {
+                vertices.push(Vertex {
+                    position: Vec3::new(normal.x * radius, y, normal.z * radius),
+                    normal,
+                    texel: Vec2::new(u, v),
+                });
+            }
+        }
+        for segment in 0..segments {
+            let bottom = segment * 2;
+            let top = bottom + 1;
+            faces.push(Face::new(bottom, top, bottom + 2));
+            faces.push(Face::new(bottom + 2, top, top + 2));
+        }
+
+        // Caps: a center vertex fanned out to a ring of edge vertices
+        for (y, normal) in [(half_height, Vec3::UP), (-half_height, Vec3::DOWN)] {
+            let center = vertices.len() as u32;
+            vertices.push(Vertex {
+                position: Vec3::new(0.0, y, 0.0),
+                normal,
+                texel: Vec2::new(0.5, 0.5),
+            });
+            for segment in 0..segments {
+                let theta = 2.0 * PI * segment as f32 / segments as f32;
+                let (sin, cos) = theta.sin_cos();
+                vertices.push(Vertex {
+                    position: Vec3::new(cos * radius, y, sin * radius),
+                    normal,
+                    texel: Vec2::new(0.5 + cos * 0.5, 0.5 + sin * 0.5),
+                });
+            }
+            for segment in 0..segments {
+                let current = center + 1 + segment;
+                let next = center + 1 + (segment + 1) % segments;
+                if y > 0.0 {
+                    faces.push(Face::new(center, next, current));
+                } else {
+                    faces.push(Face::new(center, current, next));
+                }
+            }
+        }
+
+        Self::new(vertices, faces, Vec3::ZERO, Vec3::ONE, Vec3::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts every face normal points away from the mesh center.
+    fn assert_faces_point_outward(mesh: &Mesh) {
+        for (i, face) in mesh.faces().iter().enumerate() {
+            let centroid = (mesh.vertices()[face.a as usize].position
+                + mesh.vertices()[face.b as usize].position
+                + mesh.vertices()[face.c as usize].position)
+                / 3.0;
+            assert!(
+                mesh.face_normal(i).dot(centroid) > 0.0,
+                "face {} points inward",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn plane_counts_match_subdivisions() {
+        let mesh = Mesh::plane(2.0, 2.0, 4);
+        assert_eq!(mesh.vertices().len(), 25);
+        assert_eq!(mesh.faces().len(), 32);
+        for i in 0..mesh.faces().len() {
+            assert_eq!(mesh.face_normal(i), Vec3::UP);
+        }
+    }
+
+    #[test]
+    fn sphere_counts_and_normals_point_outward() {
+        let mesh = Mesh::sphere(2.0, 8, 12);
+        assert_eq!(mesh.vertices().len(), 9 * 13);
+        assert_eq!(mesh.faces().len(), 2 * 12 * 7);
+        assert_faces_point_outward(&mesh);
+        for vertex in mesh.vertices() {
+            assert!((vertex.normal - vertex.position / 2.0).magnitude() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn cylinder_counts_and_normals_point_outward() {
+        let mesh = Mesh::cylinder(1.0, 2.0, 16);
+        assert_eq!(mesh.vertices().len(), 4 * 17);
+        assert_eq!(mesh.faces().len(), 4 * 16);
+        assert_faces_point_outward(&mesh);
+    }
+}
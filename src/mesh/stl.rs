@@ -0,0 +1,284 @@
+//! STL parsing (ASCII and binary).
+//!
+//! ASCII STL lists facets as text:
+//!
+//! ```text
+//! solid name
+//!   facet normal nx ny nz
+//!     outer loop
+//!       vertex x y z
+//!       vertex x y z
+//!       vertex x y z
+//!     endloop
+//!   endfacet
+//! endsolid name
+//! ```
+//!
+//! Binary STL is an 80-byte header, a little-endian `u32` facet count, then 50
+//! bytes per facet: the normal and three vertices as `f32` triples, plus a `u16`
+//! attribute that is ignored.
+//!
+//! # Detection
+//!
+//! Some exporters start binary headers with `solid` too, so a file is treated as
+//! binary whenever its size matches the facet count in the header. Otherwise it
+//! is ASCII only if it starts with `solid` and is entirely printable text, which
+//! binary facet data practically never is.
+//!
+//! # Index Layout
+//!
+//! STL repeats every corner per facet. Corners with the same position are merged
+//! into one [`Vertex`] whose normal is the average of the surrounding facet
+//! normals. Facets without a usable normal (all zeros) use their winding instead.
+
+use std::collections::HashMap;
+
+use super::{Face, LoadError, Vertex};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+
+const HEADER_SIZE: usize = 80;
+const FACET_SIZE: usize = 50;
+
+/// Geometry read from an STL file.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct StlData {
+    pub vertices: Vec<Vertex>,
+    pub faces: Vec<Face>,
+}
+
+/// Parses ASCII or binary STL data into shared vertices and triangle faces.
+pub(crate) fn parse(bytes: &[u8]) -> Result<StlData, LoadError> {
+    let facets = if is_binary(bytes) {
+        parse_binary(bytes)?
+    } else {
+        parse_ascii(&String::from_utf8_lossy(bytes))?
+    };
+    Ok(index_facets(&facets))
+}
+
+/// A facet's normal followed by its three corners.
+type Facet = [Vec3; 4];
+
+fn is_binary(bytes: &[u8]) -> bool {
+    if let Some(count) = facet_count(bytes) {
+        if bytes.len() == HEADER_SIZE + 4 + count * FACET_SIZE {
+            return true;
+        }
+    }
+    let is_text = bytes
+        .iter()
+        .all(|b| b.is_ascii_graphic() || b.is_ascii_whitespace());
+    !(is_text && bytes.trim_ascii_start().starts_with(b"solid"))
+}
+
+fn facet_count(bytes: &[u8]) -> Option<usize> {
+    let count = bytes.get(HEADER_SIZE..HEADER_SIZE + 4)?;
+    Some(u32::from_le_bytes(count.try_into().unwrap()) as usize)
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<Vec<Facet>, LoadError> {
+    let count = facet_count(bytes).ok_or(LoadError::Truncated {
+        expected: HEADER_SIZE + 4,
+        found: bytes.len(),
+    })?;
+    let expected = HEADER_SIZE + 4 + count * FACET_SIZE;
+    if bytes.len() < expected {
+        return Err(LoadError::Truncated {
+            expected,
+            found: bytes.len(),
+        });
+    }
+
+    let read_f32 = |at: usize| f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+    let facets = (0..count)
+        .map(|i| {
+            let start = HEADER_SIZE + 4 + i * FACET_SIZE;
+            std::array::from_fn(|v| {
+                let at = start + v * 12;
+                Vec3::new(read_f32(at), read_f32(at + 4), read_f32(at + 8))
+            })
+        })
+        .collect();
+    Ok(facets)
+}
+
+fn parse_ascii(text: &str) -> Result<Vec<Facet>, LoadError> {
+    let mut facets = Vec::new();
+    let mut normal = Vec3::ZERO;
+    let mut corners: Vec<Vec3> = Vec::with_capacity(3);
+
+    for (i, line) in text.lines().enumerate() {
+        let error = |reason| LoadError::ParseError {
+            line: i + 1,
+            content: line.trim().to_string(),
+            reason,
+        };
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("facet") => {
+                if tokens.next() != Some("normal") {
+                    return Err(error("expected 'facet normal'".to_string()));
+                }
+                normal = parse_vec3(&mut tokens).map_err(error)?;
+                corners.clear();
+            }
+            Some("vertex") => corners.push(parse_vec3(&mut tokens).map_err(error)?),
+            Some("endfacet") => {
+                if corners.len() != 3 {
+                    return Err(error(format!(
+                        "facet needs 3 vertices, found {}",
+                        corners.len()
+                    )));
+                }
+                facets.push([normal, corners[0], corners[1], corners[2]]);
+            }
+            // solid, outer loop, endloop, endsolid
+            _ => {}
+        }
+    }
+
+    Ok(facets)
+}
+
+fn parse_vec3<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<Vec3, String> {
+    let mut values = [0.0; 3];
+    for value in values.iter_mut() {
+        let token = tokens.next().ok_or("expected 3 numbers")?;
+        *value = token
+            .parse()
+            .map_err(|_| format!("invalid number '{}'", token))?;
+    }
+    Ok(Vec3::new(values[0], values[1], values[2]))
+}
+
+/// Merges corners that share a position and averages their facet normals.
+fn index_facets(facets: &[Facet]) -> StlData {
+    let mut data = StlData::default();
+    let mut normal_sums: Vec<Vec3> = Vec::new();
+    // Adding 0.0 turns -0.0 into 0.0 so both hash the same
+    let mut indices: HashMap<[u32; 3], u32> = HashMap::new();
+
+    for &[normal, a, b, c] in facets {
+        let normal = if normal == Vec3::ZERO {
+            (b - a).cross(c - a).normalize()
+        } else {
+            normal.normalize()
+        };
+
+        let corners = [a, b, c].map(|position| {
+            let key = [position.x, position.y, position.z].map(|v| (v + 0.0).to_bits());
+            let index = *indices.entry(key).or_insert_with(|| {
+                data.vertices.push(Vertex {
+                    position,
+                    normal: Vec3::ZERO,
+                    texel: Vec2::ZERO,
+                });
+                normal_sums.push(Vec3::ZERO);
+                (data.vertices.len() - 1) as u32
+            });
+            normal_sums[index as usize] = normal_sums[index as usize] + normal;
+            index
+        });
+        data.faces
+            .push(Face::new(corners[0], corners[1], corners[2]));
+    }
+
+    for (vertex, sum) in data.vertices.iter_mut().zip(normal_sums) {
+        vertex.normal = sum.normalize();
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outward-wound tetrahedron with corners at the origin and on each axis.
+    const TETRAHEDRON: &str = "\
+solid tetra
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 0 1 0
+      vertex 1 0 0
+    endloop
+  endfacet
+  facet normal 0 -1 0
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 0 1
+    endloop
+  endfacet
+  facet normal -1 0 0
+    outer loop
+      vertex 0 0 0
+      vertex 0 0 1
+      vertex 0 1 0
+    endloop
+  endfacet
+  facet normal 0.57735 0.57735 0.57735
+    outer loop
+      vertex 1 0 0
+      vertex 0 1 0
+      vertex 0 0 1
+    endloop
+  endfacet
+endsolid tetra
+";
+
+    /// Encodes facets as binary STL, with a header that starts like ASCII.
+    fn binary_stl(facets: &[Facet]) -> Vec<u8> {
+        let mut bytes = b"solid but actually binary".to_vec();
+        bytes.resize(HEADER_SIZE, 0);
+        bytes.extend((facets.len() as u32).to_le_bytes());
+        for facet in facets {
+            for v in facet {
+                for f in [v.x, v.y, v.z] {
+                    bytes.extend(f.to_le_bytes());
+                }
+            }
+            bytes.extend(0u16.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn ascii_and_binary_give_identical_geometry() {
+        let ascii = parse(TETRAHEDRON.as_bytes()).unwrap();
+        let facets = parse_ascii(TETRAHEDRON).unwrap();
+        let binary = parse(&binary_stl(&facets)).unwrap();
+
+        // Each corner appears in three facets but is stored once
+        assert_eq!(ascii.vertices.len(), 4);
+        assert_eq!(ascii.faces.len(), 4);
+        assert_eq!(ascii, binary);
+
+        // The origin corner averages the three axis-aligned facet normals
+        let origin = &ascii.vertices[0];
+        assert_eq!(origin.position, Vec3::ZERO);
+        assert!((origin.normal - Vec3::new(-1.0, -1.0, -1.0).normalize()).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn truncated_binary_is_an_error() {
+        let facets = parse_ascii(TETRAHEDRON).unwrap();
+        let bytes = binary_stl(&facets);
+
+        // The header starts with "solid", but the NUL padding gives it away as binary
+        assert!(matches!(
+            parse(&bytes[..bytes.len() - 10]),
+            Err(LoadError::Truncated { expected, found })
+                if expected == bytes.len() && found == bytes.len() - 10
+        ));
+        assert!(matches!(
+            parse(&bytes[..40]),
+            Err(LoadError::Truncated {
+                expected: 84,
+                found: 40
+            })
+        ));
+    }
+}
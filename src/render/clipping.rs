@@ -0,0 +1,341 @@
+//! Clip-space polygon clipping.
+//!
+//! Triangles that straddle the near plane cannot be projected as-is: vertices
+//! behind the camera have `w <= 0` and the perspective divide flips them across
+//! the screen. Instead of dropping those triangles, we clip them in homogeneous
+//! clip space using the Sutherland–Hodgman algorithm and re-triangulate the result.
+//!
+//! # Near Plane
+//!
+//! With the projection from [`Mat4::perspective_lh`](crate::math::mat4::Mat4::perspective_lh),
+//! the near plane maps to NDC z = -1, so a clip-space vertex is inside when
+//! `z >= -w`. The signed distance `z + w` is positive inside, zero on the plane,
+//! and negative outside.
+//!
+//! # Far Plane
+//!
+//! The far plane maps to NDC z = 1, so a vertex is inside when `z <= w` and its
+//! signed distance is `w - z`. Geometry beyond it is clipped the same way as
+//! geometry in front of the near plane.
+//!
+//! # Sutherland–Hodgman
+//!
+//! Walk each polygon edge (current -> next) and emit vertices by case:
+//!
+//! ```text
+//! current   next      output
+//! inside    inside    next
+//! inside    outside   intersection
+//! outside   inside    intersection, next
+//! outside   outside   (nothing)
+//! ```
+//!
+//! Clipping a triangle against one plane yields zero, three, or four vertices,
+//! which is fanned back into zero, one, or two triangles.
+
+use crate::colors::{lerp_color, pack_color, unpack_color};
+use crate::math::vec2::Vec2;
+use crate::math::vec3::Vec3;
+use crate::math::vec4::Vec4;
+
+/// A clip-space vertex with the attributes that must be interpolated when clipping.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClipVertex {
+    /// Position in homogeneous clip space (after projection, before the divide).
+    pub position: Vec4,
+    /// Texture coordinates.
+    pub texel: Vec2,
+    /// Per-vertex color (ARGB8888).
+    pub color: u32,
+    /// World-space position, for per-pixel lighting.
+    pub world_position: Vec3,
+    /// World-space normal, for per-pixel lighting.
+    pub normal: Vec3,
+    /// World-space tangent with the bitangent sign in `w`, for normal mapping.
+    pub tangent: Vec4,
+}
+
+impl ClipVertex {
+    pub fn new(position: Vec4, texel: Vec2, color: u32) -> Self {
+        Self {
+            position,
+            texel,
+            color,
+            world_position: Vec3::ZERO,
+            normal: Vec3::ZERO,
+            tangent: Vec4::new(0.0, 0.0, 0.0, 1.0),
+        }
+    }
+
+    /// Attach world-space position and normal, interpolated alongside the other attributes.
+    pub fn with_world_attributes(mut self, world_position: Vec3, normal: Vec3) -> Self {
+        self.world_position = world_position;
+        self.normal = normal;
+        self
+    }
+
+    /// Attach a world-space tangent, interpolated alongside the other attributes.
+    pub fn with_tangent(mut self, tangent: Vec4) -> Self {
+        self.tangent = tangent;
+        self
+    }
+
+    /// Signed distance to the near plane: positive inside, negative outside.
+    #[inline]
+    pub(crate) fn near_distance(&self) -> f32 {
+        self.position.z + self.position.w
+    }
+
+    /// Signed distance to the far plane: positive inside, negative outside.
+    #[inline]
+    pub(crate) fn far_distance(&self) -> f32 {
+        self.position.w - self.position.z
+    }
+
+    /// Linearly interpolates every attribute between `self` and `other`.
+    ///
+    /// Interpolating in clip space (before the perspective divide) is what keeps
+    /// the attributes correct once the new vertex is projected.
+    fn lerp(&self, other: &ClipVertex, t: f32) -> ClipVertex {
+        let (r, g, b) = lerp_color(unpack_color(self.color), unpack_color(other.color), t);
+        let a0 = ((self.color >> 24) & 0xFF) as f32 / 255.0;
+        let a1 = ((other.color >> 24) & 0xFF) as f32 / 255.0;
+
+        ClipVertex {
+            position: self.position + (other.position - self.position) * t,
+            texel: self.texel + (other.texel - self.texel) * t,
+            color: pack_color(r, g, b, a0 + (a1 - a0) * t),
+            world_position: self.world_position.lerp(other.world_position, t),
+            normal: self.normal.lerp(other.normal, t),
+            // The sign in w is shared by the whole face, so only xyz blends
+            tangent: Vec4 {
+                w: self.tangent.w,
+                ..self.tangent + (other.tangent - self.tangent) * t
+            },
+        }
+    }
+}
+
+/// Clips a convex polygon against the near plane (`z >= -w`).
+///
+/// Returns the vertices of the clipped polygon in the same winding order.
+/// The result is empty when the polygon lies entirely behind the near plane.
+pub fn clip_polygon_near(polygon: &[ClipVertex]) -> Vec<ClipVertex> {
+    clip_polygon(polygon, ClipVertex::near_distance)
+}
+
+/// Clips a convex polygon against the far plane (`z <= w`).
+///
+/// Returns the vertices of the clipped polygon in the same winding order.
+/// The result is empty when the polygon lies entirely beyond the far plane.
+pub fn clip_polygon_far(polygon: &[ClipVertex]) -> Vec<ClipVertex> {
+    clip_polygon(polygon, ClipVertex::far_distance)
+}
+
+/// Sutherland–Hodgman against the plane whose signed distance is `distance`.
+fn clip_polygon(polygon: &[ClipVertex], distance: fn(&ClipVertex) -> f32) -> Vec<ClipVertex> {
+    let mut output = Vec::with_capacity(polygon.len() + 1);
+
+    for (i, current) in polygon.iter().enumerate() {
+        let next = &polygon[(i + 1) % polygon.len()];
+        let d_current = distance(current);
+        let d_next = distance(next);
+
+        if d_current >= 0.0 {
+            if d_next >= 0.0 {
+                output.push(*next);
+            } else {
+                output.push(current.lerp(next, d_current / (d_current - d_next)));
+            }
+        } else if d_next >= 0.0 {
+            output.push(current.lerp(next, d_current / (d_current - d_next)));
+            output.push(*next);
+        }
+    }
+
+    output
+}
+
+/// Clips a triangle against both the near and far planes and fans the result back
+/// into triangles.
+///
+/// Returns no triangles when fully clipped and the original triangle when fully
+/// inside. Otherwise the clipped polygon is fanned from its first vertex, so a
+/// triangle can come back as up to three triangles.
+pub fn clip_triangle(triangle: [ClipVertex; 3]) -> Vec<[ClipVertex; 3]> {
+    let inside = |v: &ClipVertex| v.near_distance() >= 0.0 && v.far_distance() >= 0.0;
+    if triangle.iter().all(inside) {
+        return vec![triangle];
+    }
+
+    let polygon = clip_polygon_far(&clip_polygon_near(&triangle));
+    if polygon.len() < 3 {
+        return Vec::new();
+    }
+
+    (1..polygon.len() - 1)
+        .map(|i| [polygon[0], polygon[i], polygon[i + 1]])
+        .collect()
+}
+
+/// Clips a clip-space line segment to the view volume (`-w <= x, y, z <= w`).
+///
+/// Returns the visible part of the segment, or `None` when none of it is visible.
+/// Clipping to the side planes as well as the near plane keeps the projected
+/// endpoints on screen, so line drawing never walks off-screen pixels.
+pub fn clip_line(a: Vec4, b: Vec4) -> Option<(Vec4, Vec4)> {
+    let distances = |v: Vec4| {
+        [
+            v.w + v.x,
+            v.w - v.x,
+            v.w + v.y,
+            v.w - v.y,
+            v.w + v.z,
+            v.w - v.z,
+        ]
+    };
+
+    // Liang–Barsky: shrink the parameter range [t0, t1] plane by plane
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+    for (d_a, d_b) in distances(a).into_iter().zip(distances(b)) {
+        if d_a < 0.0 && d_b < 0.0 {
+            return None;
+        }
+        if d_a < 0.0 {
+            t0 = t0.max(d_a / (d_a - d_b));
+        } else if d_b < 0.0 {
+            t1 = t1.min(d_a / (d_a - d_b));
+        }
+    }
+    (t0 <= t1).then(|| (a + (b - a) * t0, a + (b - a) * t1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn vertex(x: f32, y: f32, z: f32, w: f32, u: f32) -> ClipVertex {
+        ClipVertex::new(Vec4::new(x, y, z, w), Vec2::new(u, 0.0), 0xFFFFFFFF)
+    }
+
+    #[test]
+    fn lines_are_clipped_to_the_view_volume() {
+        let inside = Vec4::new(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(
+            clip_line(inside, inside * 2.0),
+            Some((inside, inside * 2.0))
+        );
+
+        // From the center out past the right plane (x = w)
+        let (a, b) = clip_line(inside, Vec4::new(3.0, 0.0, 0.0, 1.0)).unwrap();
+        assert_eq!(a, inside);
+        assert_relative_eq!(b.x, 1.0);
+
+        // Behind the near plane at both ends
+        let behind = Vec4::new(0.0, 0.0, -2.0, 1.0);
+        assert_eq!(clip_line(behind, Vec4::new(1.0, 0.0, -3.0, 1.0)), None);
+    }
+
+    #[test]
+    fn triangle_in_front_is_untouched() {
+        let tri = [
+            vertex(0.0, 0.0, 1.0, 2.0, 0.0),
+            vertex(1.0, 0.0, 1.0, 2.0, 1.0),
+            vertex(0.0, 1.0, 1.0, 2.0, 0.0),
+        ];
+        assert_eq!(clip_triangle(tri), vec![tri]);
+    }
+
+    #[test]
+    fn triangle_behind_is_removed() {
+        let tri = [
+            vertex(0.0, 0.0, -3.0, -1.0, 0.0),
+            vertex(1.0, 0.0, -3.0, -1.0, 1.0),
+            vertex(0.0, 1.0, -3.0, -1.0, 0.0),
+        ];
+        assert!(clip_triangle(tri).is_empty());
+    }
+
+    #[test]
+    fn one_vertex_behind_yields_two_triangles() {
+        // v0 is behind the near plane (z + w = -2), v1 and v2 are in front (z + w = 2).
+        let tri = [
+            vertex(0.0, 0.0, -2.0, 0.0, 0.0),
+            vertex(4.0, 0.0, 1.0, 1.0, 1.0),
+            vertex(0.0, 4.0, 1.0, 1.0, 1.0),
+        ];
+        let clipped = clip_triangle(tri);
+        assert_eq!(clipped.len(), 2);
+
+        // Every output vertex must be on or in front of the near plane.
+        for v in clipped.iter().flatten() {
+            assert!(v.near_distance() >= -1e-6);
+        }
+
+        // The new vertex on edge v0 -> v1 sits halfway along it, with its UV interpolated.
+        let on_edge = clipped
+            .iter()
+            .flatten()
+            .find(|v| v.position.y == 0.0 && v.position.x > 0.0 && v.position.x < 4.0)
+            .expect("intersection on edge v0 -> v1");
+        assert_relative_eq!(on_edge.position.x, 2.0);
+        assert_relative_eq!(on_edge.position.z, -0.5);
+        assert_relative_eq!(on_edge.position.w, 0.5);
+        assert_relative_eq!(on_edge.texel.x, 0.5);
+    }
+
+    #[test]
+    fn two_vertices_behind_yields_one_triangle() {
+        let tri = [
+            vertex(0.0, 0.0, 1.0, 1.0, 0.0),
+            vertex(4.0, 0.0, -2.0, 0.0, 1.0),
+            vertex(0.0, 4.0, -2.0, 0.0, 1.0),
+        ];
+        let clipped = clip_triangle(tri);
+        assert_eq!(clipped.len(), 1);
+
+        // Winding is preserved: the point where edge v2->v0 crosses, v0, then the
+        // point where v0->v1 crosses.
+        let [a, b, c] = clipped[0];
+        assert_eq!(b, tri[0]);
+        assert_relative_eq!(a.position.x, 0.0);
+        assert_relative_eq!(a.position.y, 2.0);
+        assert_relative_eq!(c.position.x, 2.0);
+        assert_relative_eq!(c.position.y, 0.0);
+    }
+
+    #[test]
+    fn far_plane_clips_the_part_beyond_it() {
+        // v0 is inside both planes; v1 and v2 lie beyond the far plane (z > w)
+        let tri = [
+            vertex(0.0, 0.0, 0.0, 1.0, 0.0),
+            vertex(4.0, 0.0, 3.0, 1.0, 1.0),
+            vertex(0.0, 4.0, 3.0, 1.0, 1.0),
+        ];
+        assert_eq!(clip_polygon_near(&tri).len(), 3);
+
+        let clipped = clip_triangle(tri);
+        assert_eq!(clipped.len(), 1);
+        for v in clipped.iter().flatten() {
+            assert!(v.far_distance() >= -1e-6, "{:?}", v.position);
+        }
+    }
+
+    #[test]
+    fn clipping_interpolates_vertex_colors() {
+        let tri = [
+            ClipVertex::new(Vec4::new(0.0, 0.0, -2.0, 0.0), Vec2::ZERO, 0xFF000000),
+            ClipVertex::new(Vec4::new(4.0, 0.0, 1.0, 1.0), Vec2::ZERO, 0xFFFFFFFF),
+            ClipVertex::new(Vec4::new(0.0, 4.0, 1.0, 1.0), Vec2::ZERO, 0xFFFFFFFF),
+        ];
+        let clipped = clip_triangle(tri);
+        let midpoint = clipped
+            .iter()
+            .flatten()
+            .find(|v| v.position.x == 2.0)
+            .unwrap();
+        // Halfway between black and white
+        assert_eq!(midpoint.color, 0xFF808080);
+    }
+}
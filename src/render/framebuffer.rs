@@ -3,6 +3,133 @@
 //! Provides a safe view into color and depth buffers with bounds-checked access.
 //! The depth buffer enables proper hidden surface removal via z-buffer algorithm.
 
+use crate::colors::{lerp_color, pack_color, unpack_color};
+
+/// How a shaded fragment is combined with the pixel already in the color buffer.
+///
+/// # Depth Interaction
+///
+/// Only [`BlendMode::Opaque`] writes the depth buffer. Blended fragments are still
+/// depth *tested*, so they are hidden behind nearer opaque surfaces, but they leave
+/// the stored depth untouched so geometry behind a translucent surface can still
+/// be drawn. Draw opaque geometry first, then blended geometry back to front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Overwrite the destination color.
+    #[default]
+    Opaque,
+    /// Source-over compositing: `src * src_a + dst * (1 - src_a)`.
+    AlphaBlend,
+    /// Add the alpha-scaled source to the destination, saturating at white.
+    Additive,
+}
+
+/// Which stage of a depth pre-pass a [`FrameBuffer`] is drawing.
+///
+/// A pre-pass draws all opaque geometry with [`DepthPass::DepthOnly`], then draws
+/// it again with [`DepthPass::Equal`], so only the nearest fragment of each pixel
+/// is shaded. Both passes must rasterize the same triangles identically for the
+/// depths to match exactly, which every rasterizer here does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthPass {
+    /// Depth test, then shade and write color in one pass.
+    #[default]
+    Full,
+    /// Write the nearest depth only; fragments are never shaded.
+    DepthOnly,
+    /// Shade opaque fragments whose depth equals the stored depth.
+    ///
+    /// A shaded pixel's depth moves one ulp nearer so it is not shaded twice
+    /// where triangles share an edge; the first fragment wins, as in one pass.
+    Equal,
+}
+
+/// Fog blended into every fragment a [`FrameBuffer`] shades.
+///
+/// The fog amount ramps linearly over the fragment's stored depth `1 / inv_depth`
+/// (clip-space W under perspective projection), from none at `start` to fully
+/// `color` at `end`. `end` may be below `start` when stored depth shrinks with
+/// distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct DepthFog {
+    pub color: u32,
+    pub start: f32,
+    pub end: f32,
+}
+
+impl DepthFog {
+    pub fn new(color: u32, start: f32, end: f32) -> Self {
+        Self { color, start, end }
+    }
+
+    /// Blend `color` toward the fog color for a fragment at `inv_depth`, keeping alpha.
+    #[inline]
+    fn apply(&self, color: u32, inv_depth: f32) -> u32 {
+        let amount = ((1.0 / inv_depth - self.start) / (self.end - self.start)).clamp(0.0, 1.0);
+        let (r, g, b) = lerp_color(unpack_color(color), unpack_color(self.color), amount);
+        pack_color(r, g, b, (color >> 24) as f32 / 255.0)
+    }
+}
+
+/// An axis-aligned pixel rectangle: `x..x + width` by `y..y + height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub const fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The smallest rectangle covering both.
+    pub fn union(&self, other: Rect) -> Rect {
+        let left = self.x.min(other.x);
+        let top = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Rect::new(left, top, (right - left) as u32, (bottom - top) as u32)
+    }
+
+    /// The overlap of both, or `None` if they don't overlap.
+    pub fn intersect(&self, other: Rect) -> Option<Rect> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        (right > left && bottom > top)
+            .then(|| Rect::new(left, top, (right - left) as u32, (bottom - top) as u32))
+    }
+
+    /// One past the last column.
+    pub fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    /// One past the last row.
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    /// Whether pixel (x, y) lies inside the rectangle.
+    #[inline]
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && ((x - self.x) as u32) < self.width
+            && ((y - self.y) as u32) < self.height
+    }
+}
+
 /// A view into color and depth buffers.
 ///
 /// Wraps 1D slices with width/height metadata to enable safe 2D pixel access.
@@ -15,11 +142,35 @@
 /// Using 1/w instead of z because it can be linearly interpolated in screen space.
 /// Larger values are closer to the camera (since w increases with distance in
 /// left-handed coordinates, 1/w decreases).
+///
+/// # Bands
+///
+/// A view can also cover only a horizontal band of a larger image (see
+/// [`FrameBuffer::band`]). Coordinates stay in full-image space; pixels outside
+/// the band are treated as out of bounds. Disjoint bands can be written in parallel.
+///
+/// # Scissor
+///
+/// [`FrameBuffer::set_scissor`] narrows the writable area further, e.g. to one
+/// split-screen viewport. Pixels outside the scissor rectangle are treated as out
+/// of bounds too, so every rasterizer honors it without extra checks.
+///
+/// # Early Depth Test
+///
+/// Rasterizers pass the shader to [`FrameBuffer::shade_fragment`] as a closure,
+/// which runs only after the fragment passes the depth test. Combined with a
+/// [`DepthPass`] pre-pass, each visible pixel is shaded once regardless of overdraw.
 pub struct FrameBuffer<'a> {
     color_buffer: &'a mut [u32],
     depth_buffer: &'a mut [f32],
     width: u32,
     height: u32,
+    y_offset: u32, // First image row covered by this view
+    blend_mode: BlendMode,
+    scissor: Option<Rect>, // In image coordinates, like the pixel coordinates
+    depth_pass: DepthPass,
+    fog: Option<DepthFog>,
+    shaded_fragments: usize, // Shader invocations since creation
 }
 
 impl<'a> FrameBuffer<'a> {
@@ -48,21 +199,241 @@ impl<'a> FrameBuffer<'a> {
             depth_buffer,
             width,
             height,
+            y_offset: 0,
+            blend_mode: BlendMode::default(),
+            scissor: None,
+            depth_pass: DepthPass::default(),
+            fog: None,
+            shaded_fragments: 0,
         }
     }
 
+    /// Create a view over `rows` image rows starting at row `y_offset`.
+    ///
+    /// The slices hold just those rows (`width * rows` elements each), e.g. from
+    /// `chunks_mut` over the full buffers.
+    pub fn band(
+        color_buffer: &'a mut [u32],
+        depth_buffer: &'a mut [f32],
+        width: u32,
+        y_offset: u32,
+        rows: u32,
+    ) -> Self {
+        let mut buffer = Self::new(color_buffer, depth_buffer, width, rows);
+        buffer.y_offset = y_offset;
+        buffer
+    }
+
+    /// Buffer index of image pixel (x, y), or None if outside this view.
+    #[inline]
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if self.scissor.is_some_and(|rect| !rect.contains(x, y)) {
+            return None;
+        }
+        let row = y - self.y_offset as i32;
+        if x >= 0 && x < self.width as i32 && row >= 0 && row < self.height as i32 {
+            Some((row as u32 * self.width + x as u32) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Set how [`FrameBuffer::write_fragment`] combines colors.
+    ///
+    /// Rasterizers set this per triangle from [`Triangle::blend_mode`](crate::render::Triangle).
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Restrict all reads and writes to `rect`, or lift the restriction with `None`.
+    pub fn set_scissor(&mut self, rect: Option<Rect>) {
+        self.scissor = rect;
+    }
+
+    pub fn scissor(&self) -> Option<Rect> {
+        self.scissor
+    }
+
+    /// Set which stage of a depth pre-pass [`FrameBuffer::shade_fragment`] performs.
+    pub fn set_depth_pass(&mut self, pass: DepthPass) {
+        self.depth_pass = pass;
+    }
+
+    pub fn depth_pass(&self) -> DepthPass {
+        self.depth_pass
+    }
+
+    /// Fog every fragment shaded from now on, or stop with `None`.
+    ///
+    /// Only [`FrameBuffer::shade_fragment`] (and so every rasterizer) applies
+    /// it; lines and pixels set directly stay unfogged.
+    pub fn set_fog(&mut self, fog: Option<DepthFog>) {
+        self.fog = fog;
+    }
+
+    pub fn fog(&self) -> Option<DepthFog> {
+        self.fog
+    }
+
+    /// Number of fragments shaded through [`FrameBuffer::shade_fragment`] so far.
+    pub fn shaded_fragments(&self) -> usize {
+        self.shaded_fragments
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
 
+    /// Number of rows covered by this view.
     pub fn height(&self) -> u32 {
         self.height
     }
 
+    /// First image row covered by this view (0 unless created with [`FrameBuffer::band`]).
+    pub fn y_offset(&self) -> u32 {
+        self.y_offset
+    }
+
+    /// Test a depth value against the depth buffer and store it if it passes.
+    ///
+    /// The test passes when `inv_depth` is greater than the stored value (closer to
+    /// the camera, since we store 1/w). Returns `false` for out-of-bounds coordinates.
+    ///
+    /// # Arguments
+    /// * `x`, `y` - Pixel coordinates
+    /// * `inv_depth` - The 1/w value for this pixel (larger = closer)
+    #[inline]
+    pub fn test_and_set_depth(&mut self, x: i32, y: i32, inv_depth: f32) -> bool {
+        if let Some(idx) = self.index(x, y) {
+            if inv_depth > self.depth_buffer[idx] {
+                self.depth_buffer[idx] = inv_depth;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Test a depth value against the depth buffer without updating it.
+    ///
+    /// Used by blended fragments, which must not occlude what is drawn after them.
+    #[inline]
+    pub fn depth_test(&self, x: i32, y: i32, inv_depth: f32) -> bool {
+        self.index(x, y)
+            .is_some_and(|idx| inv_depth > self.depth_buffer[idx])
+    }
+
+    /// Write a shaded fragment using the current [`BlendMode`].
+    ///
+    /// Opaque fragments go through [`FrameBuffer::set_pixel_with_depth`]; blended
+    /// fragments are depth tested read-only and then composited.
+    #[inline]
+    pub fn write_fragment(&mut self, x: i32, y: i32, inv_depth: f32, color: u32) {
+        self.shade_fragment(x, y, inv_depth, || color);
+    }
+
+    /// Depth test a fragment, then call `shade` for its color only if it passes.
+    ///
+    /// Behaves like [`FrameBuffer::write_fragment`] under [`DepthPass::Full`].
+    /// [`DepthPass::DepthOnly`] records opaque depths without shading, and
+    /// [`DepthPass::Equal`] shades opaque fragments only where their depth matches
+    /// the stored one. Blended fragments always use the read-only depth test.
+    #[inline]
+    pub fn shade_fragment(&mut self, x: i32, y: i32, inv_depth: f32, shade: impl FnOnce() -> u32) {
+        let Some(idx) = self.index(x, y) else {
+            return;
+        };
+        let stored = self.depth_buffer[idx];
+        let opaque = self.blend_mode == BlendMode::Opaque;
+        let passes = match self.depth_pass {
+            DepthPass::DepthOnly => {
+                if opaque && inv_depth > stored {
+                    self.depth_buffer[idx] = inv_depth;
+                }
+                return;
+            }
+            DepthPass::Equal if opaque => {
+                // Nudge the depth nearer so triangles sharing an edge shade it once
+                let matches = inv_depth == stored;
+                if matches {
+                    self.depth_buffer[idx] = stored.next_up();
+                }
+                matches
+            }
+            DepthPass::Full if opaque => {
+                let nearer = inv_depth > stored;
+                if nearer {
+                    self.depth_buffer[idx] = inv_depth;
+                }
+                nearer
+            }
+            _ => inv_depth > stored,
+        };
+        if !passes {
+            return;
+        }
+
+        let mut color = shade();
+        if let Some(fog) = self.fog {
+            color = fog.apply(color, inv_depth);
+        }
+        self.shaded_fragments += 1;
+        match self.blend_mode {
+            BlendMode::Opaque => self.color_buffer[idx] = color,
+            BlendMode::AlphaBlend => self.blend_pixel(x, y, color),
+            BlendMode::Additive => self.add_pixel(x, y, color),
+        }
+    }
+
+    /// Composite `src` over the pixel at (x, y) using the source alpha (src-over).
+    ///
+    /// ```text
+    /// out_rgb = src_rgb * src_a + dst_rgb * (1 - src_a)
+    /// out_a   = src_a + dst_a * (1 - src_a)
+    /// ```
+    ///
+    /// Silently ignores out-of-bounds coordinates.
+    #[inline]
+    pub fn blend_pixel(&mut self, x: i32, y: i32, src: u32) {
+        if let Some(dst) = self.get_pixel(x, y) {
+            let src_a = (src >> 24) as f32 / 255.0;
+            let blended = [24, 16, 8, 0].iter().fold(0u32, |color, &shift| {
+                let s = ((src >> shift) & 0xFF) as f32;
+                let d = ((dst >> shift) & 0xFF) as f32;
+                // Alpha composites as coverage; color channels are weighted by source alpha
+                let out = if shift == 24 {
+                    s + d * (1.0 - src_a)
+                } else {
+                    s * src_a + d * (1.0 - src_a)
+                };
+                color | ((out.round() as u32).min(255) << shift)
+            });
+            self.set_pixel(x, y, blended);
+        }
+    }
+
+    /// Add the alpha-scaled `src` color to the pixel at (x, y), saturating each channel.
+    ///
+    /// The destination alpha is kept. Silently ignores out-of-bounds coordinates.
+    #[inline]
+    pub fn add_pixel(&mut self, x: i32, y: i32, src: u32) {
+        if let Some(dst) = self.get_pixel(x, y) {
+            let src_a = (src >> 24) as f32 / 255.0;
+            let added = [16, 8, 0].iter().fold(dst & 0xFF000000, |color, &shift| {
+                let s = ((src >> shift) & 0xFF) as f32 * src_a;
+                let d = (dst >> shift) & 0xFF;
+                color | ((d + s.round() as u32).min(255) << shift)
+            });
+            self.set_pixel(x, y, added);
+        }
+    }
+
     /// Set a pixel at (x, y) with depth testing.
     ///
-    /// The pixel is only written if the depth value is greater than the existing
-    /// depth at that location (closer to camera, since we store 1/w).
+    /// The pixel is only written if [`FrameBuffer::test_and_set_depth`] passes.
     /// Silently ignores out-of-bounds coordinates.
     ///
     /// # Arguments
@@ -71,31 +442,187 @@ impl<'a> FrameBuffer<'a> {
     /// * `color` - The color to write if depth test passes
     #[inline]
     pub fn set_pixel_with_depth(&mut self, x: i32, y: i32, inv_depth: f32, color: u32) {
-        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
-            let idx = (y as u32 * self.width + x as u32) as usize;
-            // Depth test: larger 1/w means closer to camera
-            if inv_depth > self.depth_buffer[idx] {
-                self.depth_buffer[idx] = inv_depth;
-                self.color_buffer[idx] = color;
-            }
+        if self.test_and_set_depth(x, y, inv_depth) {
+            self.set_pixel(x, y, color);
         }
     }
 
     /// Set a pixel without depth testing (for overlays, UI, etc.)
     #[inline]
     pub fn set_pixel(&mut self, x: i32, y: i32, color: u32) {
-        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
-            self.color_buffer[(y as u32 * self.width + x as u32) as usize] = color;
+        if let Some(idx) = self.index(x, y) {
+            self.color_buffer[idx] = color;
         }
     }
 
     /// Get the color at (x, y), or None if out of bounds.
     #[inline]
     pub fn get_pixel(&self, x: i32, y: i32) -> Option<u32> {
-        if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
-            Some(self.color_buffer[(y as u32 * self.width + x as u32) as usize])
-        } else {
-            None
+        self.index(x, y).map(|idx| self.color_buffer[idx])
+    }
+
+    /// Iterate over the color rows of this view, top to bottom.
+    ///
+    /// Each row is a separate mutable slice, so rows can be handed to different
+    /// threads. The scissor does not apply to direct row access.
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [u32]> {
+        self.color_buffer
+            .chunks_exact_mut(self.width.max(1) as usize)
+    }
+
+    /// Split this view into two disjoint bands at image row `y`.
+    ///
+    /// The first covers rows up to `y` and the second the rows from `y` on. Both
+    /// keep full-image coordinates, the blend mode, scissor, depth pass and fog, so each
+    /// can be drawn into independently, e.g. from its own thread. They borrow this
+    /// view, which sees both halves' writes once they are dropped.
+    ///
+    /// # Panics
+    /// Panics if `y` lies outside the rows covered by this view.
+    pub fn split_at_row(&mut self, y: u32) -> (FrameBuffer<'_>, FrameBuffer<'_>) {
+        assert!(
+            y >= self.y_offset && y <= self.y_offset + self.height,
+            "row {y} outside the view's rows"
+        );
+        let rows = y - self.y_offset;
+        let split = (rows * self.width) as usize;
+        let (top_color, bottom_color) = self.color_buffer.split_at_mut(split);
+        let (top_depth, bottom_depth) = self.depth_buffer.split_at_mut(split);
+
+        let half = |color, depth, y_offset, rows| FrameBuffer {
+            blend_mode: self.blend_mode,
+            scissor: self.scissor,
+            depth_pass: self.depth_pass,
+            fog: self.fog,
+            ..FrameBuffer::band(color, depth, self.width, y_offset, rows)
+        };
+        (
+            half(top_color, top_depth, self.y_offset, rows),
+            half(bottom_color, bottom_depth, y, self.height - rows),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes `src` over a single `dst` pixel with `mode` and returns the result.
+    fn composite(dst: u32, src: u32, mode: BlendMode) -> u32 {
+        let mut color = [dst];
+        let mut depth = [0.0];
+        let mut fb = FrameBuffer::new(&mut color, &mut depth, 1, 1);
+        fb.set_blend_mode(mode);
+        fb.write_fragment(0, 0, 1.0, src);
+        color[0]
+    }
+
+    #[test]
+    fn half_alpha_red_over_blue_is_purple() {
+        assert_eq!(
+            composite(0xFF0000FF, 0x80FF0000, BlendMode::AlphaBlend),
+            0xFF80007F
+        );
+    }
+
+    #[test]
+    fn additive_saturates() {
+        assert_eq!(
+            composite(0xFFC08000, 0xFF80FF10, BlendMode::Additive),
+            0xFFFFFF10
+        );
+        // Source alpha scales the contribution
+        assert_eq!(
+            composite(0xFF000000, 0x80FF0000, BlendMode::Additive),
+            0xFF800000
+        );
+    }
+
+    #[test]
+    fn scissor_drops_pixels_outside_the_rect() {
+        let mut color = [0u32; 16];
+        let mut depth = [0.0; 16];
+        let mut fb = FrameBuffer::new(&mut color, &mut depth, 4, 4);
+        fb.set_scissor(Some(Rect::new(1, 1, 2, 2)));
+        for y in -1..5 {
+            for x in -1..5 {
+                fb.set_pixel_with_depth(x, y, 1.0, 0xFFFFFFFF);
+            }
         }
+        assert_eq!(fb.get_pixel(0, 0), None);
+
+        fb.set_scissor(None);
+        let written: Vec<_> = (0..16).filter(|&i| color[i] != 0).collect();
+        assert_eq!(written, [5, 6, 9, 10]);
+    }
+
+    #[test]
+    fn split_halves_write_to_the_shared_buffer() {
+        let mut color = [0u32; 12];
+        let mut depth = [0.0; 12];
+        let mut fb = FrameBuffer::new(&mut color, &mut depth, 3, 4);
+        {
+            let (mut top, mut bottom) = fb.split_at_row(1);
+            assert_eq!(
+                (top.height(), bottom.height(), bottom.y_offset()),
+                (1, 3, 1)
+            );
+            top.set_pixel(0, 0, 0xFF0000FF);
+            bottom.set_pixel(2, 3, 0xFF00FF00);
+            // Each half treats the other's rows as out of bounds
+            top.set_pixel(0, 1, 0xFFFFFFFF);
+            bottom.set_pixel(1, 0, 0xFFFFFFFF);
+            for (i, row) in bottom.rows_mut().enumerate() {
+                row[1] = i as u32;
+            }
+        }
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFF0000FF));
+        assert_eq!(fb.get_pixel(2, 3), Some(0xFF00FF00));
+        assert_eq!(fb.rows_mut().count(), 4);
+        assert_eq!(
+            color,
+            [0xFF0000FF, 0, 0, 0, 0, 0, 0, 1, 0, 0, 2, 0xFF00FF00]
+        );
+    }
+
+    #[test]
+    fn depth_prepass_shades_only_the_nearest_fragment() {
+        let mut color = [0u32];
+        let mut depth = [0.0];
+        let mut fb = FrameBuffer::new(&mut color, &mut depth, 1, 1);
+        let fragments = [(0.25, 0xFF0000FF), (0.75, 0xFF00FF00), (0.5, 0xFFFF0000)];
+
+        fb.set_depth_pass(DepthPass::DepthOnly);
+        for (inv_depth, _) in fragments {
+            fb.shade_fragment(0, 0, inv_depth, || unreachable!("depth-only shaded"));
+        }
+        fb.set_depth_pass(DepthPass::Equal);
+        for (inv_depth, c) in fragments {
+            fb.shade_fragment(0, 0, inv_depth, || c);
+        }
+
+        // A second fragment at the same depth is not shaded again
+        fb.shade_fragment(0, 0, 0.75, || 0xFFFFFFFF);
+
+        assert_eq!(fb.shaded_fragments(), 1);
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFF00FF00));
+        assert!(depth[0] > 0.75 && depth[0] < 0.7501);
+    }
+
+    #[test]
+    fn blended_fragments_do_not_write_depth() {
+        let mut color = [0xFF0000FF];
+        let mut depth = [0.5];
+        let mut fb = FrameBuffer::new(&mut color, &mut depth, 1, 1);
+        fb.set_blend_mode(BlendMode::AlphaBlend);
+
+        // Behind the stored depth: rejected
+        fb.write_fragment(0, 0, 0.25, 0xFFFF0000);
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFF0000FF));
+
+        // In front: composited, but the depth buffer keeps its value
+        fb.write_fragment(0, 0, 1.0, 0xFFFF0000);
+        assert_eq!(fb.get_pixel(0, 0), Some(0xFFFF0000));
+        assert_eq!(depth[0], 0.5);
     }
 }
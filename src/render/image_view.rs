@@ -0,0 +1,77 @@
+//! Read-only access to a rendered image.
+//!
+//! [`ImageView`] borrows a row-major ARGB8888 pixel buffer together with its
+//! dimensions, so callers can read pixels by coordinate instead of computing
+//! `y * width + x` into a raw slice.
+
+/// A borrowed, row-major view of ARGB8888 pixels.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageView<'a> {
+    pixels: &'a [u32],
+    width: u32,
+    height: u32,
+}
+
+impl<'a> ImageView<'a> {
+    /// Wrap `pixels` as a `width x height` image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pixels.len()` is not `width * height`.
+    pub fn new(pixels: &'a [u32], width: u32, height: u32) -> Self {
+        assert_eq!(
+            pixels.len(),
+            width as usize * height as usize,
+            "pixel buffer does not match {width}x{height}"
+        );
+        Self {
+            pixels,
+            width,
+            height,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The pixel at (`x`, `y`), or `None` outside the image.
+    pub fn get(&self, x: u32, y: u32) -> Option<u32> {
+        if x < self.width && y < self.height {
+            Some(self.pixels[y as usize * self.width as usize + x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Every pixel, rows top to bottom.
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'a, u32>> {
+        self.pixels.iter().copied()
+    }
+
+    /// The underlying pixels, rows top to bottom.
+    pub fn as_slice(&self) -> &'a [u32] {
+        self.pixels
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_indexes_row_major_and_rejects_out_of_bounds() {
+        let pixels = [0, 1, 2, 3, 4, 5];
+        let image = ImageView::new(&pixels, 3, 2);
+
+        assert_eq!(image.get(2, 0), Some(2));
+        assert_eq!(image.get(0, 1), Some(3));
+        assert_eq!(image.get(3, 0), None);
+        assert_eq!(image.get(0, 2), None);
+        assert_eq!(image.iter().sum::<u32>(), 15);
+    }
+}
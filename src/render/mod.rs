@@ -3,15 +3,66 @@
 //! This module contains all rendering-related components:
 //! - [`FrameBuffer`]: A view into a 2D pixel buffer for safe pixel access
 //! - [`Renderer`]: Owns the color buffer and provides primitive drawing operations
+//! - [`ImageView`]: Read-only, coordinate-indexed access to a rendered image
 //! - [`rasterizer`]: Triangle rasterization algorithms
+//! - [`clipping`]: Clip-space polygon clipping against the near plane
+//! - [`font`]: Embedded 8x8 bitmap font for text overlays
+//!
+//! The rasterizers work on any [`FrameBuffer`], so they can also draw into
+//! buffers the caller owns:
+//!
+//! ```
+//! use russsty::engine::TextureMode;
+//! use russsty::math::{vec2::Vec2, vec3::Vec3};
+//! use russsty::render::{EdgeFunctionRasterizer, FrameBuffer, Rasterizer, Triangle};
+//! use russsty::ShadingMode;
+//!
+//! let (width, height) = (32, 32);
+//! let mut pixels = vec![0u32; (width * height) as usize];
+//! let mut depth = vec![0.0f32; (width * height) as usize];
+//! let mut buffer = FrameBuffer::new(&mut pixels, &mut depth, width, height);
+//!
+//! // Screen-space vertices; z holds clip-space W, so it must be non-zero
+//! let red = 0xFFFF0000;
+//! let triangle = Triangle::new(
+//!     [
+//!         Vec3::new(2.0, 2.0, 1.0),
+//!         Vec3::new(30.0, 2.0, 1.0),
+//!         Vec3::new(16.0, 30.0, 1.0),
+//!     ],
+//!     red,
+//!     [red; 3],
+//!     [Vec2::ZERO; 3],
+//!     1.0,
+//!     ShadingMode::Flat,
+//!     TextureMode::None,
+//! );
+//! EdgeFunctionRasterizer::new().fill_triangle(&triangle, &mut buffer, triangle.color, None);
+//!
+//! assert_eq!(pixels[(10 * width + 16) as usize], red);
+//! assert_eq!(pixels[0], 0);
+//! ```
 
-pub mod framebuffer;
-pub mod rasterizer;
-pub mod renderer;
+pub(crate) mod clipping;
+pub(crate) mod font;
+pub(crate) mod framebuffer;
+pub(crate) mod image_view;
+pub(crate) mod postprocess;
+pub(crate) mod rasterizer;
+pub(crate) mod renderer;
 
-pub use framebuffer::FrameBuffer;
+pub use framebuffer::{BlendMode, DepthFog, DepthPass, FrameBuffer, Rect};
+pub use image_view::ImageView;
+pub use postprocess::{
+    BoxBlur, ColorGrade, Grayscale, Invert, PostProcess, SobelEdgeDetect, Vignette,
+};
+pub use rasterizer::shader::{PhongLight, PhongParams, PixelShader};
+pub(crate) use rasterizer::RasterizerDispatcher;
 pub use rasterizer::{
-    EdgeFunctionRasterizer, Rasterizer, RasterizerDispatcher, RasterizerType, ScanlineRasterizer,
-    Triangle,
+    EdgeFunctionRasterizer, EdgeFunctionSimdRasterizer, Rasterizer, RasterizerType,
+    ScanlineRasterizer, Triangle,
 };
-pub use renderer::Renderer;
+pub(crate) use renderer::Renderer;
+
+#[cfg(feature = "parallel")]
+pub use rasterizer::TiledRasterizer;
@@ -0,0 +1,348 @@
+//! Full-screen post-processing effects.
+//!
+//! A [`PostProcess`] rewrites the finished color buffer after every triangle,
+//! line and vertex marker has been drawn, e.g. to tint, blur or darken the
+//! frame. [`Engine::add_post_process`](crate::engine::Engine::add_post_process)
+//! queues effects, which run in the order they were added, before the debug
+//! overlay is drawn and before supersampled frames are resolved.
+//!
+//! # Built-in Effects
+//!
+//! | Effect | Result |
+//! |--------|--------|
+//! | [`Grayscale`] | Luma of each pixel (Rec. 601 weights) |
+//! | [`Invert`] | Complement of the color channels |
+//! | [`Vignette`] | Darkens toward the corners |
+//! | [`BoxBlur`] | Mean of the surrounding square of pixels |
+//! | [`SobelEdgeDetect`] | White where brightness changes sharply, black elsewhere |
+//! | [`ColorGrade`] | Brightness, contrast and saturation, plus an optional vignette |
+//!
+//! Every built-in effect keeps each pixel's alpha.
+
+use crate::colors::{pack_color, unpack_color};
+
+/// A full-screen pass over a finished frame.
+///
+/// `buffer` holds `width * height` ARGB8888 pixels in row-major order. When
+/// supersampling, these are the samples before they are averaged down.
+/// Effects are `Send + Sync` so an [`Engine`](crate::engine::Engine) holding
+/// them can move between threads.
+pub trait PostProcess: Send + Sync {
+    fn process(&self, buffer: &mut [u32], width: u32, height: u32);
+}
+
+/// Perceived brightness of a pixel in [0, 1], with Rec. 601 weights.
+fn luma(pixel: u32) -> f32 {
+    let (r, g, b) = unpack_color(pixel);
+    0.299 * r + 0.587 * g + 0.114 * b
+}
+
+/// Replace each pixel with its luma, for a black-and-white image.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Grayscale;
+
+impl PostProcess for Grayscale {
+    fn process(&self, buffer: &mut [u32], _width: u32, _height: u32) {
+        for pixel in buffer {
+            let luma = luma(*pixel);
+            *pixel = (*pixel & 0xFF000000) | (pack_color(luma, luma, luma, 0.0) & 0x00FFFFFF);
+        }
+    }
+}
+
+/// Invert the color channels, like a photographic negative.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Invert;
+
+impl PostProcess for Invert {
+    fn process(&self, buffer: &mut [u32], _width: u32, _height: u32) {
+        for pixel in buffer {
+            *pixel ^= 0x00FFFFFF;
+        }
+    }
+}
+
+/// Darken the frame toward its edges.
+///
+/// Brightness falls with the squared distance from the center, reaching
+/// `1 - strength` in the corners.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Vignette {
+    pub strength: f32,
+}
+
+impl Vignette {
+    pub fn new(strength: f32) -> Self {
+        Self {
+            strength: strength.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Default for Vignette {
+    fn default() -> Self {
+        Self::new(0.5)
+    }
+}
+
+impl PostProcess for Vignette {
+    fn process(&self, buffer: &mut [u32], width: u32, height: u32) {
+        let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+        let corner_squared = cx * cx + cy * cy;
+        if corner_squared == 0.0 {
+            return;
+        }
+        let rows = buffer.chunks_exact_mut(width.max(1) as usize);
+        for (y, row) in rows.enumerate() {
+            let dy = y as f32 + 0.5 - cy;
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let dx = x as f32 + 0.5 - cx;
+                let falloff = (dx * dx + dy * dy) / corner_squared;
+                let (r, g, b) = unpack_color(*pixel);
+                let scale = 1.0 - self.strength * falloff.min(1.0);
+                let alpha = (*pixel >> 24) as f32 / 255.0;
+                *pixel = pack_color(r * scale, g * scale, b * scale, alpha);
+            }
+        }
+    }
+}
+
+/// Blur by averaging the `(2 * radius + 1)` square around each pixel.
+///
+/// Runs as a horizontal then a vertical pass. Samples past the frame's edges are
+/// clamped to the nearest edge pixel. A radius of 0 leaves the frame unchanged.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct BoxBlur {
+    pub radius: u32,
+}
+
+impl BoxBlur {
+    pub fn new(radius: u32) -> Self {
+        Self { radius }
+    }
+
+    /// Average `len` pixels spaced `stride` apart, starting at `start`, in place.
+    /// `line` is scratch space for the original pixels, reused across lines.
+    fn blur_line(
+        &self,
+        buffer: &mut [u32],
+        line: &mut Vec<u32>,
+        start: usize,
+        stride: usize,
+        len: usize,
+    ) {
+        line.clear();
+        line.extend((0..len).map(|i| buffer[start + i * stride]));
+        let radius = self.radius as isize;
+        let count = 2 * self.radius + 1;
+        for i in 0..len {
+            let mut sums = [0u32; 4];
+            for offset in -radius..=radius {
+                let sample = (i as isize + offset).clamp(0, len as isize - 1) as usize;
+                for (sum, byte) in sums.iter_mut().zip(line[sample].to_be_bytes()) {
+                    *sum += byte as u32;
+                }
+            }
+            let mean = sums.map(|sum| ((sum + count / 2) / count) as u8);
+            let alpha = line[i] & 0xFF000000;
+            buffer[start + i * stride] = alpha | (u32::from_be_bytes(mean) & 0x00FFFFFF);
+        }
+    }
+}
+
+impl PostProcess for BoxBlur {
+    fn process(&self, buffer: &mut [u32], width: u32, height: u32) {
+        if self.radius == 0 {
+            return;
+        }
+        let (width, height) = (width as usize, height as usize);
+        let mut line = Vec::with_capacity(width.max(height));
+        for y in 0..height {
+            self.blur_line(buffer, &mut line, y * width, 1, width);
+        }
+        for x in 0..width {
+            self.blur_line(buffer, &mut line, x, width, height);
+        }
+    }
+}
+
+/// Highlight edges with the Sobel operator on each pixel's luma.
+///
+/// The output is grayscale: the gradient magnitude, capped at 1, so flat areas
+/// turn black and sharp brightness changes turn white. Samples past the frame's
+/// edges are clamped to the nearest edge pixel.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SobelEdgeDetect;
+
+impl PostProcess for SobelEdgeDetect {
+    fn process(&self, buffer: &mut [u32], width: u32, height: u32) {
+        let (width, height) = (width as isize, height as isize);
+        let luma: Vec<f32> = buffer.iter().map(|&pixel| luma(pixel)).collect();
+        let at = |x: isize, y: isize| {
+            luma[(y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize]
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let gx = (at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1))
+                    - (at(x - 1, y - 1) + 2.0 * at(x - 1, y) + at(x - 1, y + 1));
+                let gy = (at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1))
+                    - (at(x - 1, y - 1) + 2.0 * at(x, y - 1) + at(x + 1, y - 1));
+                let edge = (gx * gx + gy * gy).sqrt().min(1.0);
+                let pixel = &mut buffer[(y * width + x) as usize];
+                *pixel = (*pixel & 0xFF000000) | (pack_color(edge, edge, edge, 0.0) & 0x00FFFFFF);
+            }
+        }
+    }
+}
+
+/// Color grading: brightness, contrast and saturation, plus an optional vignette.
+///
+/// Applied per channel in that order, on values in [0, 1], and clamped after:
+/// - `brightness` is added (neutral `0.0`)
+/// - `contrast` scales the distance from mid-gray `0.5` (neutral `1.0`)
+/// - `saturation` scales the distance from the pixel's luma (neutral `1.0`;
+///   `0.0` is grayscale)
+///
+/// The default grade is neutral and leaves the frame unchanged.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct ColorGrade {
+    pub brightness: f32,
+    pub contrast: f32,
+    pub saturation: f32,
+    pub vignette: Option<Vignette>, // Applied after the color adjustments
+}
+
+impl ColorGrade {
+    pub fn new() -> Self {
+        Self {
+            brightness: 0.0,
+            contrast: 1.0,
+            saturation: 1.0,
+            vignette: None,
+        }
+    }
+
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness;
+        self
+    }
+
+    pub fn with_contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast;
+        self
+    }
+
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        self.saturation = saturation;
+        self
+    }
+
+    /// Darken toward the corners; see [`Vignette`].
+    pub fn with_vignette(mut self, strength: f32) -> Self {
+        self.vignette = Some(Vignette::new(strength));
+        self
+    }
+
+    fn grade(&self, pixel: u32) -> u32 {
+        let gray = luma(pixel);
+        let (r, g, b) = unpack_color(pixel);
+        let channel = |c: f32| {
+            let c = (c + self.brightness - 0.5) * self.contrast + 0.5;
+            let gray = (gray + self.brightness - 0.5) * self.contrast + 0.5;
+            (gray + (c - gray) * self.saturation).clamp(0.0, 1.0)
+        };
+        let alpha = (pixel >> 24) as f32 / 255.0;
+        pack_color(channel(r), channel(g), channel(b), alpha)
+    }
+}
+
+impl Default for ColorGrade {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PostProcess for ColorGrade {
+    fn process(&self, buffer: &mut [u32], width: u32, height: u32) {
+        for pixel in buffer.iter_mut() {
+            *pixel = self.grade(*pixel);
+        }
+        if let Some(vignette) = self.vignette {
+            vignette.process(buffer, width, height);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grayscale_keeps_alpha_and_equalizes_channels() {
+        let mut buffer = [0x80FF0000, 0xFF00FF00, 0xFFFFFFFF];
+        Grayscale.process(&mut buffer, 3, 1);
+        assert_eq!(buffer, [0x804C4C4C, 0xFF969696, 0xFFFFFFFF]);
+    }
+
+    #[test]
+    fn color_grade_brightness_lightens_gray() {
+        let brighter = ColorGrade::new().with_brightness(0.5);
+        let mut buffer = [0xFF404040, 0x80808080];
+        brighter.process(&mut buffer, 2, 1);
+        // A quarter gray moves up by half the range; mid-gray saturates at white
+        assert_eq!(buffer, [0xFFC0C0C0, 0x80FFFFFF]);
+
+        let mut colorful = [0xFFC08040, 0xFF123456];
+        let neutral = colorful;
+        ColorGrade::default().process(&mut colorful, 2, 1);
+        assert_eq!(colorful, neutral);
+    }
+
+    #[test]
+    fn color_grade_vignette_darkens_corners_only() {
+        let mut buffer = [0xFF808080; 25];
+        ColorGrade::new()
+            .with_vignette(0.5)
+            .process(&mut buffer, 5, 5);
+        assert_eq!(buffer[12], 0xFF808080);
+        // The corner pixel's center is 64% of the way to the corner: 1 - 0.5 * 0.64
+        assert_eq!(buffer[0], 0xFF575757);
+    }
+
+    #[test]
+    fn box_blur_spreads_a_single_bright_pixel() {
+        let mut buffer = [0xFF000000; 25];
+        buffer[12] = 0xFFFFFFFF;
+        BoxBlur::new(1).process(&mut buffer, 5, 5);
+        // The 3x3 block around the white pixel shares its intensity
+        for y in 0..5 {
+            for x in 0..5 {
+                let near = (1..=3).contains(&x) && (1..=3).contains(&y);
+                let expected = if near { 0xFF1C1C1C } else { 0xFF000000 };
+                assert_eq!(buffer[y * 5 + x], expected, "({x}, {y})");
+            }
+        }
+
+        // Samples past the edge repeat the edge pixel
+        let mut line = [0xFFFFFFFF, 0xFF000000, 0xFF000000];
+        BoxBlur::new(1).process(&mut line, 3, 1);
+        assert_eq!(line, [0xFFAAAAAA, 0xFF555555, 0xFF000000]);
+    }
+
+    #[test]
+    fn sobel_highlights_a_black_white_boundary() {
+        // Left half black, right half white
+        let mut buffer = [0u32; 16];
+        for (i, pixel) in buffer.iter_mut().enumerate() {
+            *pixel = if i % 4 < 2 { 0xFF000000 } else { 0xFFFFFFFF };
+        }
+        SobelEdgeDetect.process(&mut buffer, 4, 4);
+        for row in buffer.chunks(4) {
+            assert_eq!(row, [0xFF000000, 0xFFFFFFFF, 0xFFFFFFFF, 0xFF000000]);
+        }
+    }
+}
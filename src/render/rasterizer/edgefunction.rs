@@ -49,10 +49,13 @@
 use super::shader::{FlatShader, GouraudShader, PixelShader};
 use super::{Rasterizer, Triangle};
 use crate::engine::TextureMode;
+use crate::math::utils::{edge_function, triangle_area};
+use crate::math::vec2::Vec2;
 use crate::math::vec3::Vec3;
 use crate::render::framebuffer::FrameBuffer;
 use crate::render::rasterizer::shader::{
     PerspectiveCorrectTextureModulateShader, PerspectiveCorrectTextureShader,
+    TextureModulateShader, TextureShader,
 };
 use crate::texture::Texture;
 use crate::ShadingMode;
@@ -73,6 +76,10 @@ use crate::ShadingMode;
 ///
 /// # Performance Considerations
 ///
+/// Edge functions are evaluated once per triangle and then stepped by constant
+/// per-pixel and per-row increments, so the inner loop has no multiplies for
+/// the inside test.
+///
 /// The bounding box approach means we test many pixels outside the triangle,
 /// especially for thin/elongated triangles. More sophisticated implementations
 /// use hierarchical testing or tile-based approaches to reduce wasted work.
@@ -84,31 +91,6 @@ impl EdgeFunctionRasterizer {
         EdgeFunctionRasterizer {}
     }
 
-    /// Computes the edge function value for point P relative to edge (A -> B).
-    ///
-    /// The edge function is the signed area of the parallelogram formed by
-    /// vectors (B - A) and (P - A), computed as their 2D cross product:
-    ///
-    /// ```text
-    /// E(P) = (B.x - A.x) * (P.y - A.y) - (B.y - A.y) * (P.x - A.x)
-    /// ```
-    ///
-    /// # Returns
-    ///
-    /// - Positive: P is to the right of edge AB
-    /// - Negative: P is to the left of edge AB
-    /// - Zero: P lies exactly on the edge AB
-    ///
-    /// # Arguments
-    ///
-    /// * `a` - Start point of the edge
-    /// * `b` - End point of the edge
-    /// * `p` - Point to test against the edge
-    #[inline]
-    fn edge_function(a: Vec3, b: Vec3, p: Vec3) -> f32 {
-        (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
-    }
-
     /// Rasterize a triangle using the provided pixel shader.
     ///
     /// This method handles all the common rasterization logic:
@@ -142,37 +124,52 @@ impl EdgeFunctionRasterizer {
         // ─────────────────────────────────────────────────────────────────────
         let min_x = v0.x.min(v1.x).min(v2.x).floor() as i32;
         let max_x = v0.x.max(v1.x).max(v2.x).ceil() as i32;
-        let min_y = v0.y.min(v1.y).min(v2.y).floor() as i32;
+        let top = v0.y.min(v1.y).min(v2.y).floor() as i32;
         let max_y = v0.y.max(v1.y).max(v2.y).ceil() as i32;
 
-        // Clip to framebuffer bounds
+        // Clip to framebuffer bounds (a band view only covers some rows)
+        let first_row = buffer.y_offset() as i32;
         let min_x = min_x.max(0);
         let max_x = max_x.min(buffer.width() as i32 - 1);
-        let min_y = min_y.max(0);
-        let max_y = max_y.min(buffer.height() as i32 - 1);
+        let min_y = top.max(first_row);
+        let max_y = max_y.min(first_row + buffer.height() as i32 - 1);
 
         // ─────────────────────────────────────────────────────────────────────
         // Step 2: Compute signed area (2x triangle area)
         // ─────────────────────────────────────────────────────────────────────
-        let area = Self::edge_function(v0, v1, v2);
+        let area = triangle_area(v0.xy(), v1.xy(), v2.xy());
         if area.abs() < f32::EPSILON {
             return; // Degenerate triangle
         }
         let inv_area = 1.0 / area;
 
         // ─────────────────────────────────────────────────────────────────────
-        // Step 3: Iterate over all pixels in bounding box
+        // Step 3: Set up incremental edge stepping
         // ─────────────────────────────────────────────────────────────────────
-        for y in min_y..=max_y {
-            for x in min_x..=max_x {
-                // Sample at pixel center
-                let p = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+        // Each edge function is linear in the pixel position:
+        //
+        //   E(P) = (B.x - A.x) * (P.y - A.y) - (B.y - A.y) * (P.x - A.x)
+        //
+        // so moving one pixel right adds -(B.y - A.y) and moving one row down
+        // adds (B.x - A.x). E is evaluated once at the first pixel center of the
+        // triangle's top row, each row starts that many row steps further down,
+        // and pixels step right from there. The origin ignores band clipping, so
+        // a band of rows rasterizes exactly as it would in the full buffer.
+        let edges = [(v1, v2), (v2, v0), (v0, v1)];
+        let step_x = edges.map(|(a, b)| a.y - b.y);
+        let step_y = edges.map(|(a, b)| b.x - a.x);
+        let origin = Vec2::new(min_x as f32 + 0.5, top as f32 + 0.5); // Sample at pixel center
+        let origin_w = edges.map(|(a, b)| edge_function(a.xy(), b.xy(), origin));
 
-                // Compute edge functions
-                let w0 = Self::edge_function(v1, v2, p);
-                let w1 = Self::edge_function(v2, v0, p);
-                let w2 = Self::edge_function(v0, v1, p);
+        // ─────────────────────────────────────────────────────────────────────
+        // Step 4: Iterate over all pixels in bounding box
+        // ─────────────────────────────────────────────────────────────────────
+        for y in min_y..=max_y {
+            let rows_down = (y - top) as f32;
+            let [mut w0, mut w1, mut w2] =
+                std::array::from_fn(|i| origin_w[i] + step_y[i] * rows_down);
 
+            for x in min_x..=max_x {
                 // Inside test (handles both CW and CCW winding)
                 let inside = if area > 0.0 {
                     // CCW winding: positive edge functions for interior
@@ -189,10 +186,13 @@ impl EdgeFunctionRasterizer {
                     // Interpolate 1/w for depth testing (linear in screen space)
                     let depth = lambda[0] * inv_w0 + lambda[1] * inv_w1 + lambda[2] * inv_w2;
 
-                    // Delegate to shader for color computation
-                    let color = shader.shade(lambda);
-                    buffer.set_pixel_with_depth(x, y, depth, color);
+                    // Delegate to shader for color computation, once the depth test passes
+                    buffer.shade_fragment(x, y, depth, || shader.shade(lambda));
                 }
+
+                w0 += step_x[0];
+                w1 += step_x[1];
+                w2 += step_x[2];
             }
         }
     }
@@ -227,11 +227,12 @@ impl Rasterizer for EdgeFunctionRasterizer {
         texture: Option<&Texture>,
     ) {
         let [v0, v1, v2] = triangle.points;
+        buffer.set_blend_mode(triangle.blend_mode);
 
         // Select shader based on texture_mode and shading_mode
         match (triangle.texture_mode, texture) {
             // Textured paths (when texture is available)
-            (TextureMode::Replace, Some(tex)) => {
+            (TextureMode::Replace, Some(tex)) if triangle.perspective_correct => {
                 let shader = PerspectiveCorrectTextureShader::new(
                     tex,
                     triangle.texture_coords,
@@ -239,20 +240,38 @@ impl Rasterizer for EdgeFunctionRasterizer {
                 );
                 Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
             }
-            (TextureMode::Modulate, Some(tex)) => {
+            (TextureMode::Modulate, Some(tex)) if triangle.perspective_correct => {
                 let shader = PerspectiveCorrectTextureModulateShader::new(
                     tex,
                     triangle.texture_coords,
                     triangle.points,
                     triangle.vertex_colors,
-                );
+                )
+                .with_gamma_correct(triangle.gamma_correct);
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            // Orthographic triangles have no perspective to correct for
+            (TextureMode::Replace, Some(tex)) => {
+                let shader = TextureShader::new(tex, triangle.texture_coords);
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            (TextureMode::Modulate, Some(tex)) => {
+                let shader = TextureModulateShader::new(
+                    tex,
+                    triangle.texture_coords,
+                    triangle.vertex_colors,
+                )
+                .with_gamma_correct(triangle.gamma_correct);
                 Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
             }
 
             // Non-textured paths (texture_mode is None, or no texture loaded)
             _ => match triangle.shading_mode {
-                ShadingMode::Gouraud => {
-                    let shader = GouraudShader::new(triangle.vertex_colors);
+                // Phong needs frame-wide lighting state, so callers that have it use
+                // fill_triangle_with_shader; otherwise fall back to the lit vertex colors.
+                ShadingMode::Gouraud | ShadingMode::Phong => {
+                    let shader = GouraudShader::new(triangle.vertex_colors)
+                        .with_gamma_correct(triangle.gamma_correct);
                     Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
                 }
                 ShadingMode::Flat | ShadingMode::None => {
@@ -262,4 +281,132 @@ impl Rasterizer for EdgeFunctionRasterizer {
             },
         }
     }
+
+    fn fill_triangle_with_shader<S: PixelShader>(
+        &self,
+        points: [Vec3; 3],
+        buffer: &mut FrameBuffer,
+        shader: &S,
+    ) {
+        let [v0, v1, v2] = points;
+        Self::rasterize_with_shader(v0, v1, v2, buffer, shader);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::utils::edge_function;
+    use crate::math::vec2::Vec2;
+
+    const WIDTH: u32 = 800;
+    const HEIGHT: u32 = 600;
+
+    /// Per-pixel loop evaluating all three edge functions directly, without stepping.
+    fn reference_fill<S: PixelShader>(points: [Vec3; 3], buffer: &mut FrameBuffer, shader: &S) {
+        let [v0, v1, v2] = points;
+        let inv_w = points.map(|v| 1.0 / v.z);
+        let min_x = (v0.x.min(v1.x).min(v2.x).floor() as i32).max(0);
+        let max_x = (v0.x.max(v1.x).max(v2.x).ceil() as i32).min(WIDTH as i32 - 1);
+        let min_y = (v0.y.min(v1.y).min(v2.y).floor() as i32).max(0);
+        let max_y = (v0.y.max(v1.y).max(v2.y).ceil() as i32).min(HEIGHT as i32 - 1);
+        let [a, b, c] = points.map(Vec3::xy);
+        let area = triangle_area(a, b, c);
+        let inv_area = 1.0 / area;
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let w0 = edge_function(b, c, p);
+                let w1 = edge_function(c, a, p);
+                let w2 = edge_function(a, b, p);
+                let inside = if area > 0.0 {
+                    w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0
+                } else {
+                    w0 <= 0.0 && w1 <= 0.0 && w2 <= 0.0
+                };
+                if inside {
+                    let lambda = [w0 * inv_area, w1 * inv_area, w2 * inv_area];
+                    let depth = lambda[0] * inv_w[0] + lambda[1] * inv_w[1] + lambda[2] * inv_w[2];
+                    buffer.write_fragment(x, y, depth, shader.shade(lambda));
+                }
+            }
+        }
+    }
+
+    /// Distance in pixels from the center of pixel (`x`, `y`) to the nearest triangle edge.
+    fn edge_distance(points: [Vec3; 3], x: usize, y: usize) -> f32 {
+        let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+        let [a, b, c] = points.map(Vec3::xy);
+        [(b, c), (c, a), (a, b)]
+            .into_iter()
+            .map(|(a, b)| edge_function(a, b, p).abs() / (b - a).magnitude())
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    fn assert_matches_reference(points: [Vec3; 3]) {
+        let shader = GouraudShader::new([0xFFFF0000, 0xFF00FF00, 0xFF0000FF]);
+        let size = (WIDTH * HEIGHT) as usize;
+
+        let (mut color, mut depth) = (vec![0u32; size], vec![0.0f32; size]);
+        let mut fb = FrameBuffer::new(&mut color, &mut depth, WIDTH, HEIGHT);
+        EdgeFunctionRasterizer::new().fill_triangle_with_shader(points, &mut fb, &shader);
+
+        let (mut expected_color, mut expected_depth) = (vec![0u32; size], vec![0.0f32; size]);
+        let mut fb = FrameBuffer::new(&mut expected_color, &mut expected_depth, WIDTH, HEIGHT);
+        reference_fill(points, &mut fb, &shader);
+
+        assert!(color.iter().any(|&c| c != 0), "triangle was not drawn");
+        for i in 0..size {
+            let (x, y) = (i % WIDTH as usize, i / WIDTH as usize);
+            let (covered, expected_covered) = (color[i] != 0, expected_color[i] != 0);
+            if covered != expected_covered {
+                // Stepping drift can only flip pixels whose centers sit on an edge
+                let distance = edge_distance(points, x, y);
+                assert!(
+                    distance < 1e-2,
+                    "pixel ({x}, {y}) is {distance} px from an edge"
+                );
+                continue;
+            }
+            if covered {
+                let channels = |c: u32| c.to_be_bytes();
+                for (a, b) in channels(color[i])
+                    .into_iter()
+                    .zip(channels(expected_color[i]))
+                {
+                    assert!(a.abs_diff(b) <= 1, "pixel ({x}, {y})");
+                }
+                assert!(
+                    (depth[i] - expected_depth[i]).abs() <= 1e-5,
+                    "pixel ({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stepping_matches_per_pixel_evaluation() {
+        // The medium and large benchmark triangles, with varying W for depth
+        let medium = [
+            Vec3::new(100.0, 100.0, 1.0),
+            Vec3::new(300.0, 100.0, 2.0),
+            Vec3::new(200.0, 300.0, 3.0),
+        ];
+        let large = [
+            Vec3::new(50.0, 50.0, 1.5),
+            Vec3::new(750.0, 100.0, 4.0),
+            Vec3::new(400.0, 550.0, 2.5),
+        ];
+        // Sub-pixel vertices, opposite winding, and partly off screen
+        let awkward = [
+            Vec3::new(-40.3, 420.7, 2.0),
+            Vec3::new(612.9, 610.2, 1.0),
+            Vec3::new(333.3, 13.1, 5.0),
+        ];
+
+        for points in [medium, large, awkward] {
+            assert_matches_reference(points);
+        }
+    }
 }
@@ -0,0 +1,309 @@
+//! SIMD edge function rasterization over 2x2 pixel quads.
+//!
+//! Same algorithm as [`EdgeFunctionRasterizer`](super::EdgeFunctionRasterizer),
+//! but the bounding box is walked in 2x2 quads, the way GPUs shade pixels.
+//! Each edge function is evaluated for all four pixels of a quad at once in a
+//! 4-lane `f32x4`:
+//!
+//! ```text
+//! lane:  0 1      pixel: (x, y)   (x+1, y)
+//!        2 3             (x, y+1) (x+1, y+1)
+//! ```
+//!
+//! # Partial Quads
+//!
+//! When the bounding box has an odd width or height, the last quad in a row or
+//! column hangs over the edge. Those lanes are masked off along with the lanes
+//! that fail the inside test, so only covered pixels inside the box are written.
+//!
+//! # Portability
+//!
+//! Vector math comes from the `wide` crate, which uses SSE/NEON/WASM SIMD where
+//! the target has it and falls back to scalar code elsewhere. Each lane performs
+//! exactly the same f32 operations as [`edge_function`](crate::math::utils::edge_function),
+//! so coverage matches evaluating the edge functions per pixel.
+
+use wide::{f32x4, CmpGe, CmpLe};
+
+use super::shader::{FlatShader, GouraudShader, PixelShader};
+use super::{Rasterizer, Triangle};
+use crate::engine::TextureMode;
+use crate::math::utils::triangle_area;
+use crate::math::vec3::Vec3;
+use crate::render::framebuffer::FrameBuffer;
+use crate::render::rasterizer::shader::{
+    PerspectiveCorrectTextureModulateShader, PerspectiveCorrectTextureShader,
+    TextureModulateShader, TextureShader,
+};
+use crate::texture::Texture;
+use crate::ShadingMode;
+
+/// Pixel-center offsets of the four quad lanes from the quad origin.
+const LANE_X: [f32; 4] = [0.5, 1.5, 0.5, 1.5];
+const LANE_Y: [f32; 4] = [0.5, 0.5, 1.5, 1.5];
+
+/// Triangle rasterizer evaluating edge functions for 2x2 quads with SIMD.
+pub struct EdgeFunctionSimdRasterizer;
+
+impl EdgeFunctionSimdRasterizer {
+    /// Creates a new SIMD edge function rasterizer instance.
+    pub fn new() -> Self {
+        EdgeFunctionSimdRasterizer {}
+    }
+
+    /// Evaluates the edge function (A -> B) at four points at once.
+    ///
+    /// Lane for lane the same arithmetic as [`edge_function`](crate::math::utils::edge_function):
+    ///
+    /// ```text
+    /// E(P) = (B.x - A.x) * (P.y - A.y) - (B.y - A.y) * (P.x - A.x)
+    /// ```
+    #[inline]
+    fn edge_function(a: Vec3, b: Vec3, px: f32x4, py: f32x4) -> f32x4 {
+        f32x4::splat(b.x - a.x) * (py - f32x4::splat(a.y))
+            - f32x4::splat(b.y - a.y) * (px - f32x4::splat(a.x))
+    }
+
+    /// Rasterize a triangle quad by quad using the provided pixel shader.
+    ///
+    /// Coverage, barycentrics and depth are computed four lanes at a time; the
+    /// shader then runs once per covered lane.
+    ///
+    /// # Arguments
+    /// * `v0, v1, v2` - Triangle vertices where x,y are screen coords and z stores clip-space W
+    /// * `buffer` - Framebuffer with color and depth buffers
+    /// * `shader` - Pixel shader for color computation
+    fn rasterize_with_shader<S: PixelShader>(
+        v0: Vec3,
+        v1: Vec3,
+        v2: Vec3,
+        buffer: &mut FrameBuffer,
+        shader: &S,
+    ) {
+        let inv_w0 = f32x4::splat(1.0 / v0.z);
+        let inv_w1 = f32x4::splat(1.0 / v1.z);
+        let inv_w2 = f32x4::splat(1.0 / v2.z);
+
+        // Bounding box, clipped to the framebuffer (a band view only covers some rows)
+        let first_row = buffer.y_offset() as i32;
+        let min_x = (v0.x.min(v1.x).min(v2.x).floor() as i32).max(0);
+        let max_x = (v0.x.max(v1.x).max(v2.x).ceil() as i32).min(buffer.width() as i32 - 1);
+        let min_y = (v0.y.min(v1.y).min(v2.y).floor() as i32).max(first_row);
+        let max_y =
+            (v0.y.max(v1.y).max(v2.y).ceil() as i32).min(first_row + buffer.height() as i32 - 1);
+
+        // Signed area, computed exactly as the scalar rasterizer does
+        let area = triangle_area(v0.xy(), v1.xy(), v2.xy());
+        if area.abs() < f32::EPSILON {
+            return; // Degenerate triangle
+        }
+        let inv_area = f32x4::splat(1.0 / area);
+        let zero = f32x4::splat(0.0);
+
+        for y in (min_y..=max_y).step_by(2) {
+            let py = f32x4::splat(y as f32) + f32x4::new(LANE_Y);
+            // Bottom lanes hang off a box with an odd height
+            let row_mask = if y == max_y { 0b0011 } else { 0b1111 };
+
+            for x in (min_x..=max_x).step_by(2) {
+                let px = f32x4::splat(x as f32) + f32x4::new(LANE_X);
+                // Right lanes hang off a box with an odd width
+                let column_mask = if x == max_x { 0b0101 } else { 0b1111 };
+
+                let w0 = Self::edge_function(v1, v2, px, py);
+                let w1 = Self::edge_function(v2, v0, px, py);
+                let w2 = Self::edge_function(v0, v1, px, py);
+
+                // Inside test (handles both CW and CCW winding)
+                let inside = if area > 0.0 {
+                    w0.cmp_ge(zero) & w1.cmp_ge(zero) & w2.cmp_ge(zero)
+                } else {
+                    w0.cmp_le(zero) & w1.cmp_le(zero) & w2.cmp_le(zero)
+                };
+                let mask = inside.move_mask() & row_mask & column_mask;
+                if mask == 0 {
+                    continue;
+                }
+
+                let l0 = w0 * inv_area;
+                let l1 = w1 * inv_area;
+                let l2 = w2 * inv_area;
+                let depth = (l0 * inv_w0 + l1 * inv_w1 + l2 * inv_w2).to_array();
+                let (l0, l1, l2) = (l0.to_array(), l1.to_array(), l2.to_array());
+
+                for lane in 0..4 {
+                    if mask & (1 << lane) != 0 {
+                        let (dx, dy) = ((lane & 1) as i32, (lane >> 1) as i32);
+                        buffer.shade_fragment(x + dx, y + dy, depth[lane], || {
+                            shader.shade([l0[lane], l1[lane], l2[lane]])
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Default for EdgeFunctionSimdRasterizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rasterizer for EdgeFunctionSimdRasterizer {
+    /// Fills a triangle quad by quad, selecting a shader the same way as
+    /// [`EdgeFunctionRasterizer`](super::EdgeFunctionRasterizer).
+    fn fill_triangle(
+        &self,
+        triangle: &Triangle,
+        buffer: &mut FrameBuffer,
+        color: u32,
+        texture: Option<&Texture>,
+    ) {
+        let [v0, v1, v2] = triangle.points;
+        buffer.set_blend_mode(triangle.blend_mode);
+
+        match (triangle.texture_mode, texture) {
+            (TextureMode::Replace, Some(tex)) if triangle.perspective_correct => {
+                let shader = PerspectiveCorrectTextureShader::new(
+                    tex,
+                    triangle.texture_coords,
+                    triangle.points,
+                );
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            (TextureMode::Modulate, Some(tex)) if triangle.perspective_correct => {
+                let shader = PerspectiveCorrectTextureModulateShader::new(
+                    tex,
+                    triangle.texture_coords,
+                    triangle.points,
+                    triangle.vertex_colors,
+                )
+                .with_gamma_correct(triangle.gamma_correct);
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            // Orthographic triangles have no perspective to correct for
+            (TextureMode::Replace, Some(tex)) => {
+                let shader = TextureShader::new(tex, triangle.texture_coords);
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            (TextureMode::Modulate, Some(tex)) => {
+                let shader = TextureModulateShader::new(
+                    tex,
+                    triangle.texture_coords,
+                    triangle.vertex_colors,
+                )
+                .with_gamma_correct(triangle.gamma_correct);
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            _ => match triangle.shading_mode {
+                ShadingMode::Gouraud | ShadingMode::Phong => {
+                    let shader = GouraudShader::new(triangle.vertex_colors)
+                        .with_gamma_correct(triangle.gamma_correct);
+                    Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+                }
+                ShadingMode::Flat | ShadingMode::None => {
+                    let shader = FlatShader::new(color);
+                    Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+                }
+            },
+        }
+    }
+
+    fn fill_triangle_with_shader<S: PixelShader>(
+        &self,
+        points: [Vec3; 3],
+        buffer: &mut FrameBuffer,
+        shader: &S,
+    ) {
+        let [v0, v1, v2] = points;
+        Self::rasterize_with_shader(v0, v1, v2, buffer, shader);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vec2::Vec2;
+    use crate::render::rasterizer::EdgeFunctionRasterizer;
+
+    /// Untouched pixels keep this color.
+    const BACKGROUND: u32 = 0xFF123456;
+
+    /// Rasterizes `points` with `rasterizer` into a `width` x `height` buffer.
+    fn render<R: Rasterizer>(
+        rasterizer: &R,
+        points: [Vec3; 3],
+        width: u32,
+        height: u32,
+    ) -> Vec<u32> {
+        let shader = GouraudShader::new([0xFFFF0000, 0xFF00FF00, 0xFF0000FF]);
+        let mut color = vec![BACKGROUND; (width * height) as usize];
+        let mut depth = vec![0.0; (width * height) as usize];
+        let mut fb = FrameBuffer::new(&mut color, &mut depth, width, height);
+        rasterizer.fill_triangle_with_shader(points, &mut fb, &shader);
+        color
+    }
+
+    #[test]
+    fn lanes_match_the_scalar_edge_function() {
+        use crate::math::utils::edge_function;
+
+        let (a, b) = (Vec3::new(1.5, -2.0, 1.0), Vec3::new(7.25, 4.5, 1.0));
+        let px = [0.5, 3.0, -4.75, 100.0];
+        let py = [0.5, 1.0, 8.25, -0.125];
+        let lanes =
+            EdgeFunctionSimdRasterizer::edge_function(a, b, f32x4::from(px), f32x4::from(py))
+                .to_array();
+        for lane in 0..4 {
+            let p = Vec2::new(px[lane], py[lane]);
+            assert_eq!(lanes[lane], edge_function(a.xy(), b.xy(), p));
+        }
+    }
+
+    #[test]
+    fn masked_lanes_do_not_write_stray_pixels() {
+        // Odd bounding boxes leave partial quads on the right and bottom edges
+        let triangles = [
+            [
+                Vec3::new(1.0, 1.0, 1.0),
+                Vec3::new(8.0, 2.0, 2.0),
+                Vec3::new(3.0, 9.0, 3.0),
+            ],
+            [
+                Vec3::new(2.3, 10.6, 1.0),
+                Vec3::new(10.2, 0.4, 1.0),
+                Vec3::new(0.7, 0.9, 1.0),
+            ],
+            // A sliver one pixel wide
+            [
+                Vec3::new(5.0, 0.0, 1.0),
+                Vec3::new(6.0, 0.0, 1.0),
+                Vec3::new(5.5, 11.0, 1.0),
+            ],
+        ];
+
+        for points in triangles {
+            let simd = render(&EdgeFunctionSimdRasterizer::new(), points, 13, 11);
+            let scalar = render(&EdgeFunctionRasterizer::new(), points, 13, 11);
+            assert!(
+                simd.iter().any(|&c| c != BACKGROUND),
+                "triangle was not drawn"
+            );
+            assert_eq!(simd, scalar, "triangle {:?}", points);
+        }
+    }
+
+    #[test]
+    fn partial_quads_at_the_buffer_edge_stay_in_bounds() {
+        // Covers an entire 3x3 buffer, so the last quad in each direction is half outside
+        let points = [
+            Vec3::new(-10.0, -10.0, 1.0),
+            Vec3::new(20.0, -10.0, 1.0),
+            Vec3::new(-10.0, 20.0, 1.0),
+        ];
+        let simd = render(&EdgeFunctionSimdRasterizer::new(), points, 3, 3);
+        assert_eq!(simd, render(&EdgeFunctionRasterizer::new(), points, 3, 3));
+        assert!(simd.iter().all(|&c| c != BACKGROUND));
+    }
+}
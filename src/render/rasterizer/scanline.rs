@@ -1,36 +1,41 @@
 //! Scanline-based triangle rasterization.
 //!
-//! This module implements triangle rasterization using the classic scanline algorithm
-//! with flat-top/flat-bottom triangle decomposition. This approach was widely used
-//! in early software renderers and remains an efficient choice for CPU-based rendering.
+//! This module implements triangle rasterization using the classic scanline algorithm,
+//! walking each row between the triangle's long edge and its two short edges. This
+//! approach was widely used in early software renderers and remains an efficient
+//! choice for CPU-based rendering.
 //!
 //! # Algorithm Overview
 //!
 //! The scanline algorithm processes triangles one horizontal line at a time:
 //!
 //! 1. **Sort vertices** by Y coordinate (top to bottom in screen space)
-//! 2. **Decompose** the triangle into simpler shapes (flat-top and/or flat-bottom)
-//! 3. **Rasterize** each scanline by computing left/right edge intersections
+//! 2. **Walk rows** from `v0` to `v2`, pairing the long edge with the short edge
+//!    that spans each row
+//! 3. **Rasterize** each scanline between the two edge intersections
 //!
-//! # Triangle Decomposition
+//! # Long and Short Edges
 //!
-//! Any triangle can be decomposed into at most two simpler triangles:
+//! The long edge `v0 -> v2` spans every row. The short edges `v0 -> v1` and
+//! `v1 -> v2` each span the rows above and below `v1`:
 //!
 //! ```text
-//!        v0                   v0
-//!        /\                   /\
-//!       /  \                 /  \
-//!      /    \       =>      /----\<- split at v1.y
-//!     /      \             v1   split
-//!    /________\             \    /
-//!   v1        v2             \  /
-//!                             \/
-//!                             v2
-//!
-//!   General triangle      Flat-bottom (top) + Flat-top (bottom)
+//!        v0
+//!        /\
+//!       /  \    rows above v1.y: v0 -> v1 vs. v0 -> v2
+//!      /    \
+//!    v1------\  <- short edge switches at v1.y
+//!      \      \
+//!        \     \  rows below v1.y: v1 -> v2 vs. v0 -> v2
+//!          \    \
+//!            \   \
+//!              \  \
+//!                v2
 //! ```
 //!
-//! Special cases (already flat-top or flat-bottom) require no splitting.
+//! All rows are visited in a single pass, so the row at `v1` is filled once.
+//! Flat-top and flat-bottom triangles need no special case: their horizontal
+//! edge simply spans no rows.
 //!
 //! # Inverse Slope Method
 //!
@@ -41,16 +46,17 @@
 //! inv_slope = dx / dy = (x_end - x_start) / (y_end - y_start)
 //! ```
 //!
-//! For each scanline: `x = x_start + inv_slope * (y - y_start)`
-//!
-//! # Gouraud Shading
+//! For each scanline: `x = x_start + inv_slope * (y - y_start)`, evaluated at the
+//! row's pixel centers (`y + 0.5`).
 //!
-//! For smooth shading, we perform bilinear interpolation:
-//! 1. Interpolate colors along the left and right edges (using Y progress)
-//! 2. Interpolate between left/right colors across the scanline (using X progress)
+//! # Coverage and Interpolation
 //!
-//! This is mathematically equivalent to barycentric interpolation but decomposed
-//! into two sequential 1D interpolations, which is more natural for scanline traversal.
+//! A pixel is filled when its center lies within the span, edges included. The
+//! span ends are checked against the same edge functions the edge function
+//! rasterizer uses, which also supply the barycentric coordinates for attribute
+//! interpolation. The edge function rasterizer steps those functions
+//! incrementally instead of evaluating them per pixel, so the two can only
+//! disagree through rounding on pixel centers lying (almost) exactly on an edge.
 //!
 //! # Comparison with Edge Function Rasterization
 //!
@@ -67,7 +73,10 @@
 //! - Foley, van Dam et al., "Computer Graphics: Principles and Practice"
 //! - Abrash, Michael, "Graphics Programming Black Book"
 
-use super::shader::{FlatShader, GouraudShader, PixelShader, TextureModulateShader, TextureShader};
+use super::shader::{
+    FlatShader, GouraudShader, PerspectiveCorrectTextureModulateShader,
+    PerspectiveCorrectTextureShader, PixelShader, TextureModulateShader, TextureShader,
+};
 use super::{Rasterizer, Triangle};
 use crate::engine::TextureMode;
 use crate::math::utils::{edge_function, triangle_area};
@@ -77,23 +86,10 @@ use crate::render::framebuffer::FrameBuffer;
 use crate::texture::Texture;
 use crate::ShadingMode;
 
-/// Compute barycentric coordinates for point p in triangle (v0, v1, v2).
-///
-/// Uses precomputed inverse area for efficiency when rasterizing many pixels.
-/// Returns [λ0, λ1, λ2] where each λ represents the weight of the
-/// corresponding vertex. These sum to 1.0 for points inside the triangle.
-#[inline]
-fn barycentric(v0: Vec2, v1: Vec2, v2: Vec2, p: Vec2, inv_area: f32) -> [f32; 3] {
-    let w0 = edge_function(v1, v2, p);
-    let w1 = edge_function(v2, v0, p);
-    let w2 = edge_function(v0, v1, p);
-    [w0 * inv_area, w1 * inv_area, w2 * inv_area]
-}
-
 /// Scanline-based triangle rasterizer.
 ///
-/// This rasterizer uses the classic flat-top/flat-bottom decomposition approach,
-/// processing triangles one horizontal scanline at a time. It supports both
+/// This rasterizer walks each horizontal scanline between the triangle's long edge
+/// and the short edge spanning that row. It supports both
 /// flat shading and Gouraud (smooth) shading with per-vertex color interpolation.
 ///
 /// # Characteristics
@@ -105,8 +101,8 @@ fn barycentric(v0: Vec2, v1: Vec2, v2: Vec2, p: Vec2, inv_area: f32) -> [f32; 3]
 /// # Implementation Notes
 ///
 /// The rasterizer handles vertex sorting internally, so input triangles can have
-/// vertices in any order. When Gouraud shading is enabled, vertex colors are
-/// sorted alongside vertices to maintain correct attribute correspondence.
+/// vertices in any order. Only copies are sorted for traversal; attributes are
+/// interpolated in the original vertex order.
 pub struct ScanlineRasterizer;
 
 impl ScanlineRasterizer {
@@ -136,16 +132,33 @@ impl ScanlineRasterizer {
         }
     }
 
+    /// X coordinate where the edge from `a` down to `b` crosses the row at `y`.
+    ///
+    /// A horizontal edge has no single crossing, so its start is returned; the
+    /// span is bounded by the other edge sharing that row.
+    #[inline]
+    fn edge_x(a: Vec3, b: Vec3, y: f32) -> f32 {
+        let height = b.y - a.y;
+        if height <= 0.0 {
+            return a.x;
+        }
+        let inv_slope = (b.x - a.x) / height;
+        a.x + inv_slope * (y - a.y)
+    }
+
     // =========================================================================
     // Shader-based rasterization methods
     // =========================================================================
 
     /// Rasterize a triangle using the provided pixel shader.
     ///
-    /// This method combines scanline traversal (for efficiency) with barycentric
-    /// coordinate computation (for correct attribute interpolation). The key insight
-    /// is that we sort vertices for scanline traversal but compute barycentrics
-    /// using the original vertex order.
+    /// Rows are walked top to bottom between the long edge (`v0 -> v2` after
+    /// sorting) and whichever short edge spans the row, which switches at `v1`.
+    /// Each span is then settled with the same edge functions, evaluated the same
+    /// way, as [`EdgeFunctionRasterizer`](super::EdgeFunctionRasterizer), and the
+    /// barycentrics come from those edge functions too. Both rasterizers therefore
+    /// fill the same pixels with the same colors and depths, up to the rounding of
+    /// the edge function rasterizer's incremental stepping.
     ///
     /// # Arguments
     /// * `v0, v1, v2` - Original (unsorted) triangle vertices (z stores clip-space W)
@@ -162,178 +175,80 @@ impl ScanlineRasterizer {
         // These can be linearly interpolated in screen space for depth testing
         let inv_w = [1.0 / v0.z, 1.0 / v1.z, 1.0 / v2.z];
 
-        // Convert to Vec2 for barycentric calculations (only x, y matter)
-        let v0_2d = Vec2::new(v0.x, v0.y);
-        let v1_2d = Vec2::new(v1.x, v1.y);
-        let v2_2d = Vec2::new(v2.x, v2.y);
-
-        // Compute area for barycentric normalization
-        let area = triangle_area(v0_2d, v1_2d, v2_2d);
+        let area = triangle_area(v0.xy(), v1.xy(), v2.xy());
         if area.abs() < f32::EPSILON {
             return; // Degenerate triangle
         }
         let inv_area = 1.0 / area;
 
-        // Sort vertices for scanline traversal
-        // IMPORTANT: We sort copies, keeping original v0, v1, v2 for barycentrics
+        // Edge functions over the ORIGINAL vertex order, sampled at pixel centers
+        let edges = [(v1, v2), (v2, v0), (v0, v1)];
+        let edge_weights = |x: i32, py: f32| {
+            let p = Vec2::new(x as f32 + 0.5, py);
+            edges.map(|(a, b)| edge_function(a.xy(), b.xy(), p))
+        };
+        // Centers exactly on an edge count as inside, for either winding
+        let inside = |x: i32, py: f32| {
+            let w = edge_weights(x, py);
+            if area > 0.0 {
+                w.iter().all(|&e| e >= 0.0)
+            } else {
+                w.iter().all(|&e| e <= 0.0)
+            }
+        };
+
+        // Sort copies for scanline traversal, keeping v0, v1, v2 for the edge functions
         let mut sv0 = v0;
         let mut sv1 = v1;
         let mut sv2 = v2;
         Self::sort_vertices(&mut sv0, &mut sv1, &mut sv2);
 
-        // Check triangle type and call appropriate fill method
-        if (sv1.y - sv2.y).abs() < f32::EPSILON {
-            // Flat-bottom triangle
-            Self::fill_flat_bottom_with_shader(
-                sv0, sv1, sv2, v0_2d, v1_2d, v2_2d, inv_w, inv_area, buffer, shader,
-            );
-        } else if (sv0.y - sv1.y).abs() < f32::EPSILON {
-            // Flat-top triangle
-            Self::fill_flat_top_with_shader(
-                sv0, sv1, sv2, v0_2d, v1_2d, v2_2d, inv_w, inv_area, buffer, shader,
-            );
-        } else {
-            // General triangle - split into flat-bottom + flat-top
-
-            // t is the ratio of the height of the triangle from sv0 to sv1 to the total height of the triangle
-            let t = (sv1.y - sv0.y) / (sv2.y - sv0.y);
-            // We calculate the midpoint x coordinate by interpolating the x coordinates of sv0 and sv2 based on the ratio t
-            let split_x = sv0.x + (sv2.x - sv0.x) * t;
-            let split_point = Vec3::new(split_x, sv1.y, 0.0);
-
-            // Fill top half (flat-bottom)
-            Self::fill_flat_bottom_with_shader(
-                sv0,
-                split_point,
-                sv1,
-                v0_2d,
-                v1_2d,
-                v2_2d, // Always use original for barycentrics
-                inv_w,
-                inv_area,
-                buffer,
-                shader,
-            );
-
-            // Fill bottom half (flat-top)
-            Self::fill_flat_top_with_shader(
-                sv1,
-                split_point,
-                sv2,
-                v0_2d,
-                v1_2d,
-                v2_2d,
-                inv_w,
-                inv_area,
-                buffer,
-                shader,
-            );
-        }
-    }
-
-    /// Fill a flat-bottom triangle using a pixel shader.
-    ///
-    /// # Arguments
-    /// * `sv0, sv1, sv2` - Sorted vertices for scanline traversal
-    /// * `v0, v1, v2` - Original vertices (Vec2) for barycentric computation
-    /// * `inv_w` - 1/w values for each original vertex (for depth interpolation)
-    /// * `inv_area` - 1/area for barycentric normalization
-    fn fill_flat_bottom_with_shader<S: PixelShader>(
-        sv0: Vec3, // Top vertex (sorted)
-        sv1: Vec3, // Bottom-left (sorted)
-        sv2: Vec3, // Bottom-right (sorted)
-        v0: Vec2,  // Original vertices for barycentrics
-        v1: Vec2,
-        v2: Vec2,
-        inv_w: [f32; 3], // 1/w for each original vertex
-        inv_area: f32,
-        buffer: &mut FrameBuffer,
-        shader: &S,
-    ) {
-        let height = sv1.y - sv0.y;
-        if height.abs() < f32::EPSILON {
-            return;
-        }
-
-        let inv_slope_1 = (sv1.x - sv0.x) / height;
-        let inv_slope_2 = (sv2.x - sv0.x) / height;
-
-        let y_start = sv0.y.ceil() as i32;
-        let y_end = sv1.y.floor() as i32;
+        // Rows whose centers lie in [sv0.y, sv2.y], clipped to the buffer band.
+        // Walking the whole height in one pass means the row at v1 is visited once.
+        let first_row = buffer.y_offset() as i32;
+        let max_x = buffer.width() as i32 - 1;
+        let y_start = ((sv0.y - 0.5).ceil() as i32).max(first_row);
+        let y_end = ((sv2.y - 0.5).floor() as i32).min(first_row + buffer.height() as i32 - 1);
 
         for y in y_start..=y_end {
-            let dy = y as f32 - sv0.y;
-            let x1 = sv0.x + inv_slope_1 * dy;
-            let x2 = sv0.x + inv_slope_2 * dy;
-
-            let (x_left, x_right) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
-
-            let x_start = x_left.ceil() as i32;
-            let x_end = x_right.floor() as i32;
-
-            for x in x_start..=x_end {
-                // Compute barycentric coords using ORIGINAL vertices
-                let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
-                let lambda = barycentric(v0, v1, v2, p, inv_area);
-
-                // Interpolate 1/w for depth testing (linear in screen space)
-                let depth = lambda[0] * inv_w[0] + lambda[1] * inv_w[1] + lambda[2] * inv_w[2];
-
-                let color = shader.shade(lambda);
-                buffer.set_pixel_with_depth(x, y, depth, color);
+            let py = y as f32 + 0.5;
+            let long = Self::edge_x(sv0, sv2, py);
+            let short = if py < sv1.y {
+                Self::edge_x(sv0, sv1, py) // Upper short edge
+            } else {
+                Self::edge_x(sv1, sv2, py) // Lower short edge
+            };
+            let (x_left, x_right) = if long < short {
+                (long, short)
+            } else {
+                (short, long)
+            };
+
+            // Centers within [x_left, x_right], then nudged onto the exact edge
+            // function coverage so centers on (or rounding across) an edge agree
+            let mut x_start = ((x_left - 0.5).ceil() as i32).max(0);
+            let mut x_end = ((x_right - 0.5).floor() as i32).min(max_x);
+            while x_start > 0 && inside(x_start - 1, py) {
+                x_start -= 1;
+            }
+            while x_start <= x_end && !inside(x_start, py) {
+                x_start += 1;
+            }
+            while x_end < max_x && inside(x_end + 1, py) {
+                x_end += 1;
+            }
+            while x_end >= x_start && !inside(x_end, py) {
+                x_end -= 1;
             }
-        }
-    }
-
-    /// Fill a flat-top triangle using a pixel shader.
-    ///
-    /// # Arguments
-    /// * `sv0, sv1, sv2` - Sorted vertices for scanline traversal
-    /// * `v0, v1, v2` - Original vertices (Vec2) for barycentric computation
-    /// * `inv_w` - 1/w values for each original vertex (for depth interpolation)
-    /// * `inv_area` - 1/area for barycentric normalization
-    fn fill_flat_top_with_shader<S: PixelShader>(
-        sv0: Vec3, // Top-left (sorted)
-        sv1: Vec3, // Top-right (sorted)
-        sv2: Vec3, // Bottom vertex (sorted)
-        v0: Vec2,  // Original vertices for barycentrics
-        v1: Vec2,
-        v2: Vec2,
-        inv_w: [f32; 3], // 1/w for each original vertex
-        inv_area: f32,
-        buffer: &mut FrameBuffer,
-        shader: &S,
-    ) {
-        let height = sv2.y - sv0.y;
-        if height.abs() < f32::EPSILON {
-            return;
-        }
-
-        let inv_slope_1 = (sv2.x - sv0.x) / height;
-        let inv_slope_2 = (sv2.x - sv1.x) / height;
-
-        let y_start = sv0.y.ceil() as i32;
-        let y_end = sv2.y.floor() as i32;
-
-        for y in y_start..=y_end {
-            let dy = y as f32 - sv0.y;
-            let x1 = sv0.x + inv_slope_1 * dy;
-            let x2 = sv1.x + inv_slope_2 * dy;
-
-            let (x_left, x_right) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
-
-            let x_start = x_left.ceil() as i32;
-            let x_end = x_right.floor() as i32;
 
             for x in x_start..=x_end {
-                let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
-                let lambda = barycentric(v0, v1, v2, p, inv_area);
+                let [w0, w1, w2] = edge_weights(x, py);
+                let lambda = [w0 * inv_area, w1 * inv_area, w2 * inv_area];
 
                 // Interpolate 1/w for depth testing (linear in screen space)
                 let depth = lambda[0] * inv_w[0] + lambda[1] * inv_w[1] + lambda[2] * inv_w[2];
 
-                let color = shader.shade(lambda);
-                buffer.set_pixel_with_depth(x, y, depth, color);
+                buffer.shade_fragment(x, y, depth, || shader.shade(lambda));
             }
         }
     }
@@ -355,8 +270,10 @@ impl Rasterizer for ScanlineRasterizer {
     /// # Shader Selection
     ///
     /// The shader is selected based on texture mode and shading mode:
-    /// - Texture Replace: TextureShader (texture color only)
-    /// - Texture Modulate: TextureModulateShader (texture * lighting)
+    /// - Texture Replace: PerspectiveCorrectTextureShader (texture color only)
+    /// - Texture Modulate: PerspectiveCorrectTextureModulateShader (texture * lighting)
+    /// - Either texture mode on a non-perspective (orthographic) triangle: the affine
+    ///   TextureShader / TextureModulateShader
     /// - Gouraud: GouraudShader (interpolated vertex colors)
     /// - Flat/None: FlatShader (single color)
     ///
@@ -374,9 +291,29 @@ impl Rasterizer for ScanlineRasterizer {
         texture: Option<&Texture>,
     ) {
         let [v0, v1, v2] = triangle.points;
+        buffer.set_blend_mode(triangle.blend_mode);
 
         // Select shader based on texture_mode and shading_mode
         match (triangle.texture_mode, texture) {
+            (TextureMode::Replace, Some(tex)) if triangle.perspective_correct => {
+                let shader = PerspectiveCorrectTextureShader::new(
+                    tex,
+                    triangle.texture_coords,
+                    triangle.points,
+                );
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            (TextureMode::Modulate, Some(tex)) if triangle.perspective_correct => {
+                let shader = PerspectiveCorrectTextureModulateShader::new(
+                    tex,
+                    triangle.texture_coords,
+                    triangle.points,
+                    triangle.vertex_colors,
+                )
+                .with_gamma_correct(triangle.gamma_correct);
+                Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
+            }
+            // Orthographic triangles have no perspective to correct for
             (TextureMode::Replace, Some(tex)) => {
                 let shader = TextureShader::new(tex, triangle.texture_coords);
                 Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
@@ -386,12 +323,16 @@ impl Rasterizer for ScanlineRasterizer {
                     tex,
                     triangle.texture_coords,
                     triangle.vertex_colors,
-                );
+                )
+                .with_gamma_correct(triangle.gamma_correct);
                 Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
             }
             _ => match triangle.shading_mode {
-                ShadingMode::Gouraud => {
-                    let shader = GouraudShader::new(triangle.vertex_colors);
+                // Phong needs frame-wide lighting state, so callers that have it use
+                // fill_triangle_with_shader; otherwise fall back to the lit vertex colors.
+                ShadingMode::Gouraud | ShadingMode::Phong => {
+                    let shader = GouraudShader::new(triangle.vertex_colors)
+                        .with_gamma_correct(triangle.gamma_correct);
                     Self::rasterize_with_shader(v0, v1, v2, buffer, &shader);
                 }
                 ShadingMode::Flat | ShadingMode::None => {
@@ -401,4 +342,14 @@ impl Rasterizer for ScanlineRasterizer {
             },
         }
     }
+
+    fn fill_triangle_with_shader<S: PixelShader>(
+        &self,
+        points: [Vec3; 3],
+        buffer: &mut FrameBuffer,
+        shader: &S,
+    ) {
+        let [v0, v1, v2] = points;
+        Self::rasterize_with_shader(v0, v1, v2, buffer, shader);
+    }
 }
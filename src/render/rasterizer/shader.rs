@@ -17,8 +17,11 @@
 //! - Texture sampling
 //! - Final color computation
 
-use crate::colors::{pack_color, unpack_color};
-use crate::prelude::{Vec2, Vec3};
+use crate::colors::{
+    linear_to_srgb, pack_color, pack_color_linear, srgb_to_linear, unpack_color,
+    unpack_color_linear,
+};
+use crate::prelude::{Vec2, Vec3, Vec4};
 use crate::texture::Texture;
 
 /// Trait for per-pixel shading computations.
@@ -69,18 +72,55 @@ impl PixelShader for FlatShader {
 pub struct GouraudShader {
     /// Unpacked RGB colors for each vertex, in [0.0, 1.0] range
     colors: [(f32, f32, f32); 3],
+    /// Whether `colors` are linear light, converted back to sRGB when packing
+    linear: bool,
 }
 
 impl GouraudShader {
     pub fn new(vertex_colors: [u32; 3]) -> Self {
         Self {
-            colors: [
-                unpack_color(vertex_colors[0]),
-                unpack_color(vertex_colors[1]),
-                unpack_color(vertex_colors[2]),
-            ],
+            colors: vertex_colors.map(unpack_color),
+            linear: false,
         }
     }
+
+    /// Interpolate in linear light instead of directly on the sRGB values.
+    ///
+    /// Gradients keep their perceived brightness through the midtones, at the
+    /// cost of a power function per channel per pixel.
+    pub fn with_gamma_correct(mut self, gamma_correct: bool) -> Self {
+        if gamma_correct != self.linear {
+            self.colors = self.colors.map(|color| reencode(color, gamma_correct));
+            self.linear = gamma_correct;
+        }
+        self
+    }
+}
+
+/// Convert an unpacked color from sRGB to linear light, or back when `to_linear` is false.
+#[inline]
+fn reencode((r, g, b): (f32, f32, f32), to_linear: bool) -> (f32, f32, f32) {
+    let convert = if to_linear {
+        srgb_to_linear
+    } else {
+        linear_to_srgb
+    };
+    (convert(r), convert(g), convert(b))
+}
+
+/// Multiply a texel by an unpacked lighting color, in linear light if `linear`.
+///
+/// Keeps the texel's alpha so translucent texels can be blended.
+#[inline]
+fn modulate_texel(texel: u32, (light_r, light_g, light_b): (f32, f32, f32), linear: bool) -> u32 {
+    let alpha = (texel >> 24) as f32 / 255.0;
+    if linear {
+        let (r, g, b) = unpack_color_linear(texel);
+        pack_color_linear(r * light_r, g * light_g, b * light_b, alpha)
+    } else {
+        let (r, g, b) = unpack_color(texel);
+        pack_color(r * light_r, g * light_g, b * light_b, alpha)
+    }
 }
 
 impl PixelShader for GouraudShader {
@@ -95,7 +135,11 @@ impl PixelShader for GouraudShader {
         let b = lambda[0] * self.colors[0].2
             + lambda[1] * self.colors[1].2
             + lambda[2] * self.colors[2].2;
-        pack_color(r, g, b, 1.0)
+        if self.linear {
+            pack_color_linear(r, g, b, 1.0)
+        } else {
+            pack_color(r, g, b, 1.0)
+        }
     }
 }
 
@@ -143,6 +187,8 @@ pub struct TextureModulateShader<'a> {
     uvs: [Vec2; 3],
     /// Unpacked vertex colors representing lighting intensity
     colors: [(f32, f32, f32); 3],
+    /// Whether `colors` are linear light, multiplied with linearized texels
+    linear: bool,
 }
 
 impl<'a> TextureModulateShader<'a> {
@@ -155,7 +201,17 @@ impl<'a> TextureModulateShader<'a> {
                 unpack_color(vertex_colors[1]),
                 unpack_color(vertex_colors[2]),
             ],
+            linear: false,
+        }
+    }
+
+    /// Interpolate the lighting and multiply it into the texel in linear light.
+    pub fn with_gamma_correct(mut self, gamma_correct: bool) -> Self {
+        if gamma_correct != self.linear {
+            self.colors = self.colors.map(|color| reencode(color, gamma_correct));
+            self.linear = gamma_correct;
         }
+        self
     }
 
     /// Interpolate UV coordinates using barycentric weights
@@ -187,9 +243,7 @@ impl PixelShader for TextureModulateShader<'_> {
     fn shade(&self, lambda: [f32; 3]) -> u32 {
         let (u, v) = self.interpolate_uv(lambda);
         let tex_color = self.texture.sample(u, v);
-        let (light_r, light_g, light_b) = self.interpolate_lighting(lambda);
-        let (tex_r, tex_g, tex_b) = unpack_color(tex_color);
-        pack_color(tex_r * light_r, tex_g * light_g, tex_b * light_b, 1.0)
+        modulate_texel(tex_color, self.interpolate_lighting(lambda), self.linear)
     }
 }
 
@@ -251,6 +305,7 @@ pub struct PerspectiveCorrectTextureModulateShader<'a> {
     v_over_w: [f32; 3],
     inv_w: [f32; 3],
     colors: [(f32, f32, f32); 3],
+    linear: bool,
 }
 
 impl<'a> PerspectiveCorrectTextureModulateShader<'a> {
@@ -272,8 +327,18 @@ impl<'a> PerspectiveCorrectTextureModulateShader<'a> {
                 unpack_color(vertex_colors[1]),
                 unpack_color(vertex_colors[2]),
             ],
+            linear: false,
         }
     }
+
+    /// Interpolate the lighting and multiply it into the texel in linear light.
+    pub fn with_gamma_correct(mut self, gamma_correct: bool) -> Self {
+        if gamma_correct != self.linear {
+            self.colors = self.colors.map(|color| reencode(color, gamma_correct));
+            self.linear = gamma_correct;
+        }
+        self
+    }
 }
 
 impl PixelShader for PerspectiveCorrectTextureModulateShader<'_> {
@@ -308,8 +373,401 @@ impl PixelShader for PerspectiveCorrectTextureModulateShader<'_> {
                 + lambda[2] * self.colors[2].2,
         );
 
-        // Modulate
-        let (tex_r, tex_g, tex_b) = unpack_color(tex_color);
-        pack_color(tex_r * light_r, tex_g * light_g, tex_b * light_b, 1.0)
+        modulate_texel(tex_color, (light_r, light_g, light_b), self.linear)
+    }
+}
+
+/// Light source used by [`PhongShader`] for per-pixel lighting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PhongLight {
+    /// Parallel rays travelling in the given (normalized) direction.
+    Directional(Vec3),
+    /// Rays radiating from a world-space position.
+    Point(Vec3),
+}
+
+/// Material coefficients for [`PhongShader`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct PhongParams {
+    /// Minimum brightness applied regardless of light direction.
+    pub ambient: f32,
+    /// Multiplier for the Lambertian diffuse term.
+    pub diffuse: f32,
+    /// Multiplier for the specular highlight.
+    pub specular: f32,
+    /// Specular exponent. Higher values give smaller, sharper highlights.
+    pub shininess: f32,
+}
+
+impl Default for PhongParams {
+    fn default() -> Self {
+        Self {
+            ambient: 0.1,
+            diffuse: 1.0,
+            specular: 0.5,
+            shininess: 32.0,
+        }
+    }
+}
+
+/// Phong shader - per-pixel ambient + diffuse + specular lighting.
+///
+/// Unlike Gouraud shading, which lights each vertex and interpolates the colors,
+/// this interpolates the world-space position and normal across the triangle and
+/// evaluates the lighting model at every pixel:
+///
+/// ```text
+/// color = base * (ambient + diffuse * max(N·L, 0)) + specular * max(R·V, 0)^shininess
+/// ```
+///
+/// where `L` points toward the light, `V` toward the viewer, and `R` is the light
+/// direction reflected about `N`. The specular highlight is white.
+///
+/// Positions and normals are interpolated affinely in screen space, like the
+/// Gouraud colors.
+pub struct PhongShader {
+    /// Unpacked base color in [0.0, 1.0] range
+    base_color: (f32, f32, f32),
+    positions: [Vec3; 3],
+    normals: [Vec3; 3],
+    light: PhongLight,
+    view_position: Vec3,
+    params: PhongParams,
+    /// Whether `base_color` is linear light and lighting is evaluated linearly
+    linear: bool,
+}
+
+impl PhongShader {
+    /// Create a Phong shader.
+    ///
+    /// # Arguments
+    /// * `base_color` - Surface color (ARGB8888)
+    /// * `positions` - World-space position of each vertex
+    /// * `normals` - World-space normal of each vertex
+    /// * `light` - Light source illuminating the surface
+    /// * `view_position` - World-space camera position
+    /// * `params` - Material coefficients
+    pub fn new(
+        base_color: u32,
+        positions: [Vec3; 3],
+        normals: [Vec3; 3],
+        light: PhongLight,
+        view_position: Vec3,
+        params: PhongParams,
+    ) -> Self {
+        Self {
+            base_color: unpack_color(base_color),
+            positions,
+            normals,
+            light,
+            view_position,
+            params,
+            linear: false,
+        }
+    }
+
+    /// Evaluate the lighting model in linear light instead of on sRGB values.
+    ///
+    /// Diffuse falloff and highlights then look perceptually even, at the cost
+    /// of a power function per channel per pixel.
+    pub fn with_gamma_correct(mut self, gamma_correct: bool) -> Self {
+        if gamma_correct != self.linear {
+            self.base_color = reencode(self.base_color, gamma_correct);
+            self.linear = gamma_correct;
+        }
+        self
+    }
+
+    /// Interpolated world-space position and unit normal at the pixel.
+    #[inline]
+    fn surface(&self, lambda: [f32; 3]) -> (Vec3, Vec3) {
+        let position = self.positions[0] * lambda[0]
+            + self.positions[1] * lambda[1]
+            + self.positions[2] * lambda[2];
+        let normal = (self.normals[0] * lambda[0]
+            + self.normals[1] * lambda[1]
+            + self.normals[2] * lambda[2])
+            .normalize();
+        (position, normal)
+    }
+
+    /// Evaluate the lighting model for a surface point and unit normal.
+    #[inline]
+    fn light(&self, position: Vec3, normal: Vec3) -> u32 {
+        let to_light = match self.light {
+            PhongLight::Directional(direction) => -direction,
+            PhongLight::Point(light_position) => light_position - position,
+        }
+        .normalize();
+
+        let diffuse = normal.dot(to_light).max(0.0);
+        let specular = if diffuse > 0.0 {
+            let to_view = (self.view_position - position).normalize();
+            let reflected = (-to_light).reflect(normal);
+            reflected.dot(to_view).max(0.0).powf(self.params.shininess)
+        } else {
+            0.0
+        };
+
+        let intensity = (self.params.ambient + self.params.diffuse * diffuse).min(1.0);
+        let highlight = self.params.specular * specular;
+        let pack = if self.linear {
+            pack_color_linear
+        } else {
+            pack_color
+        };
+        pack(
+            (self.base_color.0 * intensity + highlight).min(1.0),
+            (self.base_color.1 * intensity + highlight).min(1.0),
+            (self.base_color.2 * intensity + highlight).min(1.0),
+            1.0,
+        )
+    }
+}
+
+impl PixelShader for PhongShader {
+    #[inline]
+    fn shade(&self, lambda: [f32; 3]) -> u32 {
+        let (position, normal) = self.surface(lambda);
+        self.light(position, normal)
+    }
+}
+
+/// Normal-mapped shader - Phong lighting with per-pixel normals from a texture.
+///
+/// The normal map stores tangent-space normals as colors, each channel mapping
+/// [0, 1] to [-1, 1]: red along the tangent (increasing U), green along the
+/// bitangent (increasing V) and blue along the surface normal, so the flat
+/// color `(128, 128, 255)` leaves the surface normal unchanged. The interpolated
+/// TBN basis is re-orthogonalized per pixel:
+///
+/// ```text
+/// T = normalize(t - N * (N·t))
+/// B = sign * (N x T)
+/// n = normalize(T * r + B * g + N * b)
+/// ```
+///
+/// Triangles without usable tangents are lit with the unperturbed normal.
+/// UVs are interpolated affinely unless [`NormalMappedShader::with_perspective_correct`]
+/// supplies the vertex depths.
+pub struct NormalMappedShader<'a> {
+    phong: PhongShader,
+    normal_map: &'a Texture,
+    /// Pre-divided: [uv₀/w₀, uv₁/w₁, uv₂/w₂]
+    uv_over_w: [Vec2; 3],
+    /// Reciprocal depths: [1/w₀, 1/w₁, 1/w₂]
+    inv_w: [f32; 3],
+    tangents: [Vec4; 3],
+}
+
+impl<'a> NormalMappedShader<'a> {
+    /// Wrap a Phong shader so it lights with normals sampled from `normal_map`.
+    ///
+    /// # Arguments
+    /// * `phong` - Surface color, geometry, light and material coefficients
+    /// * `normal_map` - Tangent-space normal map
+    /// * `uvs` - Texture coordinates for each vertex
+    /// * `tangents` - World-space tangent of each vertex, bitangent sign in `w`
+    pub fn new(
+        phong: PhongShader,
+        normal_map: &'a Texture,
+        uvs: [Vec2; 3],
+        tangents: [Vec4; 3],
+    ) -> Self {
+        Self {
+            phong,
+            normal_map,
+            uv_over_w: uvs,
+            inv_w: [1.0; 3],
+            tangents,
+        }
+    }
+
+    /// Interpolate UVs perspective-correctly, like [`PerspectiveCorrectTextureShader`].
+    ///
+    /// # Arguments
+    /// * `points` - Screen-space vertices (W stored in z component)
+    pub fn with_perspective_correct(mut self, points: [Vec3; 3]) -> Self {
+        let uvs: [Vec2; 3] = std::array::from_fn(|i| self.uv_over_w[i] * (1.0 / self.inv_w[i]));
+        self.inv_w = points.map(|p| 1.0 / p.z);
+        self.uv_over_w = std::array::from_fn(|i| uvs[i] * self.inv_w[i]);
+        self
+    }
+}
+
+impl PixelShader for NormalMappedShader<'_> {
+    #[inline]
+    fn shade(&self, lambda: [f32; 3]) -> u32 {
+        let (position, normal) = self.phong.surface(lambda);
+        let tangent = (self.tangents[0] * lambda[0]
+            + self.tangents[1] * lambda[1]
+            + self.tangents[2] * lambda[2])
+            .xyz();
+        let tangent = (tangent - normal * normal.dot(tangent)).normalize();
+        if tangent == Vec3::ZERO {
+            return self.phong.light(position, normal);
+        }
+        let bitangent = normal.cross(tangent) * self.tangents[0].w;
+
+        let uv_over_w = self.uv_over_w[0] * lambda[0]
+            + self.uv_over_w[1] * lambda[1]
+            + self.uv_over_w[2] * lambda[2];
+        let inv_w =
+            lambda[0] * self.inv_w[0] + lambda[1] * self.inv_w[1] + lambda[2] * self.inv_w[2];
+        let uv = uv_over_w * (1.0 / inv_w);
+        let (r, g, b) = unpack_color(self.normal_map.sample(uv.x, uv.y));
+        let mapped =
+            (tangent * (2.0 * r - 1.0) + bitangent * (2.0 * g - 1.0) + normal * (2.0 * b - 1.0))
+                .normalize();
+        self.phong.light(position, mapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Quad-sized triangle in the z = 0 plane facing -Z (toward a camera at negative z).
+    fn facing_phong(view_position: Vec3) -> PhongShader {
+        PhongShader::new(
+            0xFF404040,
+            [
+                Vec3::new(-1.0, -1.0, 0.0),
+                Vec3::new(0.0, 2.0, 0.0),
+                Vec3::new(1.0, -1.0, 0.0),
+            ],
+            [Vec3::new(0.0, 0.0, -1.0); 3],
+            PhongLight::Directional(Vec3::new(0.0, 0.0, 1.0)),
+            view_position,
+            PhongParams::default(),
+        )
+    }
+
+    #[test]
+    fn gamma_correct_gouraud_brightens_midtones() {
+        let colors = [0xFF000000, 0xFFFFFFFF, 0xFF000000];
+        let midpoint = [0.5, 0.5, 0.0];
+        assert_eq!(GouraudShader::new(colors).shade(midpoint), 0xFF808080);
+        assert_eq!(
+            GouraudShader::new(colors)
+                .with_gamma_correct(true)
+                .shade(midpoint),
+            0xFFBCBCBC
+        );
+        // Vertices themselves are reproduced exactly
+        let shader = GouraudShader::new(colors).with_gamma_correct(true);
+        assert_eq!(shader.shade([0.0, 1.0, 0.0]), 0xFFFFFFFF);
+    }
+
+    #[test]
+    fn gamma_correct_phong_lights_in_linear_space() {
+        // Light 60 degrees off the normal, so the diffuse term is one half
+        let shader = PhongShader::new(
+            0xFFFFFFFF,
+            [
+                Vec3::new(-1.0, -1.0, 0.0),
+                Vec3::new(0.0, 2.0, 0.0),
+                Vec3::new(1.0, -1.0, 0.0),
+            ],
+            [Vec3::new(0.0, 0.0, -1.0); 3],
+            PhongLight::Directional(Vec3::new(-(3.0f32.sqrt()) / 2.0, 0.0, 0.5)),
+            Vec3::new(0.0, 0.0, -5.0),
+            PhongParams {
+                ambient: 0.0,
+                specular: 0.0,
+                ..PhongParams::default()
+            },
+        );
+        let centroid = [1.0 / 3.0; 3];
+        assert_eq!(shader.shade(centroid), 0xFF808080);
+        assert_eq!(shader.with_gamma_correct(true).shade(centroid), 0xFFBCBCBC);
+    }
+
+    #[test]
+    fn phong_specular_peaks_when_reflection_meets_view() {
+        // At the centroid the light reflects straight back toward a camera on the -Z axis
+        let centroid = [1.0 / 3.0; 3];
+        let aligned = facing_phong(Vec3::new(0.0, 0.0, -5.0)).shade(centroid);
+        let off_axis = facing_phong(Vec3::new(5.0, 0.0, -1.0)).shade(centroid);
+
+        let (r, _, _) = unpack_color(aligned);
+        let (r_off, _, _) = unpack_color(off_axis);
+        // Base 0x40 (0.25) fully lit plus 0.5 specular
+        assert!((r - 0.75).abs() < 0.01, "aligned highlight was {r}");
+        assert!(r_off < 0.3, "off-axis highlight was {r_off}");
+    }
+
+    #[test]
+    fn normal_map_varies_lighting_across_a_flat_surface() {
+        // Left texel tilts the normal along -U, right texel is the flat normal
+        let normal_map =
+            Texture::from_rgba(&[38, 128, 218, 255, 128, 128, 255, 255], 2, 1).unwrap();
+        let uvs = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.5, 1.0),
+            Vec2::new(1.0, 0.0),
+        ];
+        // The facing triangle spans +X as U grows
+        let tangents = [Vec4::new(1.0, 0.0, 0.0, 1.0); 3];
+        // Diffuse only, so the comparison isn't dominated by a narrow highlight
+        let diffuse_phong = || {
+            let mut phong = facing_phong(Vec3::new(0.0, 0.0, -5.0));
+            phong.params.specular = 0.0;
+            phong
+        };
+        let shader = NormalMappedShader::new(diffuse_phong(), &normal_map, uvs, tangents);
+
+        let left = [0.8, 0.1, 0.1]; // u = 0.15
+        let right = [0.1, 0.1, 0.8]; // u = 0.85
+        let phong = diffuse_phong();
+        let (tilted, _, _) = unpack_color(shader.shade(left));
+        let (flat, _, _) = unpack_color(shader.shade(right));
+        let (unmapped, _, _) = unpack_color(phong.shade(right));
+        // 128 decodes to just above zero, so the flat texel is only nearly unperturbed
+        assert!(
+            (flat - unmapped).abs() < 0.01,
+            "flat {flat} unmapped {unmapped}"
+        );
+        assert!(tilted + 0.03 < flat, "tilted {tilted} flat {flat}");
+    }
+
+    #[test]
+    fn normal_map_uvs_are_perspective_correct() {
+        // Left texel is the flat normal, right texel tilts it along -U
+        let normal_map =
+            Texture::from_rgba(&[128, 128, 255, 255, 38, 128, 218, 255], 2, 1).unwrap();
+        let flat_map = Texture::solid(1, 0xFF8080FF);
+        let uvs = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        let tangents = [Vec4::new(1.0, 0.0, 0.0, 1.0); 3];
+        // The second vertex recedes to four times the depth of the others
+        let points = [
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(10.0, 0.0, 4.0),
+            Vec3::new(0.0, 10.0, 1.0),
+        ];
+        let phong = || facing_phong(Vec3::new(0.0, 0.0, -5.0));
+
+        // Screen-space u is 0.6, but the receding half is compressed: u ≈ 0.27
+        let lambda = [0.4, 0.6, 0.0];
+        let flat = NormalMappedShader::new(phong(), &flat_map, uvs, tangents).shade(lambda);
+        let affine = NormalMappedShader::new(phong(), &normal_map, uvs, tangents);
+        assert_ne!(affine.shade(lambda), flat);
+        let correct = affine.with_perspective_correct(points);
+        assert_eq!(correct.shade(lambda), flat);
+    }
+
+    #[test]
+    fn phong_point_light_behind_surface_is_ambient_only() {
+        let mut shader = facing_phong(Vec3::new(0.0, 0.0, -5.0));
+        shader.light = PhongLight::Point(Vec3::new(0.0, 0.0, 5.0));
+        let (r, g, b) = unpack_color(shader.shade([1.0 / 3.0; 3]));
+        let ambient = 0.25 * PhongParams::default().ambient;
+        assert!((r - ambient).abs() < 0.01);
+        assert_eq!((r, g), (g, b));
     }
 }
@@ -2,56 +2,268 @@
 //!
 //! Provides the [`Renderer`] struct which owns the color buffer and implements
 //! basic drawing operations like lines, rectangles, and wireframes.
+//!
+//! # Supersampling
+//!
+//! With a supersampling factor `n > 1` everything is drawn into buffers `n`
+//! times wider and taller than the output. [`Renderer::resolve`] then averages
+//! each `n x n` block of samples into one output pixel (a box filter), which
+//! smooths triangle and line edges at the cost of `n^2` the fill work and memory.
+//!
+//! # Dirty Rectangles
+//!
+//! The renderer tracks two regions, both as bounding rectangles:
+//! - The region drawn since the last clear. [`Renderer::clear_touched`] clears
+//!   just that, instead of the whole buffer, for mostly static scenes.
+//! - The region changed since the last [`Renderer::take_dirty_rect`], so a
+//!   window can present only what changed.
+//!
+//! Renderer primitives (lines, rects, discs, text) record their bounds once per
+//! call; the per-pixel setters record nothing. Rasterizers write through
+//! [`Renderer::as_framebuffer`], so callers record those with
+//! [`Renderer::mark_dirty`]. Background drawn with [`Renderer::draw_grid_in`]
+//! counts as changed but not as drawn, since it is redrawn after every clear.
+//!
+//! # Double Buffering
+//!
+//! Drawing and [`Renderer::resolve`] produce the back buffer. [`Renderer::as_bytes`]
+//! exposes the front buffer, which only changes on [`Renderer::swap_buffers`], so a
+//! presenter never sees a half-drawn frame. Capture helpers (`to_rgba8`,
+//! `frame_hash`, `write_png`, `write_ppm`) read the back buffer, i.e. the frame
+//! just drawn.
 
-use super::framebuffer::FrameBuffer;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::font::{self, GLYPH_SIZE};
+use super::framebuffer::{FrameBuffer, Rect};
+use super::image_view::ImageView;
+use super::postprocess::PostProcess;
 use super::rasterizer::Triangle;
 use crate::colors;
+use crate::math::vec3::Vec3;
+
+/// Default 1/w offset added to line depths (see [`Renderer::set_wireframe_depth_bias`]).
+pub const DEFAULT_WIREFRAME_DEPTH_BIAS: f32 = 0.0001;
 
 pub struct Renderer {
     color_buffer: Vec<u32>,
     depth_buffer: Vec<f32>,
     width: u32,
     height: u32,
+    supersampling: u32,
+    resolved: Vec<u32>, // Downsampled output, only used when supersampling > 1
+    front: Vec<u8>,     // Last swapped-in output as native-endian ARGB8888 bytes
+    wireframe_depth_bias: f32,
+    touched: Option<Rect>, // Drawn since the last clear, in buffer pixels
+    dirty: Option<Rect>,   // Changed since the last take_dirty_rect, in buffer pixels
 }
 
 impl Renderer {
+    /// Create a renderer with a `width` x `height` output; zero dimensions are treated as `1`.
     pub fn new(width: u32, height: u32) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
         let size = (width * height) as usize;
         Self {
             color_buffer: vec![colors::BACKGROUND; size],
             depth_buffer: vec![0.0; size], // 0.0 = infinitely far (1/w where w -> infinity)
             width,
             height,
+            supersampling: 1,
+            resolved: Vec::new(),
+            front: packed_bytes(&vec![colors::BACKGROUND; size]),
+            wireframe_depth_bias: DEFAULT_WIREFRAME_DEPTH_BIAS,
+            touched: Some(Rect::new(0, 0, width, height)),
+            dirty: Some(Rect::new(0, 0, width, height)),
         }
     }
 
+    /// Resize to an output of `width` x `height` pixels.
+    ///
+    /// The internal buffers are scaled up by the supersampling factor. Zero
+    /// dimensions, as reported for a minimized window, are treated as `1`.
     pub fn resize(&mut self, width: u32, height: u32) {
+        let (width, height) = (width.max(1), height.max(1));
+        let (width, height) = (width * self.supersampling, height * self.supersampling);
         let size = (width * height) as usize;
         self.color_buffer = vec![colors::BACKGROUND; size];
         self.depth_buffer = vec![0.0; size];
         self.width = width;
         self.height = height;
+        self.touched = Some(self.bounds());
+        self.dirty = Some(self.bounds());
+        let output_size = (self.output_width() * self.output_height()) as usize;
+        self.resolved = if self.supersampling > 1 {
+            vec![colors::BACKGROUND; output_size]
+        } else {
+            Vec::new()
+        };
+        self.front = packed_bytes(&vec![colors::BACKGROUND; output_size]);
+    }
+
+    /// Set the supersampling factor (samples per output pixel along each axis).
+    ///
+    /// `1` disables supersampling; `0` is treated as `1`. Reallocates the buffers
+    /// while keeping the output size.
+    pub fn set_supersampling(&mut self, factor: u32) {
+        let (width, height) = (self.output_width(), self.output_height());
+        self.supersampling = factor.max(1);
+        self.resize(width, height);
     }
 
+    pub fn supersampling(&self) -> u32 {
+        self.supersampling
+    }
+
+    /// Set the polygon offset added to line depths before the depth test.
+    ///
+    /// Lines over a filled triangle have the same depth as its surface, so without
+    /// an offset they z-fight with it. The bias is added to 1/w (larger = closer),
+    /// pulling lines toward the camera; 0.0 disables it.
+    pub fn set_wireframe_depth_bias(&mut self, bias: f32) {
+        self.wireframe_depth_bias = bias;
+    }
+
+    pub fn wireframe_depth_bias(&self) -> f32 {
+        self.wireframe_depth_bias
+    }
+
+    /// Width of the buffers being drawn into (output width times the supersampling factor).
     pub fn width(&self) -> u32 {
         self.width
     }
 
+    /// Height of the buffers being drawn into (output height times the supersampling factor).
     pub fn height(&self) -> u32 {
         self.height
     }
 
+    /// Width of the presented image.
+    pub fn output_width(&self) -> u32 {
+        self.width / self.supersampling
+    }
+
+    /// Height of the presented image.
+    pub fn output_height(&self) -> u32 {
+        self.height / self.supersampling
+    }
+
+    /// Box-downsample the supersampled color buffer into the output image.
+    ///
+    /// Each output channel (alpha included) is the rounded mean of its `n x n`
+    /// samples. Does nothing without supersampling, where the color buffer is
+    /// the output.
+    pub fn resolve(&mut self) {
+        let factor = self.supersampling as usize;
+        if factor == 1 {
+            return;
+        }
+        let width = self.width as usize;
+        let output_width = self.output_width() as usize;
+        let samples = (factor * factor) as u32;
+
+        for (i, pixel) in self.resolved.iter_mut().enumerate() {
+            let (x, y) = ((i % output_width) * factor, (i / output_width) * factor);
+            let mut sums = [0u32; 4];
+            for row in self.color_buffer[y * width..].chunks(width).take(factor) {
+                for &sample in &row[x..x + factor] {
+                    for (sum, byte) in sums.iter_mut().zip(sample.to_be_bytes()) {
+                        *sum += byte as u32;
+                    }
+                }
+            }
+            *pixel = u32::from_be_bytes(sums.map(|sum| ((sum + samples / 2) / samples) as u8));
+        }
+    }
+
+    /// Publish the finished back buffer (resolved, when supersampling) as the front buffer.
+    ///
+    /// Copies rather than exchanging the buffers, so the back buffer keeps the frame
+    /// it holds and [`Renderer::clear_touched`] can still clear incrementally.
+    pub fn swap_buffers(&mut self) {
+        let output = if self.supersampling > 1 {
+            &self.resolved
+        } else {
+            &self.color_buffer
+        };
+        for (bytes, pixel) in self.front.chunks_exact_mut(4).zip(output) {
+            bytes.copy_from_slice(&pixel.to_ne_bytes());
+        }
+    }
+
+    /// The back buffer's output pixels: the resolved image when supersampling, else
+    /// the color buffer.
+    fn output(&self) -> &[u32] {
+        if self.supersampling > 1 {
+            &self.resolved
+        } else {
+            &self.color_buffer
+        }
+    }
+
     pub fn clear(&mut self, color: u32) {
         self.color_buffer.fill(color);
+        self.touched = None;
+        self.dirty = Some(self.bounds());
+    }
+
+    /// Clear color and depth only where something was drawn since the last clear.
+    ///
+    /// Returns the cleared region, so the caller can redraw background there.
+    /// Everything outside it must still hold `color` and an empty depth.
+    pub fn clear_touched(&mut self, color: u32) -> Option<Rect> {
+        let region = self.touched.take()?;
+        let width = self.width as usize;
+        for y in region.y..region.bottom() {
+            let start = y as usize * width + region.x as usize;
+            let end = start + region.width as usize;
+            self.color_buffer[start..end].fill(color);
+            self.depth_buffer[start..end].fill(0.0);
+        }
+        self.dirty = Some(self.dirty.map_or(region, |dirty| dirty.union(region)));
+        Some(region)
+    }
+
+    /// Record that `rect` (in buffer pixels) was drawn, e.g. by a rasterizer.
+    pub fn mark_dirty(&mut self, rect: Rect) {
+        if let Some(rect) = rect.intersect(self.bounds()) {
+            self.touched = Some(self.touched.map_or(rect, |touched| touched.union(rect)));
+            self.dirty = Some(self.dirty.map_or(rect, |dirty| dirty.union(rect)));
+        }
+    }
+
+    /// Return the region changed since the last call, in output pixels, and reset it.
+    pub fn take_dirty_rect(&mut self) -> Option<Rect> {
+        let dirty = self.dirty.take()?;
+        let factor = self.supersampling as i32;
+        let (left, top) = (dirty.x / factor, dirty.y / factor);
+        let right = (dirty.right() + factor - 1) / factor;
+        let bottom = (dirty.bottom() + factor - 1) / factor;
+        Some(Rect::new(
+            left,
+            top,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        ))
+    }
+
+    /// The whole buffer, in buffer pixels.
+    fn bounds(&self) -> Rect {
+        Rect::new(0, 0, self.width, self.height)
     }
 
     #[inline]
     /// Clear the depth buffer to prepare for a new frame.
-    /// Sets all depths to 0.0 (infinitely far, since we store 1/w).
-    pub fn clear_depth(&mut self) {
-        self.depth_buffer.fill(0.0);
+    /// Pass 0.0 to reset every pixel to infinitely far, since we store 1/w.
+    pub fn clear_depth(&mut self, value: f32) {
+        self.depth_buffer.fill(value);
     }
 
+    /// Set a pixel at (x, y), ignoring out-of-bounds coordinates.
+    ///
+    /// Not recorded as dirty: callers drawing a whole primitive mark its bounds
+    /// once with [`Renderer::mark_dirty`].
     #[inline]
     pub fn set_pixel(&mut self, x: i32, y: i32, color: u32) {
         if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
@@ -64,7 +276,8 @@ impl Renderer {
     ///
     /// The pixel is only written if the depth value is greater than the existing
     /// depth at that location (closer to camera, since we store 1/w).
-    /// Silently ignores out-of-bounds coordinates.
+    /// Silently ignores out-of-bounds coordinates. Like [`Renderer::set_pixel`],
+    /// the write is not recorded as dirty.
     ///
     /// # Arguments
     /// * `x`, `y` - Pixel coordinates
@@ -83,17 +296,32 @@ impl Renderer {
     }
 
     pub fn draw_grid(&mut self, spacing: i32, color: u32) {
-        for y in 0..self.height as i32 {
-            for x in 0..self.width as i32 {
+        self.draw_grid_in(spacing, color, self.bounds());
+    }
+
+    /// Draw the grid lines that fall inside `region`, as background.
+    pub fn draw_grid_in(&mut self, spacing: i32, color: u32, region: Rect) {
+        let Some(region) = region.intersect(self.bounds()) else {
+            return;
+        };
+        for y in region.y..region.bottom() {
+            for x in region.x..region.right() {
                 if x % spacing == 0 || y % spacing == 0 {
-                    self.set_pixel(x, y, color);
+                    self.color_buffer[(y as u32 * self.width + x as u32) as usize] = color;
                 }
             }
         }
+        self.dirty = Some(self.dirty.map_or(region, |dirty| dirty.union(region)));
     }
 
     #[inline]
     pub fn draw_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32) {
+        self.mark_dirty(span_rect(x, y, x + width - 1, y + height - 1));
+        self.fill_rect(x, y, width, height, color);
+    }
+
+    /// Fill a rectangle, clipped to the buffer, without marking it dirty.
+    fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: u32) {
         for dy in 0..height {
             for dx in 0..width {
                 self.set_pixel(x + dx, y + dy, color);
@@ -101,6 +329,99 @@ impl Renderer {
         }
     }
 
+    /// Fill a disc of `radius` pixels centered on (cx, cy).
+    ///
+    /// A pixel is covered when its center lies within the radius, so a disc spans
+    /// about `2 * radius` pixels across. Pixels outside the buffer are clipped.
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, color: u32) {
+        let top = (cy - radius).floor() as i32;
+        let bottom = (cy + radius).ceil() as i32;
+        let (left, right) = ((cx - radius).floor() as i32, (cx + radius).ceil() as i32);
+        self.mark_dirty(span_rect(left, top, right, bottom));
+        for y in top..=bottom {
+            let dy = y as f32 + 0.5 - cy;
+            let half_width = radius * radius - dy * dy;
+            if half_width < 0.0 {
+                continue;
+            }
+            // Pixel centers x + 0.5 within cx +- half_width
+            let half_width = half_width.sqrt();
+            let left = (cx - half_width - 0.5).ceil() as i32;
+            let right = (cx + half_width - 0.5).floor() as i32;
+            for x in left..=right {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Draw `text` with its top-left corner at (x, y) using the built-in 8x8 font.
+    ///
+    /// `\n` starts a new line below the first character. Glyph pixels outside the
+    /// buffer are clipped; only set bits are drawn, so the background shows through.
+    #[allow(dead_code)]
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str, color: u32) {
+        self.draw_text_scaled(x, y, text, color, 1);
+    }
+
+    /// Like [`Renderer::draw_text`], with each font pixel drawn as a `scale` x `scale` block.
+    pub fn draw_text_scaled(&mut self, x: i32, y: i32, text: &str, color: u32, scale: i32) {
+        let advance = GLYPH_SIZE * scale;
+        let columns = text.split('\n').map(|line| line.chars().count()).max();
+        let rows = text.split('\n').count();
+        self.mark_dirty(span_rect(
+            x,
+            y,
+            x + columns.unwrap_or(0) as i32 * advance - 1,
+            y + rows as i32 * advance - 1,
+        ));
+        for (line_index, line) in text.split('\n').enumerate() {
+            let line_y = y + line_index as i32 * advance;
+            for (char_index, c) in line.chars().enumerate() {
+                let glyph_x = x + char_index as i32 * advance;
+                for (row, bits) in font::glyph(c).iter().enumerate() {
+                    for col in 0..GLYPH_SIZE {
+                        if bits & (1 << col) != 0 {
+                            let px = glyph_x + col * scale;
+                            let py = line_y + row as i32 * scale;
+                            // fill_rect clips via set_pixel
+                            self.fill_rect(px, py, scale, scale, color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draw a triangle's edges with anti-aliased [`Renderer::draw_line_aa`] lines.
+    pub fn draw_triangle_wireframe_aa(&mut self, triangle: &Triangle, color: u32) {
+        let [p0, p1, p2] = triangle.points;
+        self.draw_line_aa(p0, p1, color);
+        self.draw_line_aa(p1, p2, color);
+        self.draw_line_aa(p2, p0, color);
+    }
+
+    /// Draw a triangle's edges as depth-tested lines `width` pixels wide.
+    ///
+    /// Each edge is a capsule, so the rounded ends overlap at the corners and
+    /// form clean joins.
+    pub fn draw_triangle_wireframe_thick(&mut self, triangle: &Triangle, width: f32, color: u32) {
+        let [p0, p1, p2] = triangle.points;
+        for (a, b) in [(p0, p1), (p1, p2), (p2, p0)] {
+            self.draw_line_thick_with_depth(a, b, width, color);
+        }
+    }
+
+    /// Draw a depth-tested capsule `width` pixels wide between two screen points.
+    ///
+    /// Like the other lines, `z` holds clip-space W and the wireframe depth bias applies.
+    pub fn draw_line_thick_with_depth(&mut self, a: Vec3, b: Vec3, width: f32, color: u32) {
+        let inv_w0 = 1.0 / a.z + self.wireframe_depth_bias;
+        let inv_w1 = 1.0 / b.z + self.wireframe_depth_bias;
+        self.fill_capsule(a.x, a.y, b.x, b.y, width, |renderer, x, y, t| {
+            renderer.set_pixel_with_depth(x, y, inv_w0 + t * (inv_w1 - inv_w0), color);
+        });
+    }
+
     pub fn draw_triangle_wireframe(&mut self, triangle: &Triangle, color: u32) {
         let [p0, p1, p2] = triangle.points;
 
@@ -160,22 +481,23 @@ impl Renderer {
         // These represent how far we need to travel horizontally and vertically.
         let dx = (x1 - x0).abs();
         let dy = (y1 - y0).abs();
+        self.mark_dirty(span_rect(x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)));
 
         // Depth bias so wireframes render slightly in front of filled triangles
-        const WIREFRAME_DEPTH_BIAS: f32 = 0.0001;
+        let bias = self.wireframe_depth_bias;
 
         // Total number of steps (max of dx, dy)
         let steps = dx.max(dy);
         if steps == 0 {
             // Single pixel line
-            let inv_depth = 1.0 / w0 + WIREFRAME_DEPTH_BIAS;
+            let inv_depth = 1.0 / w0 + bias;
             self.set_pixel_with_depth(x0, y0, inv_depth, color);
             return;
         }
 
         // Precompute 1/w for depth interpolation (linear in screen space)
-        let inv_w0 = 1.0 / w0 + WIREFRAME_DEPTH_BIAS;
-        let inv_w1 = 1.0 / w1 + WIREFRAME_DEPTH_BIAS;
+        let inv_w0 = 1.0 / w0 + bias;
+        let inv_w1 = 1.0 / w1 + bias;
 
         // Determine the step direction for each axis.
         // +1 if we're moving in the positive direction, -1 if negative.
@@ -228,6 +550,160 @@ impl Renderer {
         }
     }
 
+    /// Draws a line `width` pixels wide, without depth testing.
+    ///
+    /// The line is a capsule: every pixel whose center is within `width / 2` of
+    /// the segment is filled. Measuring distance to the segment (rather than
+    /// stacking parallel one-pixel lines) gives the same thickness at every angle,
+    /// and the round caps make consecutive segments join cleanly.
+    #[allow(dead_code)]
+    pub fn draw_line_thick(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, width: f32, color: u32) {
+        self.fill_capsule(x0, y0, x1, y1, width, |renderer, x, y, _| {
+            renderer.set_pixel(x, y, color);
+        });
+    }
+
+    /// Calls `plot(self, x, y, t)` for every pixel whose center lies within
+    /// `width / 2` of segment (x0, y0) - (x1, y1).
+    ///
+    /// `t` in [0, 1] is where the pixel projects onto the segment, for
+    /// interpolating attributes such as depth.
+    fn fill_capsule(
+        &mut self,
+        x0: f32,
+        y0: f32,
+        x1: f32,
+        y1: f32,
+        width: f32,
+        mut plot: impl FnMut(&mut Self, i32, i32, f32),
+    ) {
+        let radius = width.max(1.0) * 0.5;
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let length_squared = dx * dx + dy * dy;
+
+        // Bounding box of the capsule, clipped to the buffer
+        let min_x = ((x0.min(x1) - radius).floor() as i32).max(0);
+        let max_x = ((x0.max(x1) + radius).ceil() as i32).min(self.width as i32 - 1);
+        let min_y = ((y0.min(y1) - radius).floor() as i32).max(0);
+        let max_y = ((y0.max(y1) + radius).ceil() as i32).min(self.height as i32 - 1);
+        self.mark_dirty(span_rect(min_x, min_y, max_x, max_y));
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+                // Closest point on the segment, as a fraction of its length
+                let t = if length_squared == 0.0 {
+                    0.0
+                } else {
+                    (((px - x0) * dx + (py - y0) * dy) / length_squared).clamp(0.0, 1.0)
+                };
+                let (cx, cy) = (x0 + t * dx - px, y0 + t * dy - py);
+                if cx * cx + cy * cy <= radius * radius {
+                    plot(self, x, y, t);
+                }
+            }
+        }
+    }
+
+    /// Draws an anti-aliased line using Xiaolin Wu's algorithm.
+    ///
+    /// Instead of picking one pixel per step like Bresenham, Wu's algorithm lights
+    /// the two pixels straddling the ideal line along the minor axis, splitting the
+    /// intensity by how close each pixel center is to the line:
+    ///
+    /// ```text
+    ///   line passes 30% of the way from pixel A's center to pixel B's:
+    ///   A gets 70% coverage, B gets 30%
+    /// ```
+    ///
+    /// Endpoints are additionally weighted by how much of their pixel the line
+    /// actually spans. Coverage scales the color's alpha and each pixel is
+    /// alpha-composited over what is already in the buffer.
+    ///
+    /// Points are in screen space with z holding clip-space W, like triangle
+    /// vertices. Pixels are depth tested (with the wireframe depth bias) but the
+    /// depth buffer is not written, as for any blended fragment.
+    pub fn draw_line_aa(&mut self, p0: Vec3, p1: Vec3, color: u32) {
+        let bias = self.wireframe_depth_bias;
+        // Writes go through a FrameBuffer, so record the line's box (plus the
+        // neighbouring row or column it blends into) up front
+        let left = p0.x.min(p1.x).floor() as i32 - 1;
+        let top = p0.y.min(p1.y).floor() as i32 - 1;
+        let right = p0.x.max(p1.x).ceil() as i32 + 1;
+        let bottom = p0.y.max(p1.y).ceil() as i32 + 1;
+        self.mark_dirty(span_rect(left, top, right, bottom));
+        let mut fb = self.as_framebuffer();
+
+        // Pixel centers sit at +0.5; shift so they land on integer coordinates
+        let (mut x0, mut y0, mut x1, mut y1) = (p0.x - 0.5, p0.y - 0.5, p1.x - 0.5, p1.y - 0.5);
+        let (mut inv_w0, mut inv_w1) = (1.0 / p0.z + bias, 1.0 / p1.z + bias);
+
+        // Walk the major axis: for steep lines swap x and y, and always go left to right
+        let steep = (y1 - y0).abs() > (x1 - x0).abs();
+        if steep {
+            std::mem::swap(&mut x0, &mut y0);
+            std::mem::swap(&mut x1, &mut y1);
+        }
+        if x0 > x1 {
+            std::mem::swap(&mut x0, &mut x1);
+            std::mem::swap(&mut y0, &mut y1);
+            std::mem::swap(&mut inv_w0, &mut inv_w1);
+        }
+
+        let dx = x1 - x0;
+        let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+        let alpha = (color >> 24) as f32;
+
+        let mut plot = |major: i32, minor: i32, coverage: f32| {
+            if coverage <= 0.0 {
+                return;
+            }
+            let (x, y) = if steep {
+                (minor, major)
+            } else {
+                (major, minor)
+            };
+            let t = if dx == 0.0 {
+                0.0
+            } else {
+                ((major as f32 - x0) / dx).clamp(0.0, 1.0)
+            };
+            let inv_depth = inv_w0 + t * (inv_w1 - inv_w0);
+            if fb.depth_test(x, y, inv_depth) {
+                let a = (alpha * coverage.min(1.0)).round() as u32;
+                fb.blend_pixel(x, y, (color & 0x00FFFFFF) | (a << 24));
+            }
+        };
+
+        // Fractional part, and its complement
+        let fpart = |v: f32| v - v.floor();
+        let rfpart = |v: f32| 1.0 - fpart(v);
+
+        // First endpoint
+        let x_end = x0.round();
+        let y_end = y0 + gradient * (x_end - x0);
+        let x_gap = rfpart(x0 + 0.5);
+        let x_start = x_end as i32;
+        plot(x_start, y_end.floor() as i32, rfpart(y_end) * x_gap);
+        plot(x_start, y_end.floor() as i32 + 1, fpart(y_end) * x_gap);
+        let mut y = y_end + gradient;
+
+        // Second endpoint
+        let x_end = x1.round();
+        let y_end = y1 + gradient * (x_end - x1);
+        let x_gap = fpart(x1 + 0.5);
+        let x_stop = x_end as i32;
+        plot(x_stop, y_end.floor() as i32, rfpart(y_end) * x_gap);
+        plot(x_stop, y_end.floor() as i32 + 1, fpart(y_end) * x_gap);
+
+        // Span between the endpoints: split each column between the two nearest rows
+        for x in x_start + 1..x_stop {
+            plot(x, y.floor() as i32, rfpart(y));
+            plot(x, y.floor() as i32 + 1, fpart(y));
+            y += gradient;
+        }
+    }
+
     #[allow(dead_code)]
     pub fn draw_line_dda(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
         let dx = x1 - x0;
@@ -238,6 +714,7 @@ impl Renderer {
             side_length = dy.abs();
         }
 
+        self.mark_dirty(span_rect(x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)));
         let x_increment = dx as f32 / side_length as f32;
         let y_increment = dy as f32 / side_length as f32;
         let mut current_x = x0 as f32;
@@ -250,13 +727,88 @@ impl Renderer {
         }
     }
 
+    /// The front buffer's pixels as raw bytes, as of the last [`Renderer::swap_buffers`].
+    ///
+    /// Each pixel is its ARGB8888 `u32` in native byte order, the layout SDL's
+    /// `ARGB8888` textures expect (B, G, R, A in memory on little-endian machines).
+    /// For a platform-independent order use [`Renderer::to_rgba8`].
     pub fn as_bytes(&self) -> &[u8] {
-        unsafe {
-            std::slice::from_raw_parts(
-                self.color_buffer.as_ptr() as *const u8,
-                self.color_buffer.len() * 4,
-            )
+        &self.front
+    }
+
+    /// The back buffer's pixels at output resolution (resolved when supersampling).
+    pub fn image(&self) -> ImageView<'_> {
+        ImageView::new(self.output(), self.output_width(), self.output_height())
+    }
+
+    /// Copy the back buffer's pixels into tightly packed RGBA8 bytes, rows top to bottom.
+    ///
+    /// The color buffer holds ARGB8888 `u32`s, so each pixel's channels are
+    /// reordered rather than reinterpreting the bytes (which would be BGRA on
+    /// little-endian machines).
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.output()
+            .iter()
+            .flat_map(|&argb| {
+                let [a, r, g, b] = argb.to_be_bytes();
+                [r, g, b, a]
+            })
+            .collect()
+    }
+
+    /// 64-bit FNV-1a hash of the back buffer's pixels, for golden-image regression tests.
+    ///
+    /// Hashes the ARGB8888 `u32` buffer row by row, each pixel as little-endian
+    /// bytes, so the value is the same on every platform. Equal frames hash equal;
+    /// any changed pixel almost certainly changes the hash.
+    pub fn frame_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+        self.output()
+            .iter()
+            .flat_map(|pixel| pixel.to_le_bytes())
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// Write the back buffer's pixels to a PNG file.
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> Result<(), image::ImageError> {
+        image::save_buffer(
+            path,
+            &self.to_rgba8(),
+            self.output_width(),
+            self.output_height(),
+            image::ColorType::Rgba8,
+        )
+    }
+
+    /// Write the back buffer's pixels as a binary (P6) PPM file.
+    ///
+    /// PPM needs no codec: an ASCII header followed by raw RGB bytes in scanline
+    /// order. Alpha is dropped.
+    pub fn write_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.output_width(),
+            self.output_height()
+        )?;
+        for &argb in self.output() {
+            let [_, r, g, b] = argb.to_be_bytes();
+            writer.write_all(&[r, g, b])?;
         }
+        writer.flush()
+    }
+
+    /// Run a full-screen effect over the color buffer.
+    ///
+    /// Effects may change any pixel, background included, so the whole buffer
+    /// counts as drawn and changed.
+    pub fn apply_post_process(&mut self, effect: &dyn PostProcess) {
+        effect.process(&mut self.color_buffer, self.width, self.height);
+        self.mark_dirty(self.bounds());
     }
 
     /// Get a mutable FrameBuffer view into the color and depth buffers.
@@ -269,3 +821,249 @@ impl Renderer {
         )
     }
 }
+
+/// Native-endian bytes of ARGB8888 pixels, as presented by [`Renderer::as_bytes`].
+fn packed_bytes(pixels: &[u32]) -> Vec<u8> {
+    pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_ne_bytes())
+        .collect()
+}
+
+/// The rect covering columns `left..=right` and rows `top..=bottom`, empty if reversed.
+fn span_rect(left: i32, top: i32, right: i32, bottom: i32) -> Rect {
+    Rect::new(
+        left,
+        top,
+        (right - left + 1).max(0) as u32,
+        (bottom - top + 1).max(0) as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ppm_round_trips_pixels() {
+        let mut renderer = Renderer::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                let color = 0xFF000000 | ((x * 60) << 16) | ((y * 60) << 8) | 0x20;
+                renderer.set_pixel(x as i32, y as i32, color);
+            }
+        }
+
+        // Unique per process, so concurrent test runs don't share the file
+        let path =
+            std::env::temp_dir().join(format!("russsty_round_trip_{}.ppm", std::process::id()));
+        renderer.write_ppm(&path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&bytes[..header.len()], header);
+        let pixels = &bytes[header.len()..];
+        assert_eq!(pixels.len(), 4 * 4 * 3);
+        for (i, rgb) in pixels.chunks_exact(3).enumerate() {
+            let (x, y) = (i % 4, i / 4);
+            assert_eq!(rgb, [(x * 60) as u8, (y * 60) as u8, 0x20]);
+        }
+    }
+
+    #[test]
+    fn aa_line_blends_neighbours_by_coverage() {
+        let mut renderer = Renderer::new(32, 16);
+        renderer.clear(0xFF000000);
+        // Shallow slope: the line crosses between rows as it goes
+        renderer.draw_line_aa(
+            Vec3::new(2.5, 3.5, 1.0),
+            Vec3::new(29.5, 9.5, 1.0),
+            0xFFFFFFFF,
+        );
+
+        let red = |x: i32, y: i32| (renderer.color_buffer[(y * 32 + x) as usize] >> 16) & 0xFF;
+        let mut partial_columns = 0;
+        for x in 4..28 {
+            let column: Vec<u32> = (0..16).map(|y| red(x, y)).collect();
+            let lit: Vec<u32> = column.into_iter().filter(|&r| r > 0).collect();
+            // At most two rows per column, sharing the full intensity between them
+            assert!(!lit.is_empty() && lit.len() <= 2, "column {}: {:?}", x, lit);
+            let total: u32 = lit.iter().sum();
+            assert!((253..=257).contains(&total), "column {} total {}", x, total);
+            if lit.iter().any(|&r| r < 255) {
+                partial_columns += 1;
+            }
+        }
+        assert!(partial_columns > 10, "too few partially covered columns");
+    }
+
+    #[test]
+    fn text_draws_glyph_bitmap() {
+        let mut renderer = Renderer::new(24, 20);
+        renderer.clear(0);
+        renderer.draw_text(3, 2, "A\nA", 0xFFFFFFFF);
+
+        let glyph = font::glyph('A');
+        for (line_y, top) in [(0, 2), (1, 10)] {
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..8 {
+                    let lit = bits & (1 << col) != 0;
+                    let pixel = renderer.color_buffer[(top + row) * 24 + 3 + col];
+                    assert_eq!(pixel != 0, lit, "line {} row {} col {}", line_y, row, col);
+                }
+            }
+        }
+        let bits: u32 = glyph.iter().map(|row| row.count_ones()).sum();
+        assert_eq!(
+            renderer.color_buffer.iter().filter(|&&p| p != 0).count(),
+            2 * bits as usize
+        );
+    }
+
+    #[test]
+    fn text_is_clipped_at_buffer_edges() {
+        let mut renderer = Renderer::new(8, 8);
+        renderer.clear(0);
+        // Hanging off the top-left and bottom-right corners
+        renderer.draw_text(-5, -5, "A", 0xFFFFFFFF);
+        renderer.draw_text(6, 6, "W", 0xFFFFFFFF);
+
+        let mut expected = [0u32; 64];
+        for (c, origin) in [('A', -5), ('W', 6)] {
+            for (row, bits) in font::glyph(c).iter().enumerate() {
+                for col in 0..8 {
+                    let (x, y) = (origin + col, origin + row as i32);
+                    if bits & (1 << col) != 0 && (0..8).contains(&x) && (0..8).contains(&y) {
+                        expected[(y * 8 + x) as usize] = 0xFFFFFFFF;
+                    }
+                }
+            }
+        }
+        assert_eq!(renderer.color_buffer, expected);
+        assert!(expected.iter().any(|&p| p != 0));
+    }
+
+    /// Number of lit pixels in row `y` of a `size` x `size` renderer.
+    fn lit_in_row(renderer: &Renderer, y: u32, size: u32) -> usize {
+        (0..size)
+            .filter(|&x| renderer.color_buffer[(y * size + x) as usize] != 0)
+            .count()
+    }
+
+    #[test]
+    fn thick_lines_keep_their_width_at_any_angle() {
+        const SIZE: u32 = 48;
+        for width in [3.0, 4.0, 7.0] {
+            // Horizontal: the vertical cross-section is the width
+            let mut renderer = Renderer::new(SIZE, SIZE);
+            renderer.clear(0);
+            renderer.draw_line_thick(8.0, 24.0, 40.0, 24.0, width, 0xFFFFFFFF);
+            let column = (0..SIZE)
+                .filter(|&y| renderer.color_buffer[(y * SIZE + 24) as usize] != 0)
+                .count();
+            assert!(
+                (column as f32 - width).abs() <= 1.0,
+                "horizontal {}: {}",
+                width,
+                column
+            );
+
+            // 45 degrees: a row crosses the line at 45 degrees, so the
+            // perpendicular thickness is the row count / sqrt(2)
+            let mut renderer = Renderer::new(SIZE, SIZE);
+            renderer.clear(0);
+            renderer.draw_line_thick(8.0, 8.0, 40.0, 40.0, width, 0xFFFFFFFF);
+            let perpendicular = lit_in_row(&renderer, 24, SIZE) as f32 / std::f32::consts::SQRT_2;
+            assert!(
+                (perpendicular - width).abs() <= 1.0,
+                "diagonal {}: {}",
+                width,
+                perpendicular
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_averages_each_sample_block() {
+        let mut renderer = Renderer::new(2, 1);
+        renderer.set_supersampling(2);
+        assert_eq!((renderer.width(), renderer.height()), (4, 2));
+        renderer.clear(0xFF000000);
+        // Left output pixel: one white sample of four
+        renderer.set_pixel(0, 0, 0xFFFFFFFF);
+        // Right output pixel: fully covered
+        renderer.draw_rect(2, 0, 2, 2, 0xFF204060);
+        renderer.resolve();
+
+        assert_eq!(renderer.output(), [0xFF404040, 0xFF204060]);
+        renderer.swap_buffers();
+        assert_eq!(renderer.as_bytes().len(), 2 * 4);
+    }
+
+    #[test]
+    fn primitives_mark_their_bounds() {
+        let mut renderer = Renderer::new(64, 32);
+        renderer.take_dirty_rect();
+
+        renderer.draw_text(4, 2, "ab\nc", 0xFFFFFFFF);
+        let glyph = GLYPH_SIZE as u32;
+        assert_eq!(
+            renderer.take_dirty_rect(),
+            Some(Rect::new(4, 2, 2 * glyph, 2 * glyph))
+        );
+
+        renderer.draw_line_bresenham(10, 20, 1.0, 3, 5, 1.0, 0xFFFFFFFF);
+        assert_eq!(renderer.take_dirty_rect(), Some(Rect::new(3, 5, 8, 16)));
+
+        // Single pixels are left to the caller
+        renderer.set_pixel(0, 0, 0xFFFFFFFF);
+        assert_eq!(renderer.take_dirty_rect(), None);
+    }
+
+    #[test]
+    fn frame_hash_tracks_every_pixel() {
+        let render = || {
+            let mut renderer = Renderer::new(16, 8);
+            renderer.clear(0xFF202020);
+            renderer.draw_rect(2, 2, 5, 3, 0xFFFF0000);
+            renderer
+        };
+
+        let mut renderer = render();
+        assert_eq!(renderer.frame_hash(), render().frame_hash());
+
+        let before = renderer.frame_hash();
+        renderer.set_pixel(15, 7, 0xFF202021);
+        assert_ne!(renderer.frame_hash(), before);
+    }
+
+    #[test]
+    fn front_buffer_changes_only_on_swap() {
+        let mut renderer = Renderer::new(2, 1);
+        renderer.clear(0xFF000000);
+        renderer.swap_buffers();
+        let front = renderer.as_bytes().to_vec();
+
+        renderer.set_pixel(1, 0, 0xFFFFFFFF);
+        assert_eq!(renderer.as_bytes(), front);
+
+        renderer.swap_buffers();
+        assert_eq!(renderer.as_bytes()[4..], 0xFFFFFFFFu32.to_ne_bytes());
+        // The back buffer keeps its frame for incremental clearing
+        assert_eq!(renderer.output(), [0xFF000000, 0xFFFFFFFF]);
+    }
+
+    #[test]
+    fn pixel_byte_order_is_defined() {
+        let mut renderer = Renderer::new(1, 1);
+        renderer.set_pixel(0, 0, 0x80112233);
+        renderer.swap_buffers();
+
+        assert_eq!(renderer.to_rgba8(), [0x11, 0x22, 0x33, 0x80]);
+        assert_eq!(renderer.as_bytes(), 0x80112233u32.to_ne_bytes());
+        if cfg!(target_endian = "little") {
+            assert_eq!(renderer.as_bytes(), [0x33, 0x22, 0x11, 0x80]);
+        }
+    }
+}
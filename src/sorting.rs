@@ -2,6 +2,19 @@
 //!
 //! These are educational implementations. For production use,
 //! prefer the standard library's `sort_by` method.
+//!
+//! The generic cores sort any item by a key extracted with a closure, in
+//! descending order (largest key first). The `*_by_depth_descending` functions
+//! apply them to [`Triangle::avg_depth`] for back-to-front drawing.
+//!
+//! | Function | Stable | Time (average) |
+//! |----------|--------|----------------|
+//! | [`bubble_sort_by_key_descending`] | yes | O(n²) |
+//! | [`stable_sort_by_key_descending`] (merge sort) | yes | O(n log n) |
+//! | [`sort_by_key_descending`] (quick sort) | no | O(n log n) |
+//!
+//! Stability matters for depth sorting: coplanar faces share a depth, and an
+//! unstable sort can swap them from frame to frame, making them flicker.
 
 use crate::render::Triangle;
 
@@ -11,14 +24,7 @@ use crate::render::Triangle;
 /// Space complexity: O(1)
 #[allow(dead_code)]
 pub fn bubble_sort_by_depth_descending(triangles: &mut [Triangle]) {
-    let len = triangles.len();
-    for i in 0..len {
-        for j in 0..len - i - 1 {
-            if triangles[j].avg_depth < triangles[j + 1].avg_depth {
-                triangles.swap(j, j + 1);
-            }
-        }
-    }
+    bubble_sort_by_key_descending(triangles, |t| t.avg_depth);
 }
 
 /// Merge sort triangles by avg_depth in descending order (furthest first).
@@ -26,91 +32,189 @@ pub fn bubble_sort_by_depth_descending(triangles: &mut [Triangle]) {
 /// Time complexity: O(n log n)
 /// Space complexity: O(n)
 #[allow(dead_code)]
-pub fn merge_sort_by_depth_descending(triangles: &mut Vec<Triangle>) {
-    let len = triangles.len();
+pub fn merge_sort_by_depth_descending(triangles: &mut [Triangle]) {
+    stable_sort_by_key_descending(triangles, |t| t.avg_depth);
+}
+
+/// Quick sort triangles by avg_depth in descending order (furthest first).
+///
+/// Uses the Lomuto partition scheme with the last element as pivot.
+///
+/// Time complexity: O(n log n) average, O(n²) worst case
+/// Space complexity: O(log n) for recursion stack
+#[allow(dead_code)]
+pub fn quick_sort_by_depth_descending(triangles: &mut [Triangle]) {
+    sort_by_key_descending(triangles, |t| t.avg_depth);
+}
+
+/// Bubble sort `items` by `key` in descending order.
+///
+/// Only strictly smaller neighbours are swapped, so equal keys keep their order.
+#[allow(dead_code)]
+pub fn bubble_sort_by_key_descending<T, K: PartialOrd>(items: &mut [T], key: impl Fn(&T) -> K) {
+    let len = items.len();
+    for i in 0..len {
+        for j in 0..len.saturating_sub(i + 1) {
+            if key(&items[j]) < key(&items[j + 1]) {
+                items.swap(j, j + 1);
+            }
+        }
+    }
+}
+
+/// Merge sort `items` by `key` in descending order, keeping equal keys in input order.
+#[allow(dead_code)]
+pub fn stable_sort_by_key_descending<T: Clone, K: PartialOrd>(
+    items: &mut [T],
+    key: impl Fn(&T) -> K + Copy,
+) {
+    let len = items.len();
     if len <= 1 {
         return;
     }
 
     let mid = len / 2;
-    let mut left = triangles[..mid].to_vec();
-    let mut right = triangles[mid..].to_vec();
+    let mut left = items[..mid].to_vec();
+    let mut right = items[mid..].to_vec();
 
-    merge_sort_by_depth_descending(&mut left);
-    merge_sort_by_depth_descending(&mut right);
+    stable_sort_by_key_descending(&mut left, key);
+    stable_sort_by_key_descending(&mut right, key);
 
-    *triangles = merge_descending(left, right);
+    merge_descending(items, left, right, key);
 }
 
-/// Merge two sorted vectors into one, maintaining descending order by avg_depth.
-fn merge_descending(left: Vec<Triangle>, right: Vec<Triangle>) -> Vec<Triangle> {
-    let mut result = Vec::with_capacity(left.len() + right.len());
+/// Merge two sorted vectors into `out`, maintaining descending order by `key`.
+fn merge_descending<T, K: PartialOrd>(
+    out: &mut [T],
+    left: Vec<T>,
+    right: Vec<T>,
+    key: impl Fn(&T) -> K,
+) {
     let mut left_iter = left.into_iter().peekable();
     let mut right_iter = right.into_iter().peekable();
 
-    while left_iter.peek().is_some() && right_iter.peek().is_some() {
-        // Descending: take the larger depth first
-        if left_iter.peek().unwrap().avg_depth >= right_iter.peek().unwrap().avg_depth {
-            result.push(left_iter.next().unwrap());
+    for slot in out.iter_mut() {
+        // Descending: take the larger key first. On ties prefer the left run,
+        // which came first in the input - this is what makes the sort stable.
+        let take_left = match (left_iter.peek(), right_iter.peek()) {
+            (Some(l), Some(r)) => key(l) >= key(r),
+            (Some(_), None) => true,
+            _ => false,
+        };
+        let next = if take_left {
+            left_iter.next()
         } else {
-            result.push(right_iter.next().unwrap());
-        }
+            right_iter.next()
+        };
+        *slot = next.expect("runs cover the output");
     }
-
-    result.extend(left_iter);
-    result.extend(right_iter);
-    result
 }
 
-/// Quick sort triangles by avg_depth in descending order (furthest first).
-///
-/// Uses the Lomuto partition scheme with the last element as pivot.
+/// Quick sort `items` by `key` in descending order.
 ///
-/// Time complexity: O(n log n) average, O(n²) worst case
-/// Space complexity: O(log n) for recursion stack
+/// Not stable: items with equal keys may be reordered. Use
+/// [`stable_sort_by_key_descending`] when that matters.
 #[allow(dead_code)]
-pub fn quick_sort_by_depth_descending(triangles: &mut [Triangle]) {
-    if triangles.len() <= 1 {
+pub fn sort_by_key_descending<T, K: PartialOrd>(items: &mut [T], key: impl Fn(&T) -> K + Copy) {
+    if items.len() <= 1 {
         return;
     }
 
-    let pivot_idx = partition_descending(triangles);
+    let pivot_idx = partition_descending(items, key);
 
     // Sort left partition (elements before pivot)
     if pivot_idx > 0 {
-        quick_sort_by_depth_descending(&mut triangles[..pivot_idx]);
+        sort_by_key_descending(&mut items[..pivot_idx], key);
     }
     // Sort right partition (elements after pivot)
-    if pivot_idx + 1 < triangles.len() {
-        quick_sort_by_depth_descending(&mut triangles[pivot_idx + 1..]);
+    if pivot_idx + 1 < items.len() {
+        sort_by_key_descending(&mut items[pivot_idx + 1..], key);
     }
 }
 
 /// Partition the slice around a pivot (Lomuto scheme).
 ///
 /// After partitioning:
-/// - Elements with depth >= pivot are on the left
-/// - Elements with depth < pivot are on the right
+/// - Elements with key >= pivot are on the left
+/// - Elements with key < pivot are on the right
 /// - Pivot is in its final sorted position
 ///
 /// Returns the final index of the pivot element.
-fn partition_descending(triangles: &mut [Triangle]) -> usize {
-    let pivot_idx = triangles.len() - 1;
-    let pivot_depth = triangles[pivot_idx].avg_depth;
+fn partition_descending<T, K: PartialOrd>(items: &mut [T], key: impl Fn(&T) -> K) -> usize {
+    let pivot_idx = items.len() - 1;
+    let pivot_key = key(&items[pivot_idx]);
 
     // i tracks where the next "large" element should go
     let mut i = 0;
 
     for j in 0..pivot_idx {
-        // For descending order: larger depths go first
-        if triangles[j].avg_depth >= pivot_depth {
-            triangles.swap(i, j);
+        // For descending order: larger keys go first
+        if key(&items[j]) >= pivot_key {
+            items.swap(i, j);
             i += 1;
         }
     }
 
     // Place pivot in its final position
-    triangles.swap(i, pivot_idx);
+    items.swap(i, pivot_idx);
 
     i
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Face {
+        depth: f32,
+        id: usize,
+    }
+
+    /// Faces with several tied depths, ids in input order.
+    fn faces() -> Vec<Face> {
+        [2.0, 5.0, 2.0, 1.0, 5.0, 2.0, 3.0, 1.0, 5.0]
+            .iter()
+            .enumerate()
+            .map(|(id, &depth)| Face { depth, id })
+            .collect()
+    }
+
+    /// Descending by depth, ties by ascending id (input order).
+    fn expected() -> Vec<Face> {
+        let mut faces = faces();
+        faces.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+        faces
+    }
+
+    #[test]
+    fn stable_sort_keeps_tied_items_in_input_order() {
+        let mut faces = faces();
+        stable_sort_by_key_descending(&mut faces, |f| f.depth);
+        assert_eq!(faces, expected());
+    }
+
+    #[test]
+    fn bubble_sort_is_stable() {
+        let mut faces = faces();
+        bubble_sort_by_key_descending(&mut faces, |f| f.depth);
+        assert_eq!(faces, expected());
+    }
+
+    #[test]
+    fn quick_sort_orders_by_key() {
+        let mut faces = faces();
+        sort_by_key_descending(&mut faces, |f| f.depth);
+        let depths: Vec<f32> = faces.iter().map(|f| f.depth).collect();
+        let expected: Vec<f32> = expected().iter().map(|f| f.depth).collect();
+        assert_eq!(depths, expected);
+    }
+
+    #[test]
+    fn any_key_can_be_sorted() {
+        let mut faces = faces();
+        stable_sort_by_key_descending(&mut faces, |f| f.id);
+        let ids: Vec<usize> = faces.iter().map(|f| f.id).collect();
+        assert_eq!(ids, (0..9).rev().collect::<Vec<_>>());
+    }
+}
@@ -1,23 +1,139 @@
+use std::fmt;
 use std::path::Path;
 
+use crate::render::Rect;
+
+/// Errors that can occur when creating a [`Texture`].
+#[derive(Debug)]
+pub enum TextureError {
+    /// The image file could not be opened or decoded (missing file, unsupported format, ...).
+    Image(image::ImageError),
+    /// The pixel buffer length does not match `width * height * 4`, or a dimension is zero.
+    InvalidDimensions { width: u32, height: u32, len: usize },
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextureError::Image(e) => write!(f, "failed to load texture: {}", e),
+            TextureError::InvalidDimensions { width, height, len } => write!(
+                f,
+                "{} bytes do not form a {}x{} RGBA image",
+                len, width, height
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TextureError::Image(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<image::ImageError> for TextureError {
+    fn from(e: image::ImageError) -> Self {
+        TextureError::Image(e)
+    }
+}
+
+/// How UV coordinates outside [0, 1] are mapped back onto the texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    /// Pin to the edge texel.
+    Clamp,
+    /// Tile the texture: 1.5 samples the same texel as 0.5.
+    #[default]
+    Repeat,
+    /// Tile the texture, flipping every other copy: 1.25 samples the same texel as 0.75.
+    Mirror,
+}
+
+impl WrapMode {
+    /// Map an integer texel index into [0, size).
+    #[inline]
+    fn apply_index(self, i: i32, size: u32) -> u32 {
+        let size = size as i32;
+        let i = match self {
+            WrapMode::Clamp => i.clamp(0, size - 1),
+            WrapMode::Repeat => i.rem_euclid(size),
+            WrapMode::Mirror => {
+                let i = i.rem_euclid(2 * size);
+                if i >= size {
+                    2 * size - 1 - i
+                } else {
+                    i
+                }
+            }
+        };
+        i as u32
+    }
+
+    /// Map a texture coordinate into [0, 1].
+    #[inline]
+    fn apply(self, t: f32) -> f32 {
+        match self {
+            WrapMode::Clamp => t.clamp(0.0, 1.0),
+            // rem_euclid handles negative values correctly, unlike the % operator
+            WrapMode::Repeat => t.rem_euclid(1.0),
+            WrapMode::Mirror => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 {
+                    2.0 - t
+                } else {
+                    t
+                }
+            }
+        }
+    }
+}
+
+/// How texels are combined when sampling between texel centers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilterMode {
+    /// Use the single closest texel. Fastest, but blocky when magnified.
+    #[default]
+    Nearest,
+    /// Blend the four surrounding texels weighted by distance.
+    Bilinear,
+}
+
 /// Represents a 2D texture for texture mapping.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Texture {
-    data: Vec<u32>, // The pixel data of the texture in ARGB format.
-    width: u32,     // The width of the texture in pixels.
-    height: u32,    // The height of the texture in pixels.
+    data: Vec<u32>,      // The pixel data of the texture in ARGB format.
+    width: u32,          // The width of the texture in pixels.
+    height: u32,         // The height of the texture in pixels.
+    wrap_mode: WrapMode, // How out-of-range UVs are handled.
+    filter_mode: FilterMode,
 }
 
 impl Texture {
     // Load a texture from an image file (PNG, JPG, etc.)
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, image::ImageError> {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, TextureError> {
         let img = image::open(path)?.to_rgba8();
         let (width, height) = img.dimensions();
+        Self::from_rgba(img.as_raw(), width, height)
+    }
+
+    /// Create a texture from tightly packed RGBA8 bytes, rows top to bottom.
+    pub fn from_rgba(bytes: &[u8], width: u32, height: u32) -> Result<Self, TextureError> {
+        if width == 0 || height == 0 || bytes.len() != width as usize * height as usize * 4 {
+            return Err(TextureError::InvalidDimensions {
+                width,
+                height,
+                len: bytes.len(),
+            });
+        }
 
         // Convert RGBA bytes to ARGB u32
-        let data: Vec<u32> = img
-            .pixels()
+        let data: Vec<u32> = bytes
+            .chunks_exact(4)
             .map(|p| {
-                let [r, g, b, a] = p.0;
+                let [r, g, b, a] = [p[0], p[1], p[2], p[3]];
                 ((a as u32) << 24) | ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
             })
             .collect();
@@ -26,10 +142,44 @@ impl Texture {
             data,
             width,
             height,
+            wrap_mode: WrapMode::default(),
+            filter_mode: FilterMode::default(),
         })
     }
 
-    /// Sample the texture at UV coordinates using nearest-neighbor filtering.
+    /// A `size` x `size` texture filled with one ARGB color.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is zero.
+    pub fn solid(size: u32, color: u32) -> Self {
+        Self::checkerboard(size, color, color, 1)
+    }
+
+    /// A `size` x `size` checkerboard of `cells` x `cells` squares, starting
+    /// with `color_a` in the top-left. Handy for checking UV mapping by eye.
+    ///
+    /// # Panics
+    ///
+    /// If `size` or `cells` is zero.
+    pub fn checkerboard(size: u32, color_a: u32, color_b: u32, cells: u32) -> Self {
+        assert!(size > 0 && cells > 0, "checkerboard needs a size and cells");
+        let cell = |i: u32| i * cells / size;
+        let data = (0..size)
+            .flat_map(|y| (0..size).map(move |x| (cell(x) + cell(y)) % 2))
+            .map(|parity| if parity == 0 { color_a } else { color_b })
+            .collect();
+
+        Self {
+            data,
+            width: size,
+            height: size,
+            wrap_mode: WrapMode::default(),
+            filter_mode: FilterMode::default(),
+        }
+    }
+
+    /// Sample the texture at UV coordinates using the texture's [`FilterMode`].
     ///
     /// # UV Coordinate Convention
     /// - UV coordinates are in [0,1] range
@@ -37,24 +187,100 @@ impl Texture {
     /// - We flip V to correct for this: v_corrected = 1.0 - v
     ///
     /// # Wrapping
-    /// Uses repeat/wrap mode via rem_euclid for UVs outside [0,1]
+    /// UVs outside [0,1] are mapped back according to the texture's [`WrapMode`].
     #[inline]
     pub fn sample(&self, u: f32, v: f32) -> u32 {
-        // Wrap UV coordinates to [0, 1) range using rem_euclid
-        // (handles negative values correctly, unlike % operator)
-        let u = u.rem_euclid(1.0);
+        self.sample_within(u, v, Rect::new(0, 0, self.width, self.height))
+    }
+
+    /// Sample a pixel rectangle of the texture as if it were a whole texture.
+    ///
+    /// UVs in [0, 1] span `region` (top-left origin, like the pixels), so sprite
+    /// sheets and atlases can be drawn without slicing them. Wrapping and
+    /// bilinear neighbors stay inside the region.
+    ///
+    /// `region` is clamped to the texture; one that doesn't overlap it at all
+    /// samples as transparent black (`0x00000000`).
+    pub fn sample_region(&self, u: f32, v: f32, region: Rect) -> u32 {
+        match region.intersect(Rect::new(0, 0, self.width, self.height)) {
+            Some(region) => self.sample_within(u, v, region),
+            None => 0,
+        }
+    }
+
+    /// Sample with UVs spanning `region`, which must lie within the texture.
+    #[inline]
+    fn sample_within(&self, u: f32, v: f32, region: Rect) -> u32 {
+        let u = self.wrap_mode.apply(u);
 
         // Flip V: OBJ uses bottom-left origin, textures use top-left
-        let v = (1.0 - v).rem_euclid(1.0);
+        let v = 1.0 - self.wrap_mode.apply(v);
+
+        match self.filter_mode {
+            FilterMode::Nearest => {
+                // Convert normalized [0,1] UV to pixel coordinates [0, width-1]
+                let x = ((u * region.width as f32) as u32).min(region.width - 1);
+                let y = ((v * region.height as f32) as u32).min(region.height - 1);
+                self.texel(region.x as u32 + x, region.y as u32 + y)
+            }
+            FilterMode::Bilinear => self.sample_bilinear(u, v, region),
+        }
+    }
 
-        // Convert normalized [0,1) UV to pixel coordinates [0, width-1]
-        let x = ((u * self.width as f32) as u32).min(self.width - 1);
-        let y = ((v * self.height as f32) as u32).min(self.height - 1);
+    /// Blend the four texels around (u, v), with v already flipped to top-left origin.
+    ///
+    /// Texel centers sit at half-integer pixel coordinates, so shift by 0.5 to find
+    /// the top-left texel of the 2x2 block and the fractional weights within it.
+    fn sample_bilinear(&self, u: f32, v: f32, region: Rect) -> u32 {
+        let x = u * region.width as f32 - 0.5;
+        let y = v * region.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+
+        // Neighbors past the region's edge follow the wrap mode
+        let wrap_x = |i: i32| region.x as u32 + self.wrap_mode.apply_index(i, region.width);
+        let wrap_y = |i: i32| region.y as u32 + self.wrap_mode.apply_index(i, region.height);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let corners = [
+            (self.texel(wrap_x(x0), wrap_y(y0)), (1.0 - fx) * (1.0 - fy)),
+            (self.texel(wrap_x(x0 + 1), wrap_y(y0)), fx * (1.0 - fy)),
+            (self.texel(wrap_x(x0), wrap_y(y0 + 1)), (1.0 - fx) * fy),
+            (self.texel(wrap_x(x0 + 1), wrap_y(y0 + 1)), fx * fy),
+        ];
+
+        // Blend each 8-bit channel (A, R, G, B) independently
+        [24, 16, 8, 0].iter().fold(0u32, |color, &shift| {
+            let channel: f32 = corners
+                .iter()
+                .map(|&(texel, weight)| ((texel >> shift) & 0xFF) as f32 * weight)
+                .sum();
+            color | ((channel.round() as u32).min(255) << shift)
+        })
+    }
 
+    /// Fetch the texel at pixel coordinates (x, y), top-left origin.
+    #[inline]
+    fn texel(&self, x: u32, y: u32) -> u32 {
         // Sample from flat array: index = y * width + x
         self.data[(y * self.width + x) as usize]
     }
 
+    pub fn set_filter_mode(&mut self, mode: FilterMode) {
+        self.filter_mode = mode;
+    }
+
+    pub fn filter_mode(&self) -> FilterMode {
+        self.filter_mode
+    }
+
+    pub fn set_wrap_mode(&mut self, mode: WrapMode) {
+        self.wrap_mode = mode;
+    }
+
+    pub fn wrap_mode(&self) -> WrapMode {
+        self.wrap_mode
+    }
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -62,3 +288,149 @@ impl Texture {
         self.height
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_checkerboard_png() {
+        // Top row: red, green. Bottom row: blue, white.
+        let pixels: [[u8; 4]; 4] = [
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [255, 255, 255, 255],
+        ];
+        // Unique per process, so concurrent test runs don't share the file
+        let path =
+            std::env::temp_dir().join(format!("russsty_checkerboard_{}.png", std::process::id()));
+        image::save_buffer(&path, pixels.as_flattened(), 2, 2, image::ColorType::Rgba8).unwrap();
+
+        let texture = Texture::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        let texture = texture.unwrap();
+        assert_eq!((texture.width(), texture.height()), (2, 2));
+        // V points up, so v = 0.75 samples the top row
+        assert_eq!(texture.sample(0.25, 0.75), 0xFFFF0000);
+        assert_eq!(texture.sample(0.75, 0.75), 0xFF00FF00);
+        assert_eq!(texture.sample(0.25, 0.25), 0xFF0000FF);
+        assert_eq!(texture.sample(0.75, 0.25), 0xFFFFFFFF);
+    }
+
+    /// A 4x1 strip whose texel at column x has blue channel x.
+    fn strip() -> Texture {
+        let bytes: Vec<u8> = (0..4).flat_map(|x| [0, 0, x, 255]).collect();
+        Texture::from_rgba(&bytes, 4, 1).unwrap()
+    }
+
+    /// Column index of the texel sampled at `u`.
+    fn column(texture: &Texture, u: f32) -> u32 {
+        texture.sample(u, 0.5) & 0xFF
+    }
+
+    #[test]
+    fn repeat_wraps_out_of_range_uvs() {
+        let texture = strip();
+        assert_eq!(column(&texture, 1.5), column(&texture, 0.5));
+        assert_eq!(column(&texture, 1.5), 2);
+        assert_eq!(column(&texture, -0.125), 3);
+    }
+
+    #[test]
+    fn mirror_reflects_out_of_range_uvs() {
+        let mut texture = strip();
+        texture.set_wrap_mode(WrapMode::Mirror);
+        assert_eq!(column(&texture, 1.125), 3);
+        assert_eq!(column(&texture, 1.875), 0);
+        assert_eq!(column(&texture, -0.125), 0);
+    }
+
+    #[test]
+    fn clamp_pins_to_edge_texels() {
+        let mut texture = strip();
+        texture.set_wrap_mode(WrapMode::Clamp);
+        assert_eq!(column(&texture, 1.5), 3);
+        assert_eq!(column(&texture, -0.5), 0);
+    }
+
+    #[test]
+    fn bilinear_center_averages_four_texels() {
+        let bytes = [
+            [0, 100, 0, 255],
+            [40, 0, 0, 255],
+            [80, 0, 0, 255],
+            [120, 0, 200, 255],
+        ];
+        let mut texture = Texture::from_rgba(bytes.as_flattened(), 2, 2).unwrap();
+        texture.set_filter_mode(FilterMode::Bilinear);
+        assert_eq!(texture.sample(0.5, 0.5), 0xFF3C1932);
+    }
+
+    #[test]
+    fn bilinear_at_texel_center_returns_that_texel() {
+        let mut texture = strip();
+        texture.set_filter_mode(FilterMode::Bilinear);
+        assert_eq!(texture.sample(0.625, 0.5), 0xFF000002);
+    }
+
+    #[test]
+    fn sample_region_maps_uvs_into_the_rectangle() {
+        // 4x4 texture with a different color in each 2x2 quadrant
+        let quadrant = |x: u32, y: u32| match (x < 2, y < 2) {
+            (true, true) => [255, 0, 0, 255],
+            (false, true) => [0, 255, 0, 255],
+            (true, false) => [0, 0, 255, 255],
+            (false, false) => [255, 255, 255, 255],
+        };
+        let bytes: Vec<u8> = (0..4)
+            .flat_map(|y| (0..4).flat_map(move |x| quadrant(x, y)))
+            .collect();
+        let mut texture = Texture::from_rgba(&bytes, 4, 4).unwrap();
+        let bottom_right = Rect::new(2, 2, 2, 2);
+
+        for filter in [FilterMode::Nearest, FilterMode::Bilinear] {
+            texture.set_filter_mode(filter);
+            for (u, v) in [(0.0, 0.0), (0.5, 0.5), (1.0, 1.0), (1.5, -0.25)] {
+                assert_eq!(texture.sample_region(u, v, bottom_right), 0xFFFFFFFF);
+            }
+        }
+
+        // Regions are clamped to the texture, and empty ones sample as transparent
+        let overhanging = Rect::new(2, 2, 8, 8);
+        assert_eq!(texture.sample_region(0.5, 0.5, overhanging), 0xFFFFFFFF);
+        assert_eq!(texture.sample_region(0.5, 0.5, Rect::new(4, 0, 2, 2)), 0);
+        assert_eq!(texture.sample_region(0.5, 0.5, Rect::new(0, 0, 0, 0)), 0);
+    }
+
+    #[test]
+    fn checkerboard_alternates_between_quadrants() {
+        let (a, b) = (0xFF112233, 0xFFCCDDEE);
+        let texture = Texture::checkerboard(8, a, b, 2);
+        assert_eq!((texture.width(), texture.height()), (8, 8));
+        // V points up, so v = 0.75 samples the top row of cells
+        assert_eq!(texture.sample(0.25, 0.75), a);
+        assert_eq!(texture.sample(0.75, 0.75), b);
+        assert_eq!(texture.sample(0.25, 0.25), b);
+        assert_eq!(texture.sample(0.75, 0.25), a);
+
+        let solid = Texture::solid(4, a);
+        assert!(solid.data.iter().all(|&texel| texel == a));
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(matches!(
+            Texture::from_file("does/not/exist.png"),
+            Err(TextureError::Image(_))
+        ));
+    }
+
+    #[test]
+    fn from_rgba_rejects_mismatched_length() {
+        assert!(matches!(
+            Texture::from_rgba(&[0; 12], 2, 2),
+            Err(TextureError::InvalidDimensions { .. })
+        ));
+    }
+}
@@ -18,10 +18,11 @@
 //!
 //! Call `release_mouse()` to restore normal mouse behavior.
 
+use std::collections::HashSet;
 use std::time::Instant;
 
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use sdl2::keyboard::{Keycode, Scancode};
 use sdl2::pixels::PixelFormatEnum;
 use sdl2::rect::Rect;
 
@@ -29,6 +30,8 @@ pub const WINDOW_WIDTH: u32 = 800;
 pub const WINDOW_HEIGHT: u32 = 600;
 pub const FPS: u64 = 60;
 pub const FRAME_TARGET_TIME: f64 = 1000.0 / FPS as f64;
+/// Most fixed updates [`FrameLimiter::fixed_steps`] will ask for in one frame.
+pub const MAX_FIXED_STEPS: u32 = 8;
 
 // =============================================================================
 // Window Events (Discrete)
@@ -43,24 +46,82 @@ pub enum WindowEvent {
     KeyPress(Key),
 }
 
-/// Keys that trigger discrete events.
+/// Keyboard keys reported by [`WindowEvent::KeyPress`] and [`Window::is_key_down`].
 ///
-/// These are for one-shot actions (toggle modes, etc).
-/// For continuous input (movement), use [`InputState`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// `KeyPress` is for one-shot actions (toggle modes, etc).
+/// For continuous input (movement), use [`InputState`] or [`Window::is_key_down`].
+/// The movement keys (W/A/S/D, Q/E, Space, Left Shift and Left Ctrl) are only
+/// tracked as held state and never reported as `KeyPress`, so holding them
+/// can't crowd out a toggle pressed in the same frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Key {
     Num1,
     Num2,
     Num3,
     Num4,
     Num5,
+    A,
+    B,
     C,
+    D,
+    E,
+    F,
     G,
+    H,
     M,
+    Q,
     R,
-    F,
+    S,
     T,
+    W,
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    LShift,
+    RShift,
     Escape,
+    /// A key without a name yet, carrying its raw SDL scancode
+    Other(u32),
+}
+
+/// Maps an SDL key event to a [`Key`], falling back to the raw scancode.
+fn map_key(keycode: Option<Keycode>, scancode: Option<Scancode>) -> Option<Key> {
+    let Some(keycode) = keycode else {
+        return scancode.map(|scancode| Key::Other(scancode as u32));
+    };
+    let key = match keycode {
+        Keycode::Num1 => Key::Num1,
+        Keycode::Num2 => Key::Num2,
+        Keycode::Num3 => Key::Num3,
+        Keycode::Num4 => Key::Num4,
+        Keycode::Num5 => Key::Num5,
+        Keycode::A => Key::A,
+        Keycode::B => Key::B,
+        Keycode::C => Key::C,
+        Keycode::D => Key::D,
+        Keycode::E => Key::E,
+        Keycode::F => Key::F,
+        Keycode::G => Key::G,
+        Keycode::H => Key::H,
+        Keycode::M => Key::M,
+        Keycode::Q => Key::Q,
+        Keycode::R => Key::R,
+        Keycode::S => Key::S,
+        Keycode::T => Key::T,
+        Keycode::W => Key::W,
+        Keycode::Up => Key::Up,
+        Keycode::Down => Key::Down,
+        Keycode::Left => Key::Left,
+        Keycode::Right => Key::Right,
+        Keycode::Space => Key::Space,
+        Keycode::LShift => Key::LShift,
+        Keycode::RShift => Key::RShift,
+        Keycode::Escape => Key::Escape,
+        _ => Key::Other(scancode? as u32),
+    };
+    Some(key)
 }
 
 // =============================================================================
@@ -108,9 +169,26 @@ pub struct InputState {
     /// Relative mouse movement this frame (dx, dy).
     /// Only populated when mouse is captured.
     pub mouse_delta: (i32, i32),
+
+    /// Keys currently held down, persisting across frames until released.
+    held_keys: HashSet<Key>,
 }
 
 impl InputState {
+    /// Returns whether `key` is currently held down.
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.held_keys.contains(&key)
+    }
+
+    /// Records a key-down (`pressed`) or key-up event.
+    fn set_key_down(&mut self, key: Key, pressed: bool) {
+        if pressed {
+            self.held_keys.insert(key);
+        } else {
+            self.held_keys.remove(&key);
+        }
+    }
+
     /// Resets per-frame state (mouse delta).
     ///
     /// Called at the start of each frame before processing events.
@@ -123,14 +201,31 @@ impl InputState {
 // Frame Timing
 // =============================================================================
 
+/// Caps the frame rate and measures frame time.
+///
+/// Besides the variable per-frame delta, it accumulates elapsed time for
+/// fixed-timestep updates, which keep simulation speed independent of the
+/// frame rate:
+///
+/// ```ignore
+/// const DT: f32 = 1.0 / 120.0;
+/// frame_limiter.wait_and_get_delta(&window);
+/// for _ in 0..frame_limiter.fixed_steps(DT) {
+///     simulate(DT);
+/// }
+/// let alpha = frame_limiter.step_alpha(DT); // Blend previous and current state
+/// ```
 pub struct FrameLimiter {
     previous_frame_time: u64,
+    /// Elapsed seconds not yet consumed by fixed steps.
+    accumulator: f32,
 }
 
 impl FrameLimiter {
     pub fn new(window: &Window) -> Self {
         Self {
             previous_frame_time: window.timer().ticks64(),
+            accumulator: 0.0,
         }
     }
 
@@ -148,8 +243,33 @@ impl FrameLimiter {
         }
 
         self.previous_frame_time = current_time;
+        self.accumulator += delta_time as f32 / 1000.0;
         delta_time
     }
+
+    /// Returns how many fixed updates of `dt` seconds to run this frame.
+    ///
+    /// Consumes whole steps from the time accumulated by `wait_and_get_delta`.
+    /// If updates can't keep up (e.g. after a stall), every frame would owe more
+    /// steps than the last, the "spiral of death". To avoid it, at most
+    /// [`MAX_FIXED_STEPS`] are returned and the remaining backlog is dropped, so
+    /// the simulation slows down instead of freezing.
+    pub fn fixed_steps(&mut self, dt: f32) -> u32 {
+        let steps = (self.accumulator / dt) as u32;
+        if steps > MAX_FIXED_STEPS {
+            self.accumulator = 0.0;
+            return MAX_FIXED_STEPS;
+        }
+        self.accumulator -= steps as f32 * dt;
+        steps
+    }
+
+    /// Fraction of a fixed step left over after `fixed_steps`, in `[0, 1)`.
+    ///
+    /// Use it to interpolate between the last two simulated states when rendering.
+    pub fn step_alpha(&self, dt: f32) -> f32 {
+        (self.accumulator / dt).clamp(0.0, 1.0)
+    }
 }
 
 /// Tracks frames per second with once-per-second updates.
@@ -284,15 +404,18 @@ impl Window {
 
                 // Key down - update continuous state and check for discrete events
                 Event::KeyDown {
-                    keycode: Some(keycode),
+                    keycode,
+                    scancode,
                     repeat: false,
                     ..
                 } => {
-                    self.update_key_state(keycode, true);
+                    let movement =
+                        keycode.is_some_and(|keycode| self.update_key_state(keycode, true));
+                    if let Some(key) = map_key(keycode, scancode) {
+                        self.input_state.set_key_down(key, true);
 
-                    // Check for discrete key events (only if we haven't already got one)
-                    if result == WindowEvent::None {
-                        if let Some(key) = self.keycode_to_discrete_key(keycode) {
+                        // Only report the first key press of the frame
+                        if !movement && result == WindowEvent::None {
                             result = WindowEvent::KeyPress(key);
                         }
                     }
@@ -300,10 +423,14 @@ impl Window {
 
                 // Key up - update continuous state only
                 Event::KeyUp {
-                    keycode: Some(keycode),
-                    ..
+                    keycode, scancode, ..
                 } => {
-                    self.update_key_state(keycode, false);
+                    if let Some(keycode) = keycode {
+                        self.update_key_state(keycode, false);
+                    }
+                    if let Some(key) = map_key(keycode, scancode) {
+                        self.input_state.set_key_down(key, false);
+                    }
                 }
 
                 // Mouse motion - only track when captured
@@ -322,7 +449,9 @@ impl Window {
     }
 
     /// Updates continuous key state based on key press/release.
-    fn update_key_state(&mut self, keycode: Keycode, pressed: bool) {
+    ///
+    /// Returns whether `keycode` is a movement key.
+    fn update_key_state(&mut self, keycode: Keycode, pressed: bool) -> bool {
         match keycode {
             // Movement
             Keycode::W => self.input_state.forward = pressed,
@@ -336,27 +465,9 @@ impl Window {
             Keycode::Q => self.input_state.roll_left = pressed,
             Keycode::E => self.input_state.roll_right = pressed,
 
-            _ => {}
-        }
-    }
-
-    /// Maps SDL keycode to discrete key event (if applicable).
-    fn keycode_to_discrete_key(&self, keycode: Keycode) -> Option<Key> {
-        match keycode {
-            Keycode::Num1 => Some(Key::Num1),
-            Keycode::Num2 => Some(Key::Num2),
-            Keycode::Num3 => Some(Key::Num3),
-            Keycode::Num4 => Some(Key::Num4),
-            Keycode::Num5 => Some(Key::Num5),
-            Keycode::C => Some(Key::C),
-            Keycode::G => Some(Key::G),
-            Keycode::M => Some(Key::M),
-            Keycode::R => Some(Key::R),
-            Keycode::F => Some(Key::F),
-            Keycode::T => Some(Key::T),
-            Keycode::Escape => Some(Key::Escape),
-            _ => None,
+            _ => return false,
         }
+        true
     }
 
     // =========================================================================
@@ -370,6 +481,14 @@ impl Window {
         &self.input_state
     }
 
+    /// Returns whether `key` is currently held down.
+    ///
+    /// Unlike [`WindowEvent::KeyPress`], this stays true every frame until the
+    /// key is released, so it suits continuous actions like movement.
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.input_state.is_key_down(key)
+    }
+
     // =========================================================================
     // Mouse Capture
     // =========================================================================
@@ -442,7 +561,12 @@ impl Window {
         Ok(())
     }
 
+    /// Recreate the streaming texture for a new window size.
+    ///
+    /// SDL can't create empty textures, so zero dimensions (a minimized window)
+    /// are treated as `1`, matching [`crate::engine::Engine::resize`].
     pub fn resize(&mut self, width: u32, height: u32) -> Result<(), String> {
+        let (width, height) = (width.max(1), height.max(1));
         self.width = width;
         self.height = height;
         // SAFETY: Same as in new() - texture_creator outlives texture
@@ -474,3 +598,53 @@ impl Window {
         let _ = self.canvas.window_mut().set_title(title);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn held_keys_persist_until_released() {
+        let mut input = InputState::default();
+        assert!(!input.is_key_down(Key::R));
+
+        input.set_key_down(Key::R, true);
+        // Per-frame resets leave held keys alone
+        input.reset_per_frame();
+        assert!(input.is_key_down(Key::R));
+        assert!(!input.is_key_down(Key::C));
+
+        input.set_key_down(Key::R, false);
+        assert!(!input.is_key_down(Key::R));
+    }
+
+    #[test]
+    fn fixed_steps_are_capped_after_a_stall() {
+        let dt = 0.01;
+        let mut limiter = FrameLimiter {
+            previous_frame_time: 0,
+            accumulator: 0.035,
+        };
+        assert_eq!(limiter.fixed_steps(dt), 3);
+        assert!((limiter.step_alpha(dt) - 0.5).abs() < 1e-3);
+
+        // Ten seconds behind: run the cap and drop the backlog
+        limiter.accumulator = 10.0;
+        assert_eq!(limiter.fixed_steps(dt), MAX_FIXED_STEPS);
+        assert_eq!(limiter.fixed_steps(dt), 0);
+    }
+
+    #[test]
+    fn unnamed_keys_fall_back_to_their_scancode() {
+        assert_eq!(map_key(Some(Keycode::W), Some(Scancode::W)), Some(Key::W));
+        assert_eq!(
+            map_key(Some(Keycode::F5), Some(Scancode::F5)),
+            Some(Key::Other(Scancode::F5 as u32))
+        );
+        assert_eq!(
+            map_key(None, Some(Scancode::W)),
+            Some(Key::Other(Scancode::W as u32))
+        );
+        assert_eq!(map_key(None, None), None);
+    }
+}